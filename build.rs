@@ -1,13 +1,19 @@
+use std::env;
+use std::path::PathBuf;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Try to compile protobuf files for gRPC service
-    // If protoc is not available, skip compilation and use pre-generated files
-    match tonic_build::compile_protos("proto/search.proto") {
-        Ok(_) => println!("cargo:warning=Successfully compiled protobuf files"),
-        Err(e) => {
-            println!("cargo:warning=Failed to compile protobuf files: {}. Using pre-generated files.", e);
-            // In a real implementation, we would have pre-generated files
-            // For now, we'll create a simple stub
-        }
+    // Compile the protobuf service definition; the descriptor set backs server reflection
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+
+    // Use the vendored protoc unless one is set explicitly, so builds need no system install
+    if env::var_os("PROTOC").is_none() {
+        env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
     }
+
+    tonic_build::configure()
+        .file_descriptor_set_path(out_dir.join("search_descriptor.bin"))
+        .compile(&["proto/search.proto"], &["proto"])?;
+
+    println!("cargo:rerun-if-changed=proto/search.proto");
     Ok(())
-}
\ No newline at end of file
+}
@@ -23,6 +23,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         max_connections: 5,
         connection_timeout_secs: 5,
         default_ttl_secs: 3600,
+        vector_dimension: 384,
     };
 
     // Initialize cache manager
@@ -79,6 +80,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             post_id: "post_1".to_string(),
             title: "Introduction to Machine Learning".to_string(),
             snippet: "Machine learning is a subset of artificial intelligence...".to_string(),
+            highlights: Vec::new(),
             score: 0.95,
             meta: PostMetadata {
                 author_name: "Dr. Smith".to_string(),
@@ -86,6 +88,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 date: Utc::now(),
                 language: "en".to_string(),
                 frozen: false,
+                metadata: serde_json::Map::new(),
             },
             cached_at: Utc::now(),
         },
@@ -93,6 +96,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             post_id: "post_2".to_string(),
             title: "Advanced ML Algorithms".to_string(),
             snippet: "This post covers advanced machine learning algorithms...".to_string(),
+            highlights: Vec::new(),
             score: 0.87,
             meta: PostMetadata {
                 author_name: "Prof. Johnson".to_string(),
@@ -100,6 +104,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 date: Utc::now(),
                 language: "en".to_string(),
                 frozen: false,
+                metadata: serde_json::Map::new(),
             },
             cached_at: Utc::now(),
         },
@@ -130,6 +135,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         date: Utc::now(),
         language: "en".to_string(),
         frozen: false,
+        metadata: serde_json::Map::new(),
     };
 
     // Test cache miss
@@ -1,9 +1,7 @@
 use rag_search_api::{
-    grpc::{GrpcSearchService, GrpcSearchRequest, GrpcSearchFilters},
-    search::SearchService,
-    cache::CacheManager,
-    database::DatabaseManager,
-    ml::MLService,
+    grpc::{GrpcSearchService, GrpcSearchRequest, GrpcSearchFilters, proto::{PassageAggregation, RetrievalMode}},
+    collections::CollectionRegistry,
+    observability::MetricsRegistry,
     config::Config,
 };
 use std::sync::Arc;
@@ -28,46 +26,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize the services (in a real scenario, these would be properly configured)
     println!("📦 Initializing services...");
     
-    // Note: In this demo, we're creating mock services since we don't have actual Redis/Postgres
-    // In production, these would be real connections
-    let cache_manager = Arc::new(
-        CacheManager::new(config.redis.clone()).await
+    // Note: this demo needs reachable Redis and Postgres instances and the model files;
+    // every configured collection gets its own search, ingestion and GDPR services
+    let metrics = MetricsRegistry::new()?;
+    let collections = Arc::new(
+        CollectionRegistry::new(&config, &metrics).await
             .unwrap_or_else(|e| {
-                println!("⚠️  Cache manager initialization failed: {}", e);
-                println!("   Continuing with mock cache manager for demo");
-                // In a real implementation, we'd create a mock or fallback
-                panic!("Cache manager required for demo")
-            })
-    );
-
-    let database_manager = Arc::new(
-        DatabaseManager::new(config.database.clone()).await
-            .unwrap_or_else(|e| {
-                println!("⚠️  Database manager initialization failed: {}", e);
-                println!("   Continuing with mock database manager for demo");
-                panic!("Database manager required for demo")
-            })
-    );
-
-    let ml_service = Arc::new(
-        MLService::new().await
-            .unwrap_or_else(|e| {
-                println!("⚠️  ML service initialization failed: {}", e);
-                println!("   Continuing with mock ML service for demo");
-                panic!("ML service required for demo")
-            })
-    );
-
-    let search_service = Arc::new(
-        SearchService::new(cache_manager, database_manager, ml_service).await
-            .unwrap_or_else(|e| {
-                println!("❌ Search service initialization failed: {}", e);
-                panic!("Search service required for demo")
+                println!("❌ Service initialization failed: {}", e);
+                panic!("Redis, Postgres and the ML models are required for the demo")
             })
     );
 
     // Create gRPC service
-    let grpc_service = GrpcSearchService::new(search_service);
+    let grpc_service = GrpcSearchService::new(collections);
     println!("✅ gRPC service initialized");
 
     // Demo 1: Basic streaming search
@@ -80,6 +51,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         min_score: Some(0.7),
         rerank: false,
         filters: None,
+        mode: RetrievalMode::Vector as i32,
+        fusion_weight: None,
+        mmr_lambda: None,
+        passage_aggregation: PassageAggregation::None as i32,
+        passage_top_n: None,
+        cursor: None,
+        explain: false,
+        collection: None,
     };
 
     match grpc_service.semantic_search_stream(request).await {
@@ -132,7 +111,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         filters: Some(GrpcSearchFilters {
             language: Some("en".to_string()),
             frozen: Some(false),
+            ..Default::default()
         }),
+        mode: RetrievalMode::Vector as i32,
+        fusion_weight: None,
+        mmr_lambda: None,
+        passage_aggregation: PassageAggregation::None as i32,
+        passage_top_n: None,
+        cursor: None,
+        explain: false,
+        collection: None,
     };
 
     match grpc_service.semantic_search_stream(request_with_filters).await {
@@ -209,6 +197,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         min_score: Some(2.0), // Invalid score > 1.0
         rerank: false,
         filters: None,
+        mode: RetrievalMode::Vector as i32,
+        fusion_weight: None,
+        mmr_lambda: None,
+        passage_aggregation: PassageAggregation::None as i32,
+        passage_top_n: None,
+        cursor: None,
+        explain: false,
+        collection: None,
     };
 
     match grpc_service.semantic_search_stream(invalid_request).await {
@@ -94,7 +94,8 @@ async fn main() -> SearchResult<()> {
             frozen: false,
             date_gmt: Utc::now(),
             url: "https://example.com/rust-intro".to_string(),
-            embedding: vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8], // 8-dim for demo
+            embedding: vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8], // 8-dim for demo,
+            metadata: serde_json::Map::new(),
         },
         Post {
             id: Uuid::new_v4(),
@@ -106,7 +107,8 @@ async fn main() -> SearchResult<()> {
             frozen: false,
             date_gmt: Utc::now(),
             url: "https://example.com/vector-db".to_string(),
-            embedding: vec![0.8, 0.7, 0.6, 0.5, 0.4, 0.3, 0.2, 0.1], // Different embedding
+            embedding: vec![0.8, 0.7, 0.6, 0.5, 0.4, 0.3, 0.2, 0.1], // Different embedding,
+            metadata: serde_json::Map::new(),
         },
         Post {
             id: Uuid::new_v4(),
@@ -118,7 +120,8 @@ async fn main() -> SearchResult<()> {
             frozen: false,
             date_gmt: Utc::now(),
             url: "https://example.com/ml-rust".to_string(),
-            embedding: vec![0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9], // Another embedding
+            embedding: vec![0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9], // Another embedding,
+            metadata: serde_json::Map::new(),
         },
     ];
 
@@ -168,7 +171,7 @@ async fn main() -> SearchResult<()> {
     // Test vector search
     println!("\n🔍 Testing Vector Search");
     let query_embedding = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8]; // Similar to first post
-    match db_manager.vector_search(&query_embedding, 10).await {
+    match db_manager.vector_search(&query_embedding, 10, None).await {
         Ok(candidates) => {
             println!("✅ Vector search completed, found {} candidates", candidates.len());
             for (i, candidate) in candidates.iter().enumerate() {
@@ -205,7 +208,7 @@ async fn main() -> SearchResult<()> {
     println!("\n🗑️  Testing GDPR Data Deletion");
     for post in &test_posts {
         match db_manager.delete_post(&post.post_id).await {
-            Ok(()) => println!("✅ Deleted post: {}", post.post_id),
+            Ok(_) => println!("✅ Deleted post: {}", post.post_id),
             Err(e) => println!("❌ Failed to delete post {}: {}", post.post_id, e),
         }
     }
@@ -102,6 +102,7 @@ async fn main() -> SearchResult<()> {
             post_id: "post_1".to_string(),
             title: "First Post".to_string(),
             snippet: "This is the first post snippet".to_string(),
+            highlights: Vec::new(),
             score: 0.95,
             meta: PostMetadata {
                 author_name: "Author One".to_string(),
@@ -109,6 +110,7 @@ async fn main() -> SearchResult<()> {
                 date: Utc::now(),
                 language: "en".to_string(),
                 frozen: false,
+                metadata: serde_json::Map::new(),
             },
             cached_at: Utc::now(),
         },
@@ -116,6 +118,7 @@ async fn main() -> SearchResult<()> {
             post_id: "post_2".to_string(),
             title: "Second Post".to_string(),
             snippet: "This is the second post snippet".to_string(),
+            highlights: Vec::new(),
             score: 0.87,
             meta: PostMetadata {
                 author_name: "Author Two".to_string(),
@@ -123,6 +126,7 @@ async fn main() -> SearchResult<()> {
                 date: Utc::now(),
                 language: "en".to_string(),
                 frozen: false,
+                metadata: serde_json::Map::new(),
             },
             cached_at: Utc::now(),
         },
@@ -157,7 +161,7 @@ async fn main() -> SearchResult<()> {
     // Test vector search (placeholder)
     println!("\n🔍 Testing Vector Search (Placeholder)");
     let query_embedding = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
-    match cache_manager.vector_search(&query_embedding, 10).await {
+    match cache_manager.vector_search(&query_embedding, 10, None).await {
         Ok(candidates) => {
             println!("✅ Vector search completed, found {} candidates", candidates.len());
             if candidates.is_empty() {
@@ -8,13 +8,20 @@
 /// - **Purpose**: Store post embeddings to avoid recomputation
 /// - **Key Pattern**: `search:vec:<post_id>`
 /// - **TTL**: Permanent (LRU eviction when memory limit reached)
-/// - **Data**: 384-dimensional f32 vectors stored as binary data
+/// - **Data**: HASH with the FLOAT32 `embedding` sized to the active model (384 for MiniLM)
+///   plus `language` and `frozen` tag fields
+/// - **Search**: RediSearch HNSW index (`FT.CREATE`, created on connect) queried with
+///   `FT.SEARCH ... KNN` and a language/frozen pre-filter
+/// - **Passages**: `search:chunk:<post_id>:<chunk_index>` hashes hold passage embeddings
+///   with the same pre-filter fields plus `start`/`end` offsets, under a separate HNSW index
 /// 
 /// ### 2. Top-K Cache (60s TTL)
 /// - **Purpose**: Cache complete search results for identical queries
 /// - **Key Pattern**: `search:topk:<query_hash>` (farmhash64 of normalized query)
 /// - **TTL**: 60 seconds
 /// - **Data**: Serialized JSON array of CachedResult structs
+/// - **Invalidation**: Dropped wholesale when posts are ingested; entries containing a
+///   post are tracked in `search:topk:post:<post_id>` sets and purged on erasure
 /// 
 /// ### 3. Metadata Cache (24h TTL)
 /// - **Purpose**: Cache post metadata to avoid database lookups
@@ -22,6 +29,12 @@
 /// - **TTL**: 24 hours
 /// - **Data**: Serialized JSON PostMetadata structs
 /// 
+/// ## Collections
+/// 
+/// The patterns above are those of the default collection. A named collection
+/// prefixes every key and index with `<name>:` (`docs:search:vec:<post_id>`,
+/// `idx:docs:search:vec:v2:<dim>`), so collections never share cached data.
+/// 
 /// ## Cache Statistics and Monitoring
 /// 
 /// The cache system provides comprehensive hit/miss tracking:
@@ -34,6 +47,7 @@
 /// 
 /// The cache supports GDPR "right to be forgotten" through:
 /// - `invalidate_post_data()` method for complete data deletion
+/// - `purge_top_k_for_post()` to drop cached result lists that contain the post
 /// - Audit logging of deletion operations
 /// - Non-blocking UNLINK operations for performance
 /// 
@@ -58,14 +72,15 @@ mod tests;
 
 use crate::config::RedisConfig;
 use crate::error::{SearchError, SearchResult};
-use crate::types::{CachedResult, PostMetadata, SearchCandidate};
+use crate::types::{CachedResult, ChunkCandidate, PostChunk, PostMetadata, SearchCandidate, SearchFilters};
 use chrono::{DateTime, Utc};
 use farmhash;
 use redis_client::RedisClient;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info};
 
-pub use redis_client::{RedisStats, CacheStats};
+pub use redis_client::{RedisStats, CacheStats, cosine_similarity, prefilter_is_exact};
 
 /// Cache manager for the three-tier caching strategy
 pub struct CacheManager {
@@ -90,6 +105,17 @@ impl CacheManager {
         })
     }
 
+    /// Cache manager for a named collection, sharing this manager's Redis connections
+    ///
+    /// The collection's vector indexes are created for `dimension` when missing.
+    pub async fn for_collection(&self, name: &str, dimension: usize) -> Self {
+        info!("Initializing cache manager for collection {}", name);
+
+        CacheManager {
+            redis_client: Arc::new(self.redis_client.for_collection(name, dimension).await),
+        }
+    }
+
     /// Get cached search results by query hash
     pub async fn get_top_k_cache(&self, query_hash: u64) -> SearchResult<Option<Vec<CachedResult>>> {
         self.redis_client.get_top_k_cache(query_hash).await
@@ -109,11 +135,36 @@ impl CacheManager {
         self.redis_client.get_vector(post_id).await
     }
 
+    /// Get the vector embeddings of several posts from cache in one round trip
+    pub async fn get_vector_caches(&self, post_ids: &[String]) -> SearchResult<HashMap<String, Vec<f32>>> {
+        self.redis_client.get_vectors(post_ids).await
+    }
+
     /// Store vector embedding in cache
     pub async fn set_vector_cache(&self, post_id: &str, embedding: &[f32]) -> SearchResult<()> {
         self.redis_client.set_vector(post_id, embedding).await
     }
 
+    /// Store vector embedding with the metadata fields used by KNN pre-filtering
+    pub async fn set_indexed_vector(
+        &self,
+        post_id: &str,
+        embedding: &[f32],
+        metadata: &PostMetadata,
+    ) -> SearchResult<()> {
+        self.redis_client.set_vector_with_metadata(post_id, embedding, metadata).await
+    }
+
+    /// Replace the indexed passages of a post
+    pub async fn set_post_chunks(
+        &self,
+        post_id: &str,
+        chunks: &[PostChunk],
+        metadata: &PostMetadata,
+    ) -> SearchResult<()> {
+        self.redis_client.set_post_chunks(post_id, chunks, metadata).await
+    }
+
     /// Get post metadata from cache
     pub async fn get_metadata_cache(&self, post_id: &str) -> SearchResult<Option<PostMetadata>> {
         self.redis_client.get_metadata_cache(post_id).await
@@ -142,16 +193,44 @@ impl CacheManager {
         farmhash::hash64(normalized.as_bytes())
     }
 
-    /// Perform vector similarity search using Redis
-    pub async fn vector_search(&self, query_embedding: &[f32], limit: usize) -> SearchResult<Vec<SearchCandidate>> {
-        self.redis_client.vector_search(query_embedding, limit).await
+    /// Perform vector similarity search using the Redis HNSW index
+    ///
+    /// Filters are applied as a KNN pre-filter; without a frozen filter frozen
+    /// posts are excluded.
+    pub async fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<SearchCandidate>> {
+        self.redis_client.vector_search(query_embedding, limit, filters).await
     }
 
-    /// Invalidate cache entries for GDPR compliance
-    pub async fn invalidate_post_data(&self, post_id: &str) -> SearchResult<()> {
+    /// Perform passage similarity search using the Redis passage index
+    pub async fn chunk_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<ChunkCandidate>> {
+        self.redis_client.chunk_search(query_embedding, limit, filters).await
+    }
+
+    /// Drop all cached top-k results after the indexed posts change
+    pub async fn invalidate_top_k_cache(&self) -> SearchResult<u64> {
+        self.redis_client.invalidate_top_k_cache().await
+    }
+
+    /// Invalidate cache entries for GDPR compliance, returning the number of keys removed
+    pub async fn invalidate_post_data(&self, post_id: &str) -> SearchResult<u64> {
         self.redis_client.delete_post_data(post_id).await
     }
 
+    /// Purge the top-k entries whose results contain the given post
+    pub async fn purge_top_k_for_post(&self, post_id: &str) -> SearchResult<u64> {
+        self.redis_client.purge_top_k_for_post(post_id).await
+    }
+
     /// Get Redis connection statistics
     pub async fn get_redis_stats(&self) -> SearchResult<RedisStats> {
         self.redis_client.get_stats().await
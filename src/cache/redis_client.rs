@@ -1,24 +1,138 @@
 use crate::config::RedisConfig;
 use crate::error::{SearchError, SearchResult};
-use crate::types::{CachedResult, PostMetadata, SearchCandidate, SearchSource};
+use crate::types::{
+    url_host, CachedResult, ChunkCandidate, PassageSpan, PostChunk, PostMetadata, SearchCandidate, SearchFilters,
+    SearchSource, DEFAULT_COLLECTION, MAX_CHUNKS_PER_POST,
+};
+use chrono::{DateTime, Utc};
 use fred::{
     clients::RedisPool,
-    interfaces::{ClientLike, KeysInterface},
-    types::{Builder, Expiration, RedisConfig as FredRedisConfig, InfoKind},
+    interfaces::{ClientLike, HashesInterface, KeysInterface, SetsInterface},
+    types::{
+        Builder, ClusterHash, CustomCommand, Expiration, InfoKind, RedisConfig as FredRedisConfig,
+        RedisValue, Scanner,
+    },
 };
+use futures::StreamExt;
 use serde_json;
+use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
 
+/// Keys and index names of one collection
+///
+/// The default collection keeps the original `search:` keys; a named
+/// collection prefixes every key and index with `<name>:`, so its vector
+/// indexes never cover another collection's hashes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keyspace {
+    /// Prefix shared by every key of the collection
+    prefix: String,
+}
+
+impl Keyspace {
+    /// Keyspace of the named collection
+    pub fn for_collection(name: &str) -> Self {
+        if name == DEFAULT_COLLECTION {
+            return Self::default();
+        }
+
+        Self {
+            prefix: format!("{}:search:", name),
+        }
+    }
+
+    /// Key prefix of the vector hashes covered by the RediSearch index
+    fn vector_prefix(&self) -> String {
+        format!("{}vec:", self.prefix)
+    }
+
+    /// Key prefix of the passage hashes covered by the passage index
+    fn chunk_prefix(&self) -> String {
+        format!("{}chunk:", self.prefix)
+    }
+
+    /// Key of the vector hash of a post
+    fn vector_key(&self, post_id: &str) -> String {
+        format!("{}vec:{}", self.prefix, post_id)
+    }
+
+    /// Key of the cached metadata of a post
+    fn metadata_key(&self, post_id: &str) -> String {
+        format!("{}meta:{}", self.prefix, post_id)
+    }
+
+    /// Key of a cached top-k result list
+    fn top_k_key(&self, query_hash: u64) -> String {
+        format!("{}topk:{}", self.prefix, query_hash)
+    }
+
+    /// SCAN pattern matching every cached top-k entry and index set
+    fn top_k_pattern(&self) -> String {
+        format!("{}topk:*", self.prefix)
+    }
+
+    /// Key of the set listing the top-k entries that contain a post
+    fn top_k_post_index_key(&self, post_id: &str) -> String {
+        format!("{}topk:post:{}", self.prefix, post_id)
+    }
+
+    /// Name of the RediSearch vector index for a given dimension
+    ///
+    /// The `v2` segment marks the schema with author, domain and date fields;
+    /// bumping it creates a new index rather than reusing one without them.
+    fn vector_index_name(&self, dimension: usize) -> String {
+        format!("idx:{}vec:v2:{}", self.prefix, dimension)
+    }
+
+    /// Name of the RediSearch passage index for a given dimension
+    fn chunk_index_name(&self, dimension: usize) -> String {
+        format!("idx:{}chunk:v1:{}", self.prefix, dimension)
+    }
+
+    /// Key of the passage hash `<prefix>chunk:<post_id>:<chunk_index>`
+    fn chunk_key(&self, post_id: &str, chunk_index: u32) -> String {
+        format!("{}{}:{}", self.chunk_prefix(), post_id, chunk_index)
+    }
+
+    /// Passage keys of a post for a range of chunk indexes
+    fn chunk_keys(&self, post_id: &str, indexes: Range<u32>) -> Vec<String> {
+        indexes.map(|index| self.chunk_key(post_id, index)).collect()
+    }
+
+    /// Key holding the number of passages stored for a post
+    ///
+    /// Lives outside the passage prefix so the passage index never sees it.
+    fn chunk_count_key(&self, post_id: &str) -> String {
+        format!("{}chunks:{}", self.prefix, post_id)
+    }
+}
+
+impl Default for Keyspace {
+    fn default() -> Self {
+        Self {
+            prefix: "search:".to_string(),
+        }
+    }
+}
+
 /// Redis client wrapper with connection pooling and error handling
+///
+/// Each client reads and writes the keys of one collection; clients of other
+/// collections share the connection pool.
 pub struct RedisClient {
     /// Fred Redis client with connection pooling
     client: RedisPool,
     /// Configuration
     config: RedisConfig,
+    /// Keys and indexes of the collection served by this client
+    keyspace: Keyspace,
+    /// Whether the RediSearch HNSW index is available for KNN queries
+    vector_index_ready: bool,
     /// Cache statistics tracking
     stats: Arc<CacheStatsInternal>,
 }
@@ -98,49 +212,272 @@ impl RedisClient {
 
         info!("Redis client connected successfully");
 
+        // Without the RediSearch module the service keeps running on Postgres alone
+        let keyspace = Keyspace::default();
+        let vector_index_ready = Self::try_ensure_vector_index(&client, &keyspace, config.vector_dimension).await;
+
         Ok(RedisClient { 
             client, 
             config,
+            keyspace,
+            vector_index_ready,
             stats: Arc::new(CacheStatsInternal::default()),
         })
     }
 
+    /// Client for another collection, sharing this client's connection pool
+    ///
+    /// The collection's vector indexes are created for `dimension` when missing.
+    pub async fn for_collection(&self, name: &str, dimension: usize) -> Self {
+        let keyspace = Keyspace::for_collection(name);
+        let vector_index_ready = Self::try_ensure_vector_index(&self.client, &keyspace, dimension).await;
+
+        RedisClient {
+            client: self.client.clone(),
+            config: RedisConfig {
+                vector_dimension: dimension,
+                ..self.config.clone()
+            },
+            keyspace,
+            vector_index_ready,
+            stats: Arc::new(CacheStatsInternal::default()),
+        }
+    }
+
+    /// Ensure the vector indexes exist, reporting whether KNN queries can use them
+    async fn try_ensure_vector_index(client: &RedisPool, keyspace: &Keyspace, dimension: usize) -> bool {
+        match Self::ensure_vector_index(client, keyspace, dimension).await {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Redis vector search disabled: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Create the HNSW post and passage indexes if they do not exist yet
+    ///
+    /// The index names include the dimension, so switching embedding models
+    /// builds fresh indexes instead of failing on the old ones.
+    async fn ensure_vector_index(client: &RedisPool, keyspace: &Keyspace, dimension: usize) -> SearchResult<()> {
+        let indexes = [
+            (keyspace.vector_index_name(dimension), keyspace.vector_prefix()),
+            (keyspace.chunk_index_name(dimension), keyspace.chunk_prefix()),
+        ];
+
+        for (index_name, key_prefix) in indexes {
+            let result: Result<RedisValue, _> = client
+                .custom(
+                    CustomCommand::new_static("FT.CREATE", ClusterHash::Random, false),
+                    vector_index_create_args(&index_name, &key_prefix, dimension),
+                )
+                .await;
+
+            match result {
+                Ok(_) => info!("Created Redis vector index {} ({} dimensions)", index_name, dimension),
+                Err(e) if e.details().contains("Index already exists") => {
+                    debug!("Redis vector index {} already exists", index_name);
+                }
+                Err(e) => {
+                    return Err(SearchError::RedisError(format!(
+                        "Failed to create vector index {}: {}",
+                        index_name, e
+                    )))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Store vector embedding in Redis with permanent storage
     pub async fn set_vector(&self, post_id: &str, embedding: &[f32]) -> SearchResult<()> {
-        let key = format!("search:vec:{}", post_id);
-        
-        // Serialize embedding as bytes for efficient storage
-        let embedding_bytes: Vec<u8> = embedding
-            .iter()
-            .flat_map(|f| f.to_le_bytes())
-            .collect();
+        self.write_vector(post_id, embedding, Vec::new()).await
+    }
 
-        debug!("Storing vector for post_id: {} (size: {} bytes)", post_id, embedding_bytes.len());
+    /// Store vector embedding together with the metadata fields used for KNN pre-filtering
+    pub async fn set_vector_with_metadata(
+        &self,
+        post_id: &str,
+        embedding: &[f32],
+        metadata: &PostMetadata,
+    ) -> SearchResult<()> {
+        self.write_vector(post_id, embedding, prefilter_fields(metadata)).await
+    }
+
+    /// Replace the indexed passages of a post
+    ///
+    /// Passages carry the post's pre-filter fields, so passage KNN queries
+    /// filter the same way as post queries.
+    pub async fn set_post_chunks(
+        &self,
+        post_id: &str,
+        chunks: &[PostChunk],
+        metadata: &PostMetadata,
+    ) -> SearchResult<()> {
+        if let Some(chunk) = chunks.iter().find(|chunk| chunk.embedding.len() != self.config.vector_dimension) {
+            return Err(SearchError::CacheError(format!(
+                "Passage {} of post_id {} has {} dimensions, expected {}",
+                chunk.chunk_index,
+                post_id,
+                chunk.embedding.len(),
+                self.config.vector_dimension
+            )));
+        }
+
+        // A post that got shorter must not keep its trailing passages
+        let chunk_count = chunks.len() as u32;
+        let previous_count = self.stored_chunk_count(post_id).await?;
+        if previous_count > chunk_count {
+            let _: i64 = self.client
+                .unlink(self.keyspace.chunk_keys(post_id, chunk_count..previous_count))
+                .await
+                .map_err(|e| SearchError::RedisError(format!("Failed to clear passages: {}", e)))?;
+        }
+
+        debug!("Storing {} passages for post_id: {}", chunks.len(), post_id);
+
+        for chunk in chunks {
+            let embedding_bytes = vector_to_bytes(&chunk.embedding);
+            let mut fields = prefilter_fields(metadata);
+            fields.push(("start", RedisValue::from(chunk.span.start as i64)));
+            fields.push(("end", RedisValue::from(chunk.span.end as i64)));
+            fields.push(("embedding", RedisValue::from(embedding_bytes.as_slice())));
+
+            let _: () = self.client
+                .hset(self.keyspace.chunk_key(post_id, chunk.chunk_index), fields)
+                .await
+                .map_err(|e| SearchError::RedisError(format!("Failed to store passage: {}", e)))?;
+        }
 
         let _: () = self.client
-            .set(&key, embedding_bytes, None, None, false)
+            .set(self.keyspace.chunk_count_key(post_id), chunk_count as i64, None, None, false)
             .await
-            .map_err(|e| SearchError::RedisError(format!("Failed to store vector: {}", e)))?;
+            .map_err(|e| SearchError::RedisError(format!("Failed to store passage count: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Number of passages stored for a post
+    ///
+    /// Posts whose passages were written before counts were tracked report
+    /// `MAX_CHUNKS_PER_POST`, so every key they might have is still cleared.
+    async fn stored_chunk_count(&self, post_id: &str) -> SearchResult<u32> {
+        let count: Option<i64> = self.client
+            .get(self.keyspace.chunk_count_key(post_id))
+            .await
+            .map_err(|e| SearchError::RedisError(format!("Failed to read passage count: {}", e)))?;
+
+        Ok(count
+            .map(|count| count.clamp(0, MAX_CHUNKS_PER_POST as i64) as u32)
+            .unwrap_or(MAX_CHUNKS_PER_POST as u32))
+    }
+
+    /// Write the vector hash `<prefix>vec:<post_id>` with any extra fields
+    async fn write_vector(
+        &self,
+        post_id: &str,
+        embedding: &[f32],
+        mut fields: Vec<(&str, RedisValue)>,
+    ) -> SearchResult<()> {
+        let key = self.keyspace.vector_key(post_id);
+
+        if embedding.len() != self.config.vector_dimension {
+            return Err(SearchError::CacheError(format!(
+                "Vector for post_id {} has {} dimensions, expected {}",
+                post_id,
+                embedding.len(),
+                self.config.vector_dimension
+            )));
+        }
+        
+        // Serialize embedding as FLOAT32 bytes, the layout RediSearch indexes
+        let embedding_bytes = vector_to_bytes(embedding);
+
+        debug!("Storing vector for post_id: {} (size: {} bytes)", post_id, embedding_bytes.len());
+
+        fields.push(("embedding", RedisValue::from(embedding_bytes.as_slice())));
+
+        match self.client.hset::<(), _, _>(&key, fields.clone()).await {
+            // Vectors written before the hash layout are plain strings: replace them
+            Err(e) if is_wrong_type(&e) => {
+                warn!("Replacing legacy string vector for post_id: {}", post_id);
+                let _: u64 = self.client
+                    .del(&key)
+                    .await
+                    .map_err(|e| SearchError::RedisError(format!("Failed to delete legacy vector: {}", e)))?;
+                let _: () = self.client
+                    .hset(&key, fields)
+                    .await
+                    .map_err(|e| SearchError::RedisError(format!("Failed to store vector: {}", e)))?;
+            }
+            result => result.map_err(|e| SearchError::RedisError(format!("Failed to store vector: {}", e)))?,
+        }
 
         Ok(())
     }
 
     /// Retrieve vector embedding from Redis
     pub async fn get_vector(&self, post_id: &str) -> SearchResult<Option<Vec<f32>>> {
-        let key = format!("search:vec:{}", post_id);
+        let key = self.keyspace.vector_key(post_id);
         
         debug!("Retrieving vector for post_id: {}", post_id);
 
-        let result: Option<Vec<u8>> = self.client
-            .get(&key)
-            .await
-            .map_err(|e| SearchError::RedisError(format!("Failed to get vector: {}", e)))?;
+        let result: Option<Vec<u8>> = match self.client.hget(&key, "embedding").await {
+            // A legacy string vector is a miss; the next write replaces it with a hash
+            Err(e) if is_wrong_type(&e) => {
+                debug!("Ignoring legacy string vector for post_id: {}", post_id);
+                None
+            }
+            result => result.map_err(|e| SearchError::RedisError(format!("Failed to get vector: {}", e)))?,
+        };
+
+        self.decode_cached_vector(post_id, result)
+    }
+
+    /// Retrieve the vector embeddings of several posts in one round trip
+    ///
+    /// The lookups are pipelined, so posts on different cluster nodes are
+    /// fine. Posts without a usable vector are left out of the result.
+    pub async fn get_vectors(&self, post_ids: &[String]) -> SearchResult<HashMap<String, Vec<f32>>> {
+        if post_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        debug!("Retrieving vectors for {} posts", post_ids.len());
+
+        let pipeline = self.client.next().pipeline();
+        for post_id in post_ids {
+            let _: () = pipeline
+                .hget(self.keyspace.vector_key(post_id), "embedding")
+                .await
+                .map_err(|e| SearchError::RedisError(format!("Failed to queue vector lookup: {}", e)))?;
+        }
+        let results: Vec<Result<Option<Vec<u8>>, _>> = pipeline.try_all().await;
+
+        let mut embeddings = HashMap::with_capacity(post_ids.len());
+        for (post_id, result) in post_ids.iter().zip(results) {
+            // A failed lookup, such as a legacy string vector, is a miss
+            let bytes = result.unwrap_or_else(|e| {
+                debug!("Vector lookup failed for post_id: {}: {}", post_id, e);
+                None
+            });
+            match self.decode_cached_vector(post_id, bytes) {
+                Ok(Some(embedding)) => {
+                    embeddings.insert(post_id.clone(), embedding);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Ignoring cached vector for post_id: {}: {}", post_id, e),
+            }
+        }
 
+        Ok(embeddings)
+    }
+
+    /// Decode the stored bytes of a vector, recording the hit or miss
+    fn decode_cached_vector(&self, post_id: &str, result: Option<Vec<u8>>) -> SearchResult<Option<Vec<f32>>> {
         match result {
             Some(bytes) => {
-                // Track cache hit
-                self.stats.vector_cache_hits.fetch_add(1, Ordering::Relaxed);
-                
                 // Deserialize bytes back to f32 vector
                 if bytes.len() % 4 != 0 {
                     return Err(SearchError::RedisError(
@@ -148,10 +485,22 @@ impl RedisClient {
                     ));
                 }
 
-                let embedding: Vec<f32> = bytes
-                    .chunks_exact(4)
-                    .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-                    .collect();
+                let embedding = bytes_to_vector(&bytes);
+
+                // Vectors written by a previous embedding model are treated as misses
+                if embedding.len() != self.config.vector_dimension {
+                    self.stats.vector_cache_misses.fetch_add(1, Ordering::Relaxed);
+                    warn!(
+                        "Ignoring cached vector for post_id: {} with {} dimensions (expected {})",
+                        post_id,
+                        embedding.len(),
+                        self.config.vector_dimension
+                    );
+                    return Ok(None);
+                }
+
+                // Track cache hit
+                self.stats.vector_cache_hits.fetch_add(1, Ordering::Relaxed);
 
                 debug!("Retrieved vector for post_id: {} (dimensions: {}) - CACHE HIT", post_id, embedding.len());
                 Ok(Some(embedding))
@@ -165,67 +514,139 @@ impl RedisClient {
         }
     }
 
-    /// Perform vector similarity search using Redis VSS
-    pub async fn vector_search(&self, query_embedding: &[f32], limit: usize) -> SearchResult<Vec<SearchCandidate>> {
+    /// Perform vector similarity search using a RediSearch HNSW KNN query
+    ///
+    /// Language and frozen filters are applied as a pre-filter inside the KNN
+    /// query; without filters frozen posts are excluded, matching Postgres.
+    /// When the pre-filter is not exact, more than `limit` candidates that
+    /// may fail the filter are returned for the caller to post-filter.
+    pub async fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<SearchCandidate>> {
         debug!("Performing Redis vector search with limit: {}", limit);
 
-        // For this implementation, we'll use a simple approach since Redis VSS setup
-        // requires specific index configuration. In a real implementation, you would:
-        // 1. Create a Redis Search index with vector field
-        // 2. Use FT.SEARCH with KNN query
-        // 
-        // For now, we'll implement a fallback that scans available vectors
-        // This is not optimal for production but demonstrates the interface
-
-        // For this implementation, we'll use a simple approach since Redis VSS setup
-        // requires specific index configuration. In a real implementation, you would:
-        // 1. Create a Redis Search index with vector field using FT.CREATE
-        // 2. Use FT.SEARCH with KNN query for efficient vector search
-        // 
-        // For now, we'll return empty results and log a warning
-        warn!("Redis vector search not fully implemented - requires Redis Search module with vector indexing");
-        
-        // In a production system, this would be:
-        // let search_query = format!("*=>[KNN {} @embedding $query_vec]", limit);
-        // let results = self.client.ft_search("vector_index", &search_query, query_embedding).await?;
-        
-        let keys: Vec<String> = Vec::new(); // Placeholder - would come from FT.SEARCH results
-
-        debug!("Found {} vector keys for similarity search", keys.len());
-
-        let mut candidates = Vec::new();
-
-        // Process keys in batches to avoid overwhelming Redis
-        for chunk in keys.chunks(50) {
-            let mut batch_candidates = Vec::new();
-            
-            for key in chunk {
-                if let Some(post_id) = key.strip_prefix("search:vec:") {
-                    if let Ok(Some(embedding)) = self.get_vector(post_id).await {
-                        let score = cosine_similarity(query_embedding, &embedding);
-                        batch_candidates.push(SearchCandidate {
-                            post_id: post_id.to_string(),
-                            score,
-                            source: SearchSource::Redis,
-                        });
-                    }
-                }
-            }
-            
-            candidates.extend(batch_candidates);
+        if !self.vector_index_ready {
+            return Err(SearchError::RedisError("Redis vector index is not available".to_string()));
+        }
+
+        if query_embedding.len() != self.config.vector_dimension {
+            return Err(SearchError::RedisError(format!(
+                "Query vector has {} dimensions, expected {}",
+                query_embedding.len(),
+                self.config.vector_dimension
+            )));
+        }
+
+        if limit == 0 {
+            return Ok(Vec::new());
         }
 
-        // Sort by score (descending) and limit results
-        candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-        candidates.truncate(limit);
+        let limit = knn_fetch_limit(limit, filters);
+        let query = knn_query(&knn_prefilter(filters), limit);
+        let query_bytes = vector_to_bytes(query_embedding);
+
+        let args: Vec<RedisValue> = vec![
+            self.keyspace.vector_index_name(self.config.vector_dimension).into(),
+            query.into(),
+            "PARAMS".into(),
+            "2".into(),
+            "BLOB".into(),
+            RedisValue::from(query_bytes.as_slice()),
+            "SORTBY".into(),
+            "vector_score".into(),
+            "ASC".into(),
+            "RETURN".into(),
+            "1".into(),
+            "vector_score".into(),
+            "LIMIT".into(),
+            "0".into(),
+            limit.to_string().into(),
+            "DIALECT".into(),
+            "2".into(),
+        ];
+
+        let response: RedisValue = self.client
+            .custom(CustomCommand::new_static("FT.SEARCH", ClusterHash::Random, false), args)
+            .await
+            .map_err(|e| SearchError::RedisError(format!("Redis KNN search failed: {}", e)))?;
+
+        let candidates = parse_knn_response(response, &self.keyspace.vector_prefix());
 
         debug!("Redis vector search returned {} candidates", candidates.len());
         Ok(candidates)
     }
 
+    /// Perform passage similarity search using the passage HNSW index
+    ///
+    /// Uses the same pre-filter as `vector_search`; a post can contribute
+    /// several passages.
+    pub async fn chunk_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<ChunkCandidate>> {
+        debug!("Performing Redis passage search with limit: {}", limit);
+
+        if !self.vector_index_ready {
+            return Err(SearchError::RedisError("Redis vector index is not available".to_string()));
+        }
+
+        if query_embedding.len() != self.config.vector_dimension {
+            return Err(SearchError::RedisError(format!(
+                "Query vector has {} dimensions, expected {}",
+                query_embedding.len(),
+                self.config.vector_dimension
+            )));
+        }
+
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let limit = knn_fetch_limit(limit, filters);
+        let query = knn_query(&knn_prefilter(filters), limit);
+        let query_bytes = vector_to_bytes(query_embedding);
+
+        let args: Vec<RedisValue> = vec![
+            self.keyspace.chunk_index_name(self.config.vector_dimension).into(),
+            query.into(),
+            "PARAMS".into(),
+            "2".into(),
+            "BLOB".into(),
+            RedisValue::from(query_bytes.as_slice()),
+            "SORTBY".into(),
+            "vector_score".into(),
+            "ASC".into(),
+            "RETURN".into(),
+            "3".into(),
+            "vector_score".into(),
+            "start".into(),
+            "end".into(),
+            "LIMIT".into(),
+            "0".into(),
+            limit.to_string().into(),
+            "DIALECT".into(),
+            "2".into(),
+        ];
+
+        let response: RedisValue = self.client
+            .custom(CustomCommand::new_static("FT.SEARCH", ClusterHash::Random, false), args)
+            .await
+            .map_err(|e| SearchError::RedisError(format!("Redis passage KNN search failed: {}", e)))?;
+
+        let candidates = parse_chunk_response(response, &self.keyspace.chunk_prefix());
+
+        debug!("Redis passage search returned {} candidates", candidates.len());
+        Ok(candidates)
+    }
+
     /// Store top-k search results in cache with TTL
     pub async fn set_top_k_cache(&self, query_hash: u64, results: &[CachedResult]) -> SearchResult<()> {
-        let key = format!("search:topk:{}", query_hash);
+        let key = self.keyspace.top_k_key(query_hash);
         let ttl = 60; // 60 seconds as per requirements
 
         debug!("Caching top-k results for query_hash: {} (count: {})", query_hash, results.len());
@@ -233,9 +654,26 @@ impl RedisClient {
         let serialized = serde_json::to_string(results)
             .map_err(|e| SearchError::CacheError(format!("Failed to serialize results: {}", e)))?;
 
-        let _: () = self.client
+        // Track which entries contain each post so erasure can purge them
+        let pipeline = self.client.next().pipeline();
+        let queue_error = |e: fred::error::RedisError| {
+            SearchError::RedisError(format!("Failed to queue top-k cache write: {}", e))
+        };
+
+        let _: () = pipeline
             .set(&key, serialized, Some(Expiration::EX(ttl)), None, false)
             .await
+            .map_err(queue_error)?;
+
+        for result in results {
+            let index_key = self.keyspace.top_k_post_index_key(&result.post_id);
+            let _: () = pipeline.sadd(&index_key, key.as_str()).await.map_err(queue_error)?;
+            let _: () = pipeline.expire(&index_key, ttl).await.map_err(queue_error)?;
+        }
+
+        let _: () = pipeline
+            .all()
+            .await
             .map_err(|e| SearchError::RedisError(format!("Failed to cache top-k results: {}", e)))?;
 
         Ok(())
@@ -243,7 +681,7 @@ impl RedisClient {
 
     /// Retrieve top-k search results from cache
     pub async fn get_top_k_cache(&self, query_hash: u64) -> SearchResult<Option<Vec<CachedResult>>> {
-        let key = format!("search:topk:{}", query_hash);
+        let key = self.keyspace.top_k_key(query_hash);
         
         debug!("Retrieving top-k cache for query_hash: {}", query_hash);
 
@@ -272,9 +710,77 @@ impl RedisClient {
         }
     }
 
+    /// Remove every cached top-k result
+    ///
+    /// New or updated posts can change the results of any query, so ingestion
+    /// drops the whole top-k tier rather than guessing which entries are stale.
+    /// In cluster mode every primary is scanned, and keys are unlinked one by
+    /// one in a pipeline since a page can span hash slots.
+    pub async fn invalidate_top_k_cache(&self) -> SearchResult<u64> {
+        debug!("Invalidating all top-k cache entries");
+
+        let client = self.client.next();
+        let mut scan = if client.is_clustered() {
+            client.scan_cluster(self.keyspace.top_k_pattern(), Some(500), None).boxed()
+        } else {
+            client.scan(self.keyspace.top_k_pattern(), Some(500), None).boxed()
+        };
+        let mut deleted = 0u64;
+
+        while let Some(page) = scan.next().await {
+            let mut page = page
+                .map_err(|e| SearchError::RedisError(format!("Failed to scan top-k cache: {}", e)))?;
+
+            if let Some(keys) = page.take_results() {
+                if !keys.is_empty() {
+                    let pipeline = client.pipeline();
+                    for key in keys {
+                        let _: () = pipeline
+                            .unlink(key)
+                            .await
+                            .map_err(|e| SearchError::RedisError(format!("Failed to invalidate top-k cache: {}", e)))?;
+                    }
+                    let counts: Vec<i64> = pipeline
+                        .all()
+                        .await
+                        .map_err(|e| SearchError::RedisError(format!("Failed to invalidate top-k cache: {}", e)))?;
+                    deleted += counts.iter().sum::<i64>() as u64;
+                }
+            }
+
+            page.next()
+                .map_err(|e| SearchError::RedisError(format!("Failed to continue top-k scan: {}", e)))?;
+        }
+
+        info!("Invalidated {} top-k cache entries", deleted);
+        Ok(deleted)
+    }
+
+    /// Remove the top-k entries whose results contain the given post
+    pub async fn purge_top_k_for_post(&self, post_id: &str) -> SearchResult<u64> {
+        let index_key = self.keyspace.top_k_post_index_key(post_id);
+
+        let entries: Vec<String> = self.client
+            .smembers(&index_key)
+            .await
+            .map_err(|e| SearchError::RedisError(format!("Failed to read top-k index: {}", e)))?;
+
+        let entry_count = entries.len() as u64;
+        let mut keys = entries;
+        keys.push(index_key);
+
+        let _: i64 = self.client
+            .unlink(keys)
+            .await
+            .map_err(|e| SearchError::RedisError(format!("Failed to purge top-k entries: {}", e)))?;
+
+        debug!("Purged {} top-k entries containing post_id: {}", entry_count, post_id);
+        Ok(entry_count)
+    }
+
     /// Store post metadata in cache with 24h TTL
     pub async fn set_metadata_cache(&self, post_id: &str, metadata: &PostMetadata) -> SearchResult<()> {
-        let key = format!("search:meta:{}", post_id);
+        let key = self.keyspace.metadata_key(post_id);
         let ttl = 24 * 60 * 60; // 24 hours
 
         debug!("Caching metadata for post_id: {}", post_id);
@@ -292,7 +798,7 @@ impl RedisClient {
 
     /// Retrieve post metadata from cache
     pub async fn get_metadata_cache(&self, post_id: &str) -> SearchResult<Option<PostMetadata>> {
-        let key = format!("search:meta:{}", post_id);
+        let key = self.keyspace.metadata_key(post_id);
         
         debug!("Retrieving metadata cache for post_id: {}", post_id);
 
@@ -321,12 +827,15 @@ impl RedisClient {
         }
     }
 
-    /// Delete post data from all caches (GDPR compliance)
-    pub async fn delete_post_data(&self, post_id: &str) -> SearchResult<()> {
-        let keys = vec![
-            format!("search:vec:{}", post_id),
-            format!("search:meta:{}", post_id),
+    /// Delete post data from all caches (GDPR compliance), returning the number of keys removed
+    pub async fn delete_post_data(&self, post_id: &str) -> SearchResult<u64> {
+        let chunk_count = self.stored_chunk_count(post_id).await?;
+        let mut keys = vec![
+            self.keyspace.vector_key(post_id),
+            self.keyspace.metadata_key(post_id),
+            self.keyspace.chunk_count_key(post_id),
         ];
+        keys.extend(self.keyspace.chunk_keys(post_id, 0..chunk_count));
 
         debug!("Deleting cached data for post_id: {}", post_id);
 
@@ -341,7 +850,7 @@ impl RedisClient {
         self.stats.gdpr_keys_deleted.fetch_add(deleted_count as u64, Ordering::Relaxed);
 
         info!("Deleted {} cache entries for post_id: {} (GDPR compliance)", deleted_count, post_id);
-        Ok(())
+        Ok(deleted_count as u64)
     }
 
     /// Check Redis connection health
@@ -372,6 +881,9 @@ impl RedisClient {
     }
 
     /// Get Redis connection statistics
+    ///
+    /// Server figures cover the whole Redis instance; indexed post and passage
+    /// counts come from this collection's vector indexes.
     pub async fn get_stats(&self) -> SearchResult<RedisStats> {
         // Get basic info from Redis
         let info: String = self.client
@@ -402,9 +914,31 @@ impl RedisClient {
             }
         }
 
+        if self.vector_index_ready {
+            stats.indexed_posts = self
+                .index_document_count(&self.keyspace.vector_index_name(self.config.vector_dimension))
+                .await?;
+            stats.indexed_passages = self
+                .index_document_count(&self.keyspace.chunk_index_name(self.config.vector_dimension))
+                .await?;
+        }
+
         Ok(stats)
     }
 
+    /// Number of documents in a RediSearch index
+    async fn index_document_count(&self, index_name: &str) -> SearchResult<u64> {
+        let response: RedisValue = self.client
+            .custom(
+                CustomCommand::new_static("FT.INFO", ClusterHash::Random, false),
+                vec![index_name.to_string()],
+            )
+            .await
+            .map_err(|e| SearchError::RedisError(format!("Failed to get info for index {}: {}", index_name, e)))?;
+
+        Ok(reply_field::<u64>(&response.into_array(), "num_docs").unwrap_or(0))
+    }
+
     /// Get cache hit/miss statistics
     pub fn get_cache_stats(&self) -> CacheStats {
         self.stats.to_cache_stats()
@@ -430,6 +964,8 @@ pub struct RedisStats {
     pub total_connections: u64,
     pub connected_clients: u32,
     pub used_memory_bytes: u64,
+    pub indexed_posts: u64,
+    pub indexed_passages: u64,
 }
 
 /// Cache statistics for monitoring hit/miss ratios
@@ -518,6 +1054,294 @@ fn sanitize_url_for_logging(url: &str) -> String {
     }
 }
 
+/// Arguments for FT.CREATE over the vector hashes under `key_prefix`
+fn vector_index_create_args(index_name: &str, key_prefix: &str, dimension: usize) -> Vec<String> {
+    [
+        index_name, "ON", "HASH", "PREFIX", "1", key_prefix, "SCHEMA",
+        "embedding", "VECTOR", "HNSW", "6",
+        "TYPE", "FLOAT32", "DIM", &dimension.to_string(), "DISTANCE_METRIC", "COSINE",
+        "language", "TAG",
+        "frozen", "TAG",
+        "author", "TAG", "CASESENSITIVE",
+        "domain", "TAG",
+        "date", "NUMERIC",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect()
+}
+
+/// Build the KNN pre-filter expression for the given search filters
+///
+/// URL prefixes and custom metadata have no index field. Their conditions
+/// are left out, so the pre-filter matches a superset of the filter and the
+/// service post-filter drops the rest; see `prefilter_is_exact`.
+fn knn_prefilter(filters: Option<&SearchFilters>) -> String {
+    let mentions_frozen = filters.is_some_and(SearchFilters::mentions_frozen);
+
+    let mut clauses = filters.map(knn_clauses).unwrap_or_default();
+
+    if !mentions_frozen {
+        clauses.push("-@frozen:{true}".to_string());
+    }
+
+    if clauses.is_empty() {
+        return "*".to_string();
+    }
+
+    format!("({})", clauses.join(" "))
+}
+
+/// Whether the KNN pre-filter expresses the filters exactly
+///
+/// When it does not, Redis can return posts that fail the filter, so
+/// searches fetch `RELAXED_PREFILTER_OVERFETCH` times more hits for the
+/// post-filter to choose from.
+pub fn prefilter_is_exact(filters: Option<&SearchFilters>) -> bool {
+    !filters.is_some_and(|filters| filters.mentions_url_prefix() || filters.mentions_metadata())
+}
+
+/// Hits fetched per requested result when the pre-filter is not exact
+const RELAXED_PREFILTER_OVERFETCH: usize = 4;
+
+/// KNN hits to fetch for `limit` results under the given filters
+fn knn_fetch_limit(limit: usize, filters: Option<&SearchFilters>) -> usize {
+    if prefilter_is_exact(filters) {
+        limit
+    } else {
+        limit * RELAXED_PREFILTER_OVERFETCH
+    }
+}
+
+/// Pre-filter clauses of one filter node, all of which must hold
+///
+/// Conditions without an index field are skipped, which can only widen the
+/// match: a negated node is kept only when it is expressed exactly, and an
+/// `or` is dropped when one of its alternatives has no clause left.
+fn knn_clauses(filters: &SearchFilters) -> Vec<String> {
+    let mut clauses = Vec::new();
+
+    if let Some(language) = &filters.language {
+        clauses.push(format!("@language:{{{}}}", escape_tag_value(language)));
+    }
+
+    if let Some(frozen) = filters.frozen {
+        clauses.push(format!("@frozen:{{{}}}", frozen));
+    }
+
+    if let Some(after) = &filters.date_after {
+        clauses.push(format!("@date:[{} +inf]", epoch_seconds(after)));
+    }
+
+    if let Some(before) = &filters.date_before {
+        clauses.push(format!("@date:[-inf ({}]", epoch_seconds(before)));
+    }
+
+    if let Some(authors) = &filters.authors {
+        clauses.push(format!("@author:{{{}}}", tag_union(authors.iter().map(|a| author_tag(a)))));
+    }
+
+    if let Some(authors) = &filters.exclude_authors {
+        clauses.push(format!("-@author:{{{}}}", tag_union(authors.iter().map(|a| author_tag(a)))));
+    }
+
+    if let Some(domains) = &filters.url_domains {
+        clauses.push(format!("@domain:{{{}}}", tag_union(domains.iter().cloned())));
+    }
+
+    for nested in filters.and.iter().flatten() {
+        clauses.extend(knn_clauses(nested));
+    }
+
+    if let Some(alternatives) = &filters.or {
+        let alternatives: Vec<Vec<String>> = alternatives.iter().map(knn_clauses).collect();
+        if !alternatives.is_empty() && alternatives.iter().all(|clauses| !clauses.is_empty()) {
+            let alternatives: Vec<String> = alternatives
+                .iter()
+                .map(|clauses| format!("({})", clauses.join(" ")))
+                .collect();
+            clauses.push(format!("({})", alternatives.join(" | ")));
+        }
+    }
+
+    if let Some(nested) = filters.not.as_deref().filter(|nested| prefilter_is_exact(Some(nested))) {
+        let negated = knn_clauses(nested);
+        if !negated.is_empty() {
+            clauses.push(format!("-({})", negated.join(" ")));
+        }
+    }
+
+    clauses
+}
+
+/// Escaped TAG values joined as a RediSearch union (`a | b`)
+fn tag_union(values: impl Iterator<Item = String>) -> String {
+    values
+        .map(|value| escape_tag_value(&value))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Hash fields used by the KNN pre-filter
+fn prefilter_fields(metadata: &PostMetadata) -> Vec<(&'static str, RedisValue)> {
+    vec![
+        ("language", RedisValue::from(metadata.language.as_str())),
+        ("frozen", RedisValue::from(if metadata.frozen { "true" } else { "false" })),
+        ("author", RedisValue::from(author_tag(&metadata.author_name))),
+        ("domain", RedisValue::from(domain_tags(&metadata.url))),
+        ("date", RedisValue::from(epoch_seconds(&metadata.date))),
+    ]
+}
+
+/// Author TAG value; commas would split it into several tags
+fn author_tag(author_name: &str) -> String {
+    author_name.replace(',', " ")
+}
+
+/// Comma-separated host suffixes of a URL for the `domain` TAG field
+///
+/// `blog.example.com` is indexed as `blog.example.com,example.com` so that a
+/// domain filter also matches subdomains. Single-label suffixes are skipped.
+fn domain_tags(url: &str) -> String {
+    let host = match url_host(url) {
+        Some(host) => host,
+        None => return String::new(),
+    };
+
+    let labels: Vec<&str> = host.split('.').collect();
+    (0..labels.len().saturating_sub(1))
+        .map(|start| labels[start..].join("."))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Timestamp in fractional seconds for the `date` NUMERIC field
+fn epoch_seconds(date: &DateTime<Utc>) -> f64 {
+    date.timestamp_millis() as f64 / 1000.0
+}
+
+/// Build the full KNN query string
+fn knn_query(prefilter: &str, limit: usize) -> String {
+    format!("{}=>[KNN {} @embedding $BLOB AS vector_score]", prefilter, limit)
+}
+
+/// Escape punctuation in a TAG value so RediSearch treats it literally
+fn escape_tag_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if !ch.is_alphanumeric() && ch != '_' {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Parse an FT.SEARCH reply (`[total, key, [field, value, ...], ...]`) into candidates
+///
+/// Cosine distance is converted to similarity (1 - distance) to match Postgres scores.
+fn parse_knn_response(response: RedisValue, key_prefix: &str) -> Vec<SearchCandidate> {
+    let mut values = response.into_array().into_iter();
+
+    // First element is the total number of matches
+    let _total = values.next();
+
+    let mut candidates = Vec::new();
+    while let (Some(key), Some(fields)) = (values.next(), values.next()) {
+        let post_id = match key.as_string().and_then(|k| k.strip_prefix(key_prefix).map(str::to_string)) {
+            Some(post_id) => post_id,
+            None => continue,
+        };
+
+        let fields = fields.into_array();
+        let distance = reply_field::<f32>(&fields, "vector_score");
+
+        if let Some(distance) = distance {
+            candidates.push(SearchCandidate {
+                post_id,
+                score: 1.0 - distance,
+                source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
+            });
+        }
+    }
+
+    candidates
+}
+
+/// Parse an FT.SEARCH reply over passage hashes into passage hits
+fn parse_chunk_response(response: RedisValue, key_prefix: &str) -> Vec<ChunkCandidate> {
+    let mut values = response.into_array().into_iter();
+
+    // First element is the total number of matches
+    let _total = values.next();
+
+    let mut candidates = Vec::new();
+    while let (Some(key), Some(fields)) = (values.next(), values.next()) {
+        // Post ids may contain ':', the chunk index never does
+        let key = match key.as_string() {
+            Some(key) => key,
+            None => continue,
+        };
+        let (post_id, chunk_index) = match key
+            .strip_prefix(key_prefix)
+            .and_then(|rest| rest.rsplit_once(':'))
+            .and_then(|(post_id, index)| Some((post_id.to_string(), index.parse::<u32>().ok()?)))
+        {
+            Some(parsed) => parsed,
+            None => continue,
+        };
+
+        let fields = fields.into_array();
+        let parsed = (
+            reply_field::<f32>(&fields, "vector_score"),
+            reply_field::<u32>(&fields, "start"),
+            reply_field::<u32>(&fields, "end"),
+        );
+
+        if let (Some(distance), Some(start), Some(end)) = parsed {
+            candidates.push(ChunkCandidate {
+                post_id,
+                chunk_index,
+                span: PassageSpan { start, end },
+                score: 1.0 - distance,
+                source: SearchSource::Redis,
+            });
+        }
+    }
+
+    candidates
+}
+
+/// Parse a returned field from an FT.SEARCH `[field, value, ...]` list
+fn reply_field<T: std::str::FromStr>(fields: &[RedisValue], name: &str) -> Option<T> {
+    fields
+        .chunks(2)
+        .find(|pair| pair[0].as_string().as_deref() == Some(name))
+        .and_then(|pair| pair.get(1))
+        .and_then(|value| value.as_string())
+        .and_then(|value| value.parse::<T>().ok())
+}
+
+/// Whether Redis rejected a command because the key holds another type
+fn is_wrong_type(error: &fred::error::RedisError) -> bool {
+    error.details().starts_with("WRONGTYPE")
+}
+
+/// Serialize a vector as little-endian FLOAT32 bytes
+fn vector_to_bytes(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+/// Deserialize little-endian FLOAT32 bytes into a vector
+fn bytes_to_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
 /// Calculate cosine similarity between two vectors
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() {
@@ -539,6 +1363,245 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_knn_prefilter() {
+        assert_eq!(knn_prefilter(None), "(-@frozen:{true})");
+
+        let filters = SearchFilters {
+            language: Some("en".to_string()),
+            frozen: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(knn_prefilter(Some(&filters)), "(@language:{en} @frozen:{false})");
+
+        let filters = SearchFilters {
+            language: Some("pt-BR".to_string()),
+            frozen: None,
+            ..Default::default()
+        };
+        assert_eq!(knn_prefilter(Some(&filters)), "(@language:{pt\\-BR} -@frozen:{true})");
+
+        let filters = SearchFilters {
+            date_after: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+            authors: Some(vec!["Ada".to_string(), "Bob Smith".to_string()]),
+            not: Some(Box::new(SearchFilters {
+                url_domains: Some(vec!["example.com".to_string()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        assert_eq!(
+            knn_prefilter(Some(&filters)),
+            "(@date:[1704067200 +inf] @author:{Ada | Bob\\ Smith} -(@domain:{example\\.com}) -@frozen:{true})"
+        );
+
+        let filters = SearchFilters {
+            or: Some(vec![
+                SearchFilters {
+                    language: Some("en".to_string()),
+                    ..Default::default()
+                },
+                SearchFilters {
+                    frozen: Some(true),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(knn_prefilter(Some(&filters)), "(((@language:{en}) | (@frozen:{true})))");
+
+        // A URL prefix drops only its own clause, widening the match
+        let filters = SearchFilters {
+            url_prefix: Some("https://example.com/blog/".to_string()),
+            language: Some("en".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(knn_prefilter(Some(&filters)), "(@language:{en} -@frozen:{true})");
+        assert!(!prefilter_is_exact(Some(&filters)));
+        assert_eq!(knn_fetch_limit(10, Some(&filters)), 40);
+
+        // Negating a partially expressed node would narrow the match, and an
+        // alternative without clauses matches everything
+        let prefixed = SearchFilters {
+            url_prefix: Some("https://example.com/blog/".to_string()),
+            language: Some("en".to_string()),
+            ..Default::default()
+        };
+        let filters = SearchFilters {
+            authors: Some(vec!["Ada".to_string()]),
+            not: Some(Box::new(prefixed.clone())),
+            or: Some(vec![
+                SearchFilters {
+                    url_prefix: Some("https://example.com/".to_string()),
+                    ..Default::default()
+                },
+                prefixed,
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(knn_prefilter(Some(&filters)), "(@author:{Ada} -@frozen:{true})");
+
+        // Custom metadata keeps the clauses Redis can express
+        let filters = SearchFilters {
+            language: Some("en".to_string()),
+            date_after: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+            metadata: Some(vec![crate::types::MetadataFilter {
+                key: "product.category".to_string(),
+                eq: Some(serde_json::json!("books")),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        assert_eq!(knn_prefilter(Some(&filters)), "(@language:{en} @date:[1704067200 +inf] -@frozen:{true})");
+        assert!(!prefilter_is_exact(Some(&filters)));
+        assert!(prefilter_is_exact(None));
+        assert_eq!(knn_fetch_limit(10, None), 10);
+
+        assert_eq!(
+            knn_query("(-@frozen:{true})", 10),
+            "(-@frozen:{true})=>[KNN 10 @embedding $BLOB AS vector_score]"
+        );
+    }
+
+    #[test]
+    fn test_vector_index_create_args() {
+        let keyspace = Keyspace::default();
+        let args = vector_index_create_args("idx:search:vec:384", &keyspace.vector_prefix(), 384);
+        assert_eq!(args[0], "idx:search:vec:384");
+        assert!(args.windows(2).any(|pair| pair[0] == "PREFIX" && pair[1] == "1"));
+        assert!(args.windows(2).any(|pair| pair[0] == "1" && pair[1] == "search:vec:"));
+        assert!(args.windows(2).any(|pair| pair[0] == "DIM" && pair[1] == "384"));
+        assert!(args.windows(2).any(|pair| pair[0] == "language" && pair[1] == "TAG"));
+        assert!(args.windows(2).any(|pair| pair[0] == "frozen" && pair[1] == "TAG"));
+        assert!(args.windows(2).any(|pair| pair[0] == "date" && pair[1] == "NUMERIC"));
+        assert_eq!(keyspace.vector_index_name(768), "idx:search:vec:v2:768");
+
+        let args = vector_index_create_args(&keyspace.chunk_index_name(384), &keyspace.chunk_prefix(), 384);
+        assert_eq!(args[0], "idx:search:chunk:v1:384");
+        assert!(args.windows(2).any(|pair| pair[0] == "1" && pair[1] == "search:chunk:"));
+    }
+
+    #[test]
+    fn test_collection_keyspace() {
+        // The default collection keeps the original keys
+        let keyspace = Keyspace::for_collection(DEFAULT_COLLECTION);
+        assert_eq!(keyspace, Keyspace::default());
+        assert_eq!(keyspace.vector_key("post_1"), "search:vec:post_1");
+        assert_eq!(keyspace.metadata_key("post_1"), "search:meta:post_1");
+        assert_eq!(keyspace.top_k_key(42), "search:topk:42");
+        assert_eq!(keyspace.top_k_pattern(), "search:topk:*");
+        assert_eq!(keyspace.top_k_post_index_key("post_1"), "search:topk:post:post_1");
+
+        let keyspace = Keyspace::for_collection("docs");
+        assert_eq!(keyspace.vector_key("post_1"), "docs:search:vec:post_1");
+        assert_eq!(keyspace.chunk_key("post_1", 2), "docs:search:chunk:post_1:2");
+        assert_eq!(keyspace.top_k_pattern(), "docs:search:topk:*");
+        assert_eq!(keyspace.vector_index_name(768), "idx:docs:search:vec:v2:768");
+        assert_eq!(keyspace.chunk_index_name(768), "idx:docs:search:chunk:v1:768");
+
+        // Default-collection indexes must not cover another collection's hashes
+        assert!(!keyspace.vector_key("post_1").starts_with(&Keyspace::default().vector_prefix()));
+    }
+
+    #[test]
+    fn test_index_document_count_field() {
+        // FT.INFO replies are flat `[field, value, ...]` lists
+        let response = RedisValue::Array(vec![
+            RedisValue::from("index_name"),
+            RedisValue::from("idx:search:vec:v2:384"),
+            RedisValue::from("num_docs"),
+            RedisValue::from("1250"),
+            RedisValue::from("max_doc_id"),
+            RedisValue::from("1300"),
+        ]);
+
+        assert_eq!(reply_field::<u64>(&response.into_array(), "num_docs"), Some(1250));
+    }
+
+    #[test]
+    fn test_domain_tags() {
+        assert_eq!(domain_tags("https://Blog.Example.com/post"), "blog.example.com,example.com");
+        assert_eq!(domain_tags("http://localhost:8080/"), "");
+        assert_eq!(domain_tags("not a url"), "");
+    }
+
+    #[test]
+    fn test_parse_knn_response() {
+        let response = RedisValue::Array(vec![
+            RedisValue::Integer(2),
+            RedisValue::from("search:vec:post_1"),
+            RedisValue::Array(vec![RedisValue::from("vector_score"), RedisValue::from("0.1")]),
+            RedisValue::from("search:vec:post_2"),
+            RedisValue::Array(vec![RedisValue::from("vector_score"), RedisValue::from("0.25")]),
+        ]);
+
+        let candidates = parse_knn_response(response, "search:vec:");
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].post_id, "post_1");
+        assert!((candidates[0].score - 0.9).abs() < 1e-6);
+        assert_eq!(candidates[1].post_id, "post_2");
+        assert!((candidates[1].score - 0.75).abs() < 1e-6);
+        assert!(matches!(candidates[0].source, SearchSource::Redis));
+
+        assert!(parse_knn_response(RedisValue::Array(vec![RedisValue::Integer(0)]), "search:vec:").is_empty());
+    }
+
+    #[test]
+    fn test_parse_chunk_response() {
+        let response = RedisValue::Array(vec![
+            RedisValue::Integer(2),
+            RedisValue::from("search:chunk:blog:post_1:3"),
+            RedisValue::Array(vec![
+                RedisValue::from("vector_score"),
+                RedisValue::from("0.2"),
+                RedisValue::from("start"),
+                RedisValue::from("120"),
+                RedisValue::from("end"),
+                RedisValue::from("480"),
+            ]),
+            // Missing offsets are skipped
+            RedisValue::from("search:chunk:post_2:0"),
+            RedisValue::Array(vec![RedisValue::from("vector_score"), RedisValue::from("0.3")]),
+        ]);
+
+        let candidates = parse_chunk_response(response, "search:chunk:");
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].post_id, "blog:post_1");
+        assert_eq!(candidates[0].chunk_index, 3);
+        assert_eq!(candidates[0].span, PassageSpan { start: 120, end: 480 });
+        assert!((candidates[0].score - 0.8).abs() < 1e-6);
+
+        let keyspace = Keyspace::default();
+        assert_eq!(keyspace.chunk_key("post_1", 7), "search:chunk:post_1:7");
+        assert_eq!(keyspace.chunk_keys("post_1", 2..5), vec![
+            "search:chunk:post_1:2".to_string(),
+            "search:chunk:post_1:3".to_string(),
+            "search:chunk:post_1:4".to_string(),
+        ]);
+        assert_eq!(keyspace.chunk_count_key("post_1"), "search:chunks:post_1");
+        assert!(!keyspace.chunk_count_key("post_1").starts_with(&keyspace.chunk_prefix()));
+    }
+
+    #[test]
+    fn test_is_wrong_type() {
+        use fred::error::{RedisError, RedisErrorKind};
+
+        let wrong_type = RedisError::new(
+            RedisErrorKind::Unknown,
+            "WRONGTYPE Operation against a key holding the wrong kind of value",
+        );
+        assert!(is_wrong_type(&wrong_type));
+        assert!(!is_wrong_type(&RedisError::new(RedisErrorKind::Timeout, "Request timed out")));
+    }
+
+    #[test]
+    fn test_vector_bytes_roundtrip() {
+        let embedding = vec![0.5, -1.25, 3.0];
+        let bytes = vector_to_bytes(&embedding);
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(bytes_to_vector(&bytes), embedding);
+    }
+
     #[test]
     fn test_cosine_similarity() {
         let a = vec![1.0, 0.0, 0.0];
@@ -12,6 +12,7 @@ fn create_test_redis_config() -> RedisConfig {
         max_connections: 5,
         connection_timeout_secs: 5,
         default_ttl_secs: 3600,
+        vector_dimension: 384,
     }
 }
 
@@ -23,6 +24,7 @@ fn create_test_metadata() -> PostMetadata {
         date: Utc::now(),
         language: "en".to_string(),
         frozen: false,
+        metadata: serde_json::Map::new(),
     }
 }
 
@@ -33,6 +35,7 @@ fn create_test_cached_results() -> Vec<CachedResult> {
             post_id: "post_1".to_string(),
             title: "Test Post 1".to_string(),
             snippet: "This is a test post snippet".to_string(),
+            highlights: Vec::new(),
             score: 0.95,
             meta: create_test_metadata(),
             cached_at: Utc::now(),
@@ -41,6 +44,7 @@ fn create_test_cached_results() -> Vec<CachedResult> {
             post_id: "post_2".to_string(),
             title: "Test Post 2".to_string(),
             snippet: "Another test post snippet".to_string(),
+            highlights: Vec::new(),
             score: 0.87,
             meta: create_test_metadata(),
             cached_at: Utc::now(),
@@ -75,6 +79,7 @@ fn test_redis_config_validation() {
         max_connections: 10,
         connection_timeout_secs: 5,
         default_ttl_secs: 3600,
+        vector_dimension: 384,
     };
     
     assert!(valid_config.url.starts_with("redis://"));
@@ -87,6 +92,7 @@ fn test_redis_config_validation() {
         max_connections: 5,
         connection_timeout_secs: 10,
         default_ttl_secs: 1800,
+        vector_dimension: 384,
     };
     
     assert!(tls_config.url.starts_with("rediss://"));
@@ -273,7 +279,7 @@ async fn test_vector_search_placeholder() {
         let limit = 10;
         
         // Test vector search (currently returns empty results as it's a placeholder)
-        let search_result = cache_manager.vector_search(&query_embedding, limit).await;
+        let search_result = cache_manager.vector_search(&query_embedding, limit, None).await;
         assert!(search_result.is_ok(), "Vector search failed: {:?}", search_result);
         
         // Currently returns empty results since we don't have Redis Search configured
@@ -578,6 +584,7 @@ async fn test_connection_error_handling() {
         max_connections: 5,
         connection_timeout_secs: 1, // Short timeout for faster test
         default_ttl_secs: 3600,
+        vector_dimension: 384,
     };
     
     let result = CacheManager::new(invalid_config).await;
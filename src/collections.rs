@@ -0,0 +1,268 @@
+//! Named collections
+//!
+//! A collection is an isolated corpus with its own Postgres tables, Redis
+//! keys and vector indexes, and embedding model. The default collection uses
+//! the original tables and keys; further collections come from `COLLECTIONS`.
+//! Collections share the Postgres and Redis connection pools, and collections
+//! on the same embedding model share the loaded model.
+
+use crate::cache::CacheManager;
+use crate::config::{Config, MLConfig};
+use crate::database::DatabaseManager;
+use crate::error::{SearchError, SearchResult};
+use crate::gdpr::GdprService;
+use crate::ingestion::IngestionService;
+use crate::ml::{MLService, ModelConfig};
+use crate::observability::MetricsRegistry;
+use crate::rag::{AnswerService, ContextService, Generator};
+use crate::search::{ScoreNormalization, SearchService};
+use crate::types::{CollectionStats, DEFAULT_COLLECTION};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Services bound to one collection's storage and embedding model
+pub struct Collection {
+    /// Collection name
+    name: String,
+    /// Identifier of the collection's embedding model
+    embedding_model: String,
+    /// Dimension of the collection's vectors
+    embedding_dimension: usize,
+    /// Redis keys and indexes of the collection
+    cache_manager: Arc<CacheManager>,
+    /// Postgres tables of the collection
+    database_manager: Arc<DatabaseManager>,
+    /// Complete search service with ML integration
+    search_service: Arc<SearchService>,
+    /// Post ingestion service
+    ingestion_service: Arc<IngestionService>,
+    /// GDPR erasure service
+    gdpr_service: Arc<GdprService>,
+    /// Context block assembly service
+    context_service: Arc<ContextService>,
+    /// Answer generation service; `None` when no generator is configured
+    answer_service: Option<Arc<AnswerService>>,
+}
+
+impl Collection {
+    /// Collection name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Identifier of the collection's embedding model
+    pub fn embedding_model(&self) -> &str {
+        &self.embedding_model
+    }
+
+    /// Dimension of the collection's vectors
+    pub fn embedding_dimension(&self) -> usize {
+        self.embedding_dimension
+    }
+
+    /// Search service of the collection
+    pub fn search_service(&self) -> &Arc<SearchService> {
+        &self.search_service
+    }
+
+    /// Ingestion service of the collection
+    pub fn ingestion_service(&self) -> &Arc<IngestionService> {
+        &self.ingestion_service
+    }
+
+    /// GDPR erasure service of the collection
+    pub fn gdpr_service(&self) -> &Arc<GdprService> {
+        &self.gdpr_service
+    }
+
+    /// Context block service of the collection
+    pub fn context_service(&self) -> &Arc<ContextService> {
+        &self.context_service
+    }
+
+    /// Answer service of the collection, if a generator is configured
+    pub fn answer_service(&self) -> Option<&Arc<AnswerService>> {
+        self.answer_service.as_ref()
+    }
+
+    /// Post, passage and index counts of the collection
+    ///
+    /// Redis is optional, so index counts are reported as zero when it is
+    /// unavailable rather than failing the whole report.
+    pub async fn stats(&self) -> SearchResult<CollectionStats> {
+        let postgres_stats = self.database_manager.get_stats().await?;
+        let redis_stats = self.cache_manager.get_redis_stats().await.unwrap_or_else(|e| {
+            warn!("Redis stats unavailable for collection {}: {}", self.name, e);
+            Default::default()
+        });
+
+        Ok(CollectionStats {
+            name: self.name.clone(),
+            embedding_model: self.embedding_model.clone(),
+            embedding_dimension: self.embedding_dimension,
+            total_posts: postgres_stats.total_posts,
+            posts_with_embeddings: postgres_stats.posts_with_embeddings,
+            frozen_posts: postgres_stats.frozen_posts,
+            total_passages: postgres_stats.total_passages,
+            indexed_posts: redis_stats.indexed_posts,
+            indexed_passages: redis_stats.indexed_passages,
+        })
+    }
+}
+
+/// Every collection served by this instance, default collection first
+pub struct CollectionRegistry {
+    collections: Vec<Arc<Collection>>,
+}
+
+impl CollectionRegistry {
+    /// Connect to the stores and build the services of every configured collection
+    pub async fn new(config: &Config, metrics: &MetricsRegistry) -> SearchResult<Self> {
+        let cache_manager = Arc::new(CacheManager::new(config.redis.clone()).await?);
+        let database_manager = Arc::new(DatabaseManager::new(config.database.clone()).await?);
+        let generator = crate::rag::build_generator(&config.generator)?;
+        if let Some(generator) = &generator {
+            info!("Answer generation enabled with model {}", generator.model_id());
+        }
+
+        let mut ml_services: HashMap<String, Arc<MLService>> = HashMap::new();
+        let mut collections = Vec::with_capacity(config.collections.len() + 1);
+
+        let ml_service = load_ml_service(&mut ml_services, &config.ml).await?;
+        collections.push(Arc::new(
+            build_collection(
+                DEFAULT_COLLECTION,
+                &config.ml,
+                cache_manager.clone(),
+                database_manager.clone(),
+                ml_service,
+                generator.clone(),
+                config,
+                metrics,
+            )
+            .await?,
+        ));
+
+        for collection in &config.collections {
+            let ml_config = config.ml.for_collection(collection)?;
+            let ml_service = load_ml_service(&mut ml_services, &ml_config).await?;
+            let dimension = ml_config.embedding_dimension;
+
+            collections.push(Arc::new(
+                build_collection(
+                    &collection.name,
+                    &ml_config,
+                    Arc::new(cache_manager.for_collection(&collection.name, dimension).await),
+                    Arc::new(database_manager.for_collection(&collection.name, dimension).await?),
+                    ml_service,
+                    generator.clone(),
+                    config,
+                    metrics,
+                )
+                .await?,
+            ));
+            info!(
+                "Collection {} ready with model {} ({} dimensions)",
+                collection.name, ml_config.embedding_model, dimension
+            );
+        }
+
+        Ok(Self { collections })
+    }
+
+    /// Resolve a requested collection, the default one when none is named
+    pub fn get(&self, name: Option<&str>) -> SearchResult<Arc<Collection>> {
+        let name = name.unwrap_or(DEFAULT_COLLECTION);
+        self.collections
+            .iter()
+            .find(|collection| collection.name == name)
+            .cloned()
+            .ok_or_else(|| SearchError::InvalidRequest(format!("Unknown collection '{}'", name)))
+    }
+
+    /// The default collection
+    pub fn default_collection(&self) -> &Arc<Collection> {
+        &self.collections[0]
+    }
+
+    /// Every collection, default collection first
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<Collection>> {
+        self.collections.iter()
+    }
+
+    /// Stats of every collection
+    pub async fn stats(&self) -> SearchResult<Vec<CollectionStats>> {
+        let mut stats = Vec::with_capacity(self.collections.len());
+        for collection in &self.collections {
+            stats.push(collection.stats().await?);
+        }
+        Ok(stats)
+    }
+}
+
+/// Load the embedding model of a collection, reusing one already loaded for another collection
+async fn load_ml_service(
+    ml_services: &mut HashMap<String, Arc<MLService>>,
+    ml_config: &MLConfig,
+) -> SearchResult<Arc<MLService>> {
+    if let Some(ml_service) = ml_services.get(&ml_config.embedding_model) {
+        return Ok(ml_service.clone());
+    }
+
+    let ml_service = Arc::new(MLService::new_with_config(ModelConfig::from_ml_config(ml_config)?).await?);
+    ml_services.insert(ml_config.embedding_model.clone(), ml_service.clone());
+    Ok(ml_service)
+}
+
+/// Build the services of one collection on top of its stores and model
+#[allow(clippy::too_many_arguments)]
+async fn build_collection(
+    name: &str,
+    ml_config: &MLConfig,
+    cache_manager: Arc<CacheManager>,
+    database_manager: Arc<DatabaseManager>,
+    ml_service: Arc<MLService>,
+    generator: Option<Arc<dyn Generator>>,
+    config: &Config,
+    metrics: &MetricsRegistry,
+) -> SearchResult<Collection> {
+    let ingestion_service = Arc::new(IngestionService::new(
+        ml_service.clone(),
+        cache_manager.clone(),
+        database_manager.clone(),
+    ));
+    let gdpr_service = Arc::new(GdprService::new(cache_manager.clone(), database_manager.clone()));
+
+    let mut search_service = SearchService::new(cache_manager.clone(), database_manager.clone(), ml_service.clone())
+        .await?
+        .with_metrics(metrics.metrics.clone())
+        .with_collection(name)
+        .with_score_normalization(ScoreNormalization::from_name(&config.server.score_normalization)?);
+
+    match &config.server.cursor_secret {
+        Some(secret) => search_service = search_service.with_cursor_secret(secret.as_bytes()),
+        None => warn!(
+            "CURSOR_SECRET is not set; pagination cursors of collection {} only work on this instance until it restarts",
+            name
+        ),
+    }
+    let search_service = Arc::new(search_service);
+
+    let context_service = Arc::new(ContextService::new(search_service.clone(), ml_service));
+    let answer_service = generator
+        .map(|generator| Arc::new(AnswerService::new(context_service.clone(), generator, &config.generator)));
+
+    Ok(Collection {
+        name: name.to_string(),
+        embedding_model: ml_config.embedding_model.clone(),
+        embedding_dimension: ml_config.embedding_dimension,
+        cache_manager,
+        database_manager,
+        search_service,
+        ingestion_service,
+        gdpr_service,
+        context_service,
+        answer_service,
+    })
+}
@@ -12,6 +12,10 @@ pub struct Config {
     pub redis: RedisConfig,
     /// ML model configuration
     pub ml: MLConfig,
+    /// Answer generation configuration
+    pub generator: GeneratorConfig,
+    /// Collections served next to the default collection
+    pub collections: Vec<CollectionConfig>,
 }
 
 /// Server configuration
@@ -21,12 +25,23 @@ pub struct ServerConfig {
     pub host: String,
     /// Server port
     pub port: u16,
+    /// gRPC server port
+    pub grpc_port: u16,
     /// Request timeout in milliseconds
     pub request_timeout_ms: u64,
     /// Rate limit (requests per minute)
     pub rate_limit_per_minute: u64,
     /// Maximum request body size in bytes
     pub max_request_size: usize,
+    /// Maximum request body size of batch searches in bytes
+    pub max_batch_request_size: usize,
+    /// Secret for signing pagination cursors; random per process when unset
+    pub cursor_secret: Option<String>,
+    /// Normalization comparing retrieval sources' scores when merging:
+    /// "none", "min_max", "z_score" or "rank"
+    pub score_normalization: String,
+    /// Queries of a batch search that run at the same time
+    pub batch_search_concurrency: usize,
 }
 
 /// Database configuration
@@ -40,6 +55,8 @@ pub struct DatabaseConfig {
     pub max_connections: u32,
     /// Connection timeout in seconds
     pub connection_timeout_secs: u64,
+    /// Dimension of the pgvector embedding column (follows the active embedding model)
+    pub vector_dimension: usize,
 }
 
 /// Redis configuration
@@ -53,21 +70,120 @@ pub struct RedisConfig {
     pub connection_timeout_secs: u64,
     /// Key expiration time in seconds
     pub default_ttl_secs: u64,
+    /// Dimension of cached vectors (follows the active embedding model)
+    pub vector_dimension: usize,
 }
 
 /// ML model configuration
 #[derive(Debug, Clone)]
 pub struct MLConfig {
+    /// Identifier of the embedding model (e.g. all-MiniLM-L6-v2, bert-base-nli-mean-tokens)
+    pub embedding_model: String,
     /// Path to the embedding model
     pub embedding_model_path: String,
+    /// Path to the embedding model's tokenizer.json
+    pub embedding_tokenizer_path: String,
     /// Path to the reranking model
     pub rerank_model_path: String,
+    /// Path to the reranking model's tokenizer.json
+    pub rerank_tokenizer_path: String,
     /// Maximum sequence length for embeddings
     pub max_sequence_length: usize,
     /// Embedding dimension
     pub embedding_dimension: usize,
+    /// Expected SHA256 of the embedding model; unset skips verification
+    pub embedding_model_sha256: Option<String>,
+    /// Expected SHA256 of the embedding tokenizer; unset skips verification
+    pub embedding_tokenizer_sha256: Option<String>,
+    /// Expected SHA256 of the reranking model; unset skips verification
+    pub rerank_model_sha256: Option<String>,
+    /// Expected SHA256 of the reranking tokenizer; unset skips verification
+    pub rerank_tokenizer_sha256: Option<String>,
 }
 
+/// Answer generation configuration
+#[derive(Debug, Clone)]
+pub struct GeneratorConfig {
+    /// Backend: "none" (answers disabled), "openai" (OpenAI-compatible API) or "stub"
+    pub backend: String,
+    /// Base URL of the OpenAI-compatible API
+    pub base_url: String,
+    /// API key sent as a bearer token
+    pub api_key: Option<String>,
+    /// Model name passed to the API
+    pub model: String,
+    /// Generation timeout in milliseconds, also bounding a whole streamed answer
+    pub timeout_ms: u64,
+    /// Default context budget in embedding-tokenizer tokens
+    pub max_context_tokens: u32,
+    /// Default maximum answer length in tokens
+    pub max_answer_tokens: u32,
+}
+
+/// Named collection with its own tables, Redis keys and embedding model
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionConfig {
+    /// Collection name used in requests, Postgres table names and Redis keys
+    pub name: String,
+    /// Identifier of the collection's embedding model
+    pub embedding_model: String,
+}
+
+impl CollectionConfig {
+    /// Parse a `COLLECTIONS` value of comma-separated `name` or `name:model` entries
+    ///
+    /// Collections without a model use `default_model`.
+    pub fn parse_list(value: &str, default_model: &str) -> Vec<CollectionConfig> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (name, model) = entry.split_once(':').unwrap_or((entry, default_model));
+                CollectionConfig {
+                    name: name.trim().to_string(),
+                    embedding_model: model.trim().to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl MLConfig {
+    /// Model configuration of a collection
+    ///
+    /// A collection with another embedding model loads it from the directory
+    /// of the default model, under the model's default file names, and skips
+    /// hash verification for the model and its tokenizer.
+    pub fn for_collection(&self, collection: &CollectionConfig) -> SearchResult<MLConfig> {
+        if collection.embedding_model == self.embedding_model {
+            return Ok(self.clone());
+        }
+
+        let embedding_model = crate::ml::EmbeddingModel::from_model_id(&collection.embedding_model)?;
+        let model_dir = std::path::Path::new(&self.embedding_model_path)
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("models"));
+
+        Ok(MLConfig {
+            embedding_model: embedding_model.model_id().to_string(),
+            embedding_model_path: model_dir.join(embedding_model.model_filename()).to_string_lossy().into_owned(),
+            embedding_tokenizer_path: model_dir
+                .join(embedding_model.tokenizer_filename())
+                .to_string_lossy()
+                .into_owned(),
+            embedding_dimension: embedding_model.dimension(),
+            // The configured hashes describe the default model's files
+            embedding_model_sha256: None,
+            embedding_tokenizer_sha256: None,
+            ..self.clone()
+        })
+    }
+}
+
+/// Supported answer generation backends
+pub const GENERATOR_BACKENDS: [&str; 3] = ["none", "openai", "stub"];
+
 impl Config {
     /// Load configuration from environment variables
     pub fn from_env() -> SearchResult<Self> {
@@ -76,6 +192,35 @@ impl Config {
             tracing::warn!("Could not load .env file: {}", e);
         }
 
+        // The embedding model decides the default file names and vector dimension
+        let embedding_model = crate::ml::EmbeddingModel::from_model_id(
+            &env::var("EMBEDDING_MODEL").unwrap_or_else(|_| "all-MiniLM-L6-v2".to_string()),
+        )?;
+
+        let ml = MLConfig {
+            embedding_model: embedding_model.model_id().to_string(),
+            embedding_model_path: env::var("EMBEDDING_MODEL_PATH")
+                .unwrap_or_else(|_| format!("models/{}", embedding_model.model_filename())),
+            embedding_tokenizer_path: env::var("EMBEDDING_TOKENIZER_PATH")
+                .unwrap_or_else(|_| format!("models/{}", embedding_model.tokenizer_filename())),
+            rerank_model_path: env::var("RERANK_MODEL_PATH")
+                .unwrap_or_else(|_| "models/ms-marco-MiniLM-L-6-v2.onnx".to_string()),
+            rerank_tokenizer_path: env::var("RERANK_TOKENIZER_PATH")
+                .unwrap_or_else(|_| "models/ms-marco-MiniLM-L-6-v2.tokenizer.json".to_string()),
+            max_sequence_length: env::var("MAX_SEQUENCE_LENGTH")
+                .unwrap_or_else(|_| "512".to_string())
+                .parse()
+                .map_err(|e| SearchError::ConfigError(format!("Invalid MAX_SEQUENCE_LENGTH: {}", e)))?,
+            embedding_dimension: env::var("EMBEDDING_DIMENSION")
+                .unwrap_or_else(|_| embedding_model.dimension().to_string())
+                .parse()
+                .map_err(|e| SearchError::ConfigError(format!("Invalid EMBEDDING_DIMENSION: {}", e)))?,
+            embedding_model_sha256: env::var("EMBEDDING_MODEL_SHA256").ok().filter(|hash| !hash.is_empty()),
+            embedding_tokenizer_sha256: env::var("EMBEDDING_TOKENIZER_SHA256").ok().filter(|hash| !hash.is_empty()),
+            rerank_model_sha256: env::var("RERANK_MODEL_SHA256").ok().filter(|hash| !hash.is_empty()),
+            rerank_tokenizer_sha256: env::var("RERANK_TOKENIZER_SHA256").ok().filter(|hash| !hash.is_empty()),
+        };
+
         let config = Config {
             server: ServerConfig {
                 host: env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
@@ -83,6 +228,10 @@ impl Config {
                     .unwrap_or_else(|_| "8080".to_string())
                     .parse()
                     .map_err(|e| SearchError::ConfigError(format!("Invalid SERVER_PORT: {}", e)))?,
+                grpc_port: env::var("GRPC_PORT")
+                    .unwrap_or_else(|_| "50051".to_string())
+                    .parse()
+                    .map_err(|e| SearchError::ConfigError(format!("Invalid GRPC_PORT: {}", e)))?,
                 request_timeout_ms: env::var("REQUEST_TIMEOUT_MS")
                     .unwrap_or_else(|_| "500".to_string())
                     .parse()
@@ -95,6 +244,16 @@ impl Config {
                     .unwrap_or_else(|_| "32768".to_string()) // 32KB
                     .parse()
                     .map_err(|e| SearchError::ConfigError(format!("Invalid MAX_REQUEST_SIZE: {}", e)))?,
+                max_batch_request_size: env::var("MAX_BATCH_REQUEST_SIZE")
+                    .unwrap_or_else(|_| "1048576".to_string()) // 1MB
+                    .parse()
+                    .map_err(|e| SearchError::ConfigError(format!("Invalid MAX_BATCH_REQUEST_SIZE: {}", e)))?,
+                cursor_secret: env::var("CURSOR_SECRET").ok().filter(|secret| !secret.is_empty()),
+                score_normalization: env::var("SCORE_NORMALIZATION").unwrap_or_else(|_| "none".to_string()),
+                batch_search_concurrency: env::var("BATCH_SEARCH_CONCURRENCY")
+                    .unwrap_or_else(|_| "8".to_string())
+                    .parse()
+                    .map_err(|e| SearchError::ConfigError(format!("Invalid BATCH_SEARCH_CONCURRENCY: {}", e)))?,
             },
             database: DatabaseConfig {
                 supabase_url: env::var("SUPABASE_URL")
@@ -109,6 +268,7 @@ impl Config {
                     .unwrap_or_else(|_| "30".to_string())
                     .parse()
                     .map_err(|e| SearchError::ConfigError(format!("Invalid DB_CONNECTION_TIMEOUT_SECS: {}", e)))?,
+                vector_dimension: ml.embedding_dimension,
             },
             redis: RedisConfig {
                 url: env::var("REDIS_URL")
@@ -125,21 +285,31 @@ impl Config {
                     .unwrap_or_else(|_| "3600".to_string()) // 1 hour
                     .parse()
                     .map_err(|e| SearchError::ConfigError(format!("Invalid REDIS_DEFAULT_TTL_SECS: {}", e)))?,
+                vector_dimension: ml.embedding_dimension,
             },
-            ml: MLConfig {
-                embedding_model_path: env::var("EMBEDDING_MODEL_PATH")
-                    .unwrap_or_else(|_| "models/all-MiniLM-L6-v2.onnx".to_string()),
-                rerank_model_path: env::var("RERANK_MODEL_PATH")
-                    .unwrap_or_else(|_| "models/ms-marco-MiniLM-L-6-v2.onnx".to_string()),
-                max_sequence_length: env::var("MAX_SEQUENCE_LENGTH")
-                    .unwrap_or_else(|_| "512".to_string())
+            ml,
+            generator: GeneratorConfig {
+                backend: env::var("GENERATOR_BACKEND").unwrap_or_else(|_| "none".to_string()),
+                base_url: env::var("GENERATOR_BASE_URL").unwrap_or_else(|_| "https://api.openai.com/v1".to_string()),
+                api_key: env::var("GENERATOR_API_KEY").ok().filter(|key| !key.is_empty()),
+                model: env::var("GENERATOR_MODEL").unwrap_or_else(|_| "gpt-4o-mini".to_string()),
+                timeout_ms: env::var("GENERATOR_TIMEOUT_MS")
+                    .unwrap_or_else(|_| "30000".to_string())
                     .parse()
-                    .map_err(|e| SearchError::ConfigError(format!("Invalid MAX_SEQUENCE_LENGTH: {}", e)))?,
-                embedding_dimension: env::var("EMBEDDING_DIMENSION")
-                    .unwrap_or_else(|_| "384".to_string())
+                    .map_err(|e| SearchError::ConfigError(format!("Invalid GENERATOR_TIMEOUT_MS: {}", e)))?,
+                max_context_tokens: env::var("ANSWER_MAX_CONTEXT_TOKENS")
+                    .unwrap_or_else(|_| "2000".to_string())
                     .parse()
-                    .map_err(|e| SearchError::ConfigError(format!("Invalid EMBEDDING_DIMENSION: {}", e)))?,
+                    .map_err(|e| SearchError::ConfigError(format!("Invalid ANSWER_MAX_CONTEXT_TOKENS: {}", e)))?,
+                max_answer_tokens: env::var("ANSWER_MAX_TOKENS")
+                    .unwrap_or_else(|_| "512".to_string())
+                    .parse()
+                    .map_err(|e| SearchError::ConfigError(format!("Invalid ANSWER_MAX_TOKENS: {}", e)))?,
             },
+            collections: CollectionConfig::parse_list(
+                &env::var("COLLECTIONS").unwrap_or_default(),
+                embedding_model.model_id(),
+            ),
         };
 
         // Validate configuration
@@ -155,10 +325,24 @@ impl Config {
             return Err(SearchError::ConfigError("Server port cannot be 0".to_string()));
         }
 
+        if self.server.grpc_port == 0 || self.server.grpc_port == self.server.port {
+            return Err(SearchError::ConfigError(
+                "GRPC_PORT must be non-zero and differ from SERVER_PORT".to_string(),
+            ));
+        }
+
         if self.server.request_timeout_ms == 0 {
             return Err(SearchError::ConfigError("Request timeout must be greater than 0".to_string()));
         }
 
+        if self.server.batch_search_concurrency == 0 {
+            return Err(SearchError::ConfigError("BATCH_SEARCH_CONCURRENCY must be greater than 0".to_string()));
+        }
+
+        if self.server.cursor_secret.as_ref().is_some_and(|secret| secret.len() < 32) {
+            return Err(SearchError::ConfigError("CURSOR_SECRET must be at least 32 bytes".to_string()));
+        }
+
         // Validate database config
         if !self.database.supabase_url.starts_with("https://") {
             return Err(SearchError::ConfigError("SUPABASE_URL must start with https://".to_string()));
@@ -182,6 +366,83 @@ impl Config {
             return Err(SearchError::ConfigError("Max sequence length must be greater than 0".to_string()));
         }
 
+        let embedding_model = crate::ml::EmbeddingModel::from_model_id(&self.ml.embedding_model)?;
+        if embedding_model.dimension() != self.ml.embedding_dimension {
+            return Err(SearchError::ConfigError(format!(
+                "EMBEDDING_DIMENSION {} does not match model {} ({} dimensions)",
+                self.ml.embedding_dimension,
+                embedding_model.model_id(),
+                embedding_model.dimension()
+            )));
+        }
+
+        if self.database.vector_dimension != self.ml.embedding_dimension
+            || self.redis.vector_dimension != self.ml.embedding_dimension
+        {
+            return Err(SearchError::ConfigError(
+                "Database and Redis vector dimensions must match the embedding model".to_string()
+            ));
+        }
+
+        // Validate generator config
+        if !GENERATOR_BACKENDS.contains(&self.generator.backend.as_str()) {
+            return Err(SearchError::ConfigError(format!(
+                "Unsupported GENERATOR_BACKEND '{}', expected one of: {}",
+                self.generator.backend,
+                GENERATOR_BACKENDS.join(", ")
+            )));
+        }
+
+        if self.generator.backend == "openai"
+            && !self.generator.base_url.starts_with("https://")
+            && !self.generator.base_url.starts_with("http://")
+        {
+            return Err(SearchError::ConfigError("GENERATOR_BASE_URL must be an http(s) URL".to_string()));
+        }
+
+        if self.generator.timeout_ms == 0 {
+            return Err(SearchError::ConfigError("Generator timeout must be greater than 0".to_string()));
+        }
+
+        if self.generator.max_context_tokens == 0 || self.generator.max_context_tokens > crate::types::MAX_CONTEXT_TOKENS {
+            return Err(SearchError::ConfigError(format!(
+                "ANSWER_MAX_CONTEXT_TOKENS must be between 1 and {}",
+                crate::types::MAX_CONTEXT_TOKENS
+            )));
+        }
+
+        if self.generator.max_answer_tokens == 0 || self.generator.max_answer_tokens > crate::types::MAX_ANSWER_TOKENS {
+            return Err(SearchError::ConfigError(format!(
+                "ANSWER_MAX_TOKENS must be between 1 and {}",
+                crate::types::MAX_ANSWER_TOKENS
+            )));
+        }
+
+        // Validate collections
+        let mut names = std::collections::HashSet::new();
+        for collection in &self.collections {
+            if !crate::types::is_valid_collection_name(&collection.name) {
+                return Err(SearchError::ConfigError(format!(
+                    "Invalid collection name '{}': use up to {} lowercase letters, digits and underscores, starting with a letter",
+                    collection.name,
+                    crate::types::MAX_COLLECTION_NAME_LENGTH
+                )));
+            }
+
+            if collection.name == crate::types::DEFAULT_COLLECTION {
+                return Err(SearchError::ConfigError(format!(
+                    "Collection name '{}' is reserved for the default collection",
+                    collection.name
+                )));
+            }
+
+            if !names.insert(collection.name.as_str()) {
+                return Err(SearchError::ConfigError(format!("Collection '{}' is defined twice", collection.name)));
+            }
+
+            crate::ml::EmbeddingModel::from_model_id(&collection.embedding_model)?;
+        }
+
         Ok(())
     }
 }
@@ -192,28 +453,52 @@ impl Default for Config {
             server: ServerConfig {
                 host: "0.0.0.0".to_string(),
                 port: 8080,
+                grpc_port: 50051,
                 request_timeout_ms: 500,
                 rate_limit_per_minute: 100,
                 max_request_size: 32768, // 32KB
+                max_batch_request_size: 1048576, // 1MB
+                cursor_secret: None,
+                score_normalization: "none".to_string(),
+                batch_search_concurrency: 8,
             },
             database: DatabaseConfig {
                 supabase_url: "".to_string(),
                 supabase_service_key: "".to_string(),
                 max_connections: 10,
                 connection_timeout_secs: 30,
+                vector_dimension: 384,
             },
             redis: RedisConfig {
                 url: "".to_string(),
                 max_connections: 10,
                 connection_timeout_secs: 5,
                 default_ttl_secs: 3600, // 1 hour
+                vector_dimension: 384,
             },
             ml: MLConfig {
+                embedding_model: "all-MiniLM-L6-v2".to_string(),
                 embedding_model_path: "models/all-MiniLM-L6-v2.onnx".to_string(),
+                embedding_tokenizer_path: "models/all-MiniLM-L6-v2.tokenizer.json".to_string(),
                 rerank_model_path: "models/ms-marco-MiniLM-L-6-v2.onnx".to_string(),
+                rerank_tokenizer_path: "models/ms-marco-MiniLM-L-6-v2.tokenizer.json".to_string(),
                 max_sequence_length: 512,
                 embedding_dimension: 384,
+                embedding_model_sha256: None,
+                embedding_tokenizer_sha256: None,
+                rerank_model_sha256: None,
+                rerank_tokenizer_sha256: None,
+            },
+            generator: GeneratorConfig {
+                backend: "none".to_string(),
+                base_url: "https://api.openai.com/v1".to_string(),
+                api_key: None,
+                model: "gpt-4o-mini".to_string(),
+                timeout_ms: 30000,
+                max_context_tokens: 2000,
+                max_answer_tokens: 512,
             },
+            collections: Vec::new(),
         }
     }
 }
@@ -237,6 +522,22 @@ mod tests {
         
         // Should pass validation
         assert!(config.validate().is_ok());
+
+        // Short cursor secrets are rejected
+        config.server.cursor_secret = Some("too-short".to_string());
+        assert!(config.validate().is_err());
+        config.server.cursor_secret = Some("x".repeat(32));
+        assert!(config.validate().is_ok());
+
+        // Batch searches need at least one query in flight
+        config.server.batch_search_concurrency = 0;
+        assert!(config.validate().is_err());
+        config.server.batch_search_concurrency = 8;
+        assert!(config.validate().is_ok());
+
+        // gRPC and HTTP cannot share a port
+        config.server.grpc_port = config.server.port;
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -258,12 +559,109 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_embedding_dimension_must_match_model() {
+        let mut config = Config::default();
+        config.database.supabase_url = "https://example.supabase.co".to_string();
+        config.database.supabase_service_key = "test-key".to_string();
+        config.redis.url = "redis://localhost:6379".to_string();
+
+        // Switching to the 768-d BERT model without updating the dimension is rejected
+        config.ml.embedding_model = "bert-base-nli-mean-tokens".to_string();
+        assert!(config.validate().is_err());
+
+        config.ml.embedding_dimension = 768;
+        config.database.vector_dimension = 768;
+        config.redis.vector_dimension = 768;
+        assert!(config.validate().is_ok());
+
+        config.ml.embedding_model = "unknown-model".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_generator_config_validation() {
+        let mut config = Config::default();
+        config.database.supabase_url = "https://example.supabase.co".to_string();
+        config.database.supabase_service_key = "test-key".to_string();
+        config.redis.url = "redis://localhost:6379".to_string();
+        assert!(config.validate().is_ok());
+
+        config.generator.backend = "gpt".to_string();
+        assert!(config.validate().is_err());
+
+        config.generator.backend = "openai".to_string();
+        config.generator.base_url = "localhost:8000/v1".to_string();
+        assert!(config.validate().is_err());
+        config.generator.base_url = "http://localhost:8000/v1".to_string();
+        assert!(config.validate().is_ok());
+
+        config.generator.max_context_tokens = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_collection_config() {
+        let collections = CollectionConfig::parse_list(" docs, support:bert-base-nli-mean-tokens ,", "all-MiniLM-L6-v2");
+        assert_eq!(collections.len(), 2);
+        assert_eq!(collections[0].name, "docs");
+        assert_eq!(collections[0].embedding_model, "all-MiniLM-L6-v2");
+        assert_eq!(collections[1].name, "support");
+        assert_eq!(collections[1].embedding_model, "bert-base-nli-mean-tokens");
+        assert!(CollectionConfig::parse_list("", "all-MiniLM-L6-v2").is_empty());
+
+        let mut config = Config::default();
+        config.database.supabase_url = "https://example.supabase.co".to_string();
+        config.database.supabase_service_key = "test-key".to_string();
+        config.redis.url = "redis://localhost:6379".to_string();
+        config.collections = collections;
+        assert!(config.validate().is_ok());
+
+        // Per-collection models live next to the default model
+        let ml = config.ml.for_collection(&config.collections[1]).unwrap();
+        assert_eq!(ml.embedding_dimension, 768);
+        assert_eq!(ml.embedding_model_path, "models/bert-base-nli-mean-tokens.onnx");
+
+        // Hashes of the default model never apply to another model's files
+        let mut hashed = config.ml.clone();
+        hashed.embedding_model_sha256 = Some("ab12".to_string());
+        hashed.rerank_model_sha256 = Some("cd34".to_string());
+        let ml = hashed.for_collection(&config.collections[1]).unwrap();
+        assert!(ml.embedding_model_sha256.is_none());
+        assert_eq!(ml.rerank_model_sha256.as_deref(), Some("cd34"));
+        assert_eq!(hashed.for_collection(&config.collections[0]).unwrap().embedding_model_sha256.as_deref(), Some("ab12"));
+        assert_eq!(config.ml.for_collection(&config.collections[0]).unwrap().embedding_model_path, config.ml.embedding_model_path);
+
+        for name in ["default", "Docs", "1docs", "docs-v2", "docs"] {
+            let mut invalid = config.clone();
+            invalid.collections.push(CollectionConfig {
+                name: name.to_string(),
+                embedding_model: "all-MiniLM-L6-v2".to_string(),
+            });
+            assert!(invalid.validate().is_err(), "collection '{}' should be rejected", name);
+        }
+
+        let mut reserved = config.clone();
+        reserved.collections.push(CollectionConfig {
+            name: "default".to_string(),
+            embedding_model: "all-MiniLM-L6-v2".to_string(),
+        });
+        assert!(reserved.validate().unwrap_err().to_string().contains("reserved"));
+
+        config.collections[0].embedding_model = "unknown-model".to_string();
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_server_config_defaults() {
         let config = Config::default();
         assert_eq!(config.server.host, "0.0.0.0");
         assert_eq!(config.server.port, 8080);
+        assert_eq!(config.server.grpc_port, 50051);
         assert_eq!(config.server.request_timeout_ms, 500);
         assert_eq!(config.server.rate_limit_per_minute, 100);
+        assert_eq!(config.server.score_normalization, "none");
+        assert_eq!(config.server.batch_search_concurrency, 8);
+        assert_eq!(config.server.max_batch_request_size, 1048576);
     }
 }
\ No newline at end of file
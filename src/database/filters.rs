@@ -0,0 +1,400 @@
+//! SQL compilation of search filters
+//!
+//! Filters are compiled into the `WHERE` clause of the retrieval queries so
+//! that Postgres returns `limit` matching posts instead of `limit` posts that
+//! are filtered afterwards. Every value is bound as a query parameter.
+
+use crate::types::{MetadataFilter, SearchFilters};
+use serde_json::json;
+use tokio_postgres::types::ToSql;
+
+/// Query parameter owned by a compiled filter
+pub type SqlParam = Box<dyn ToSql + Sync + Send>;
+
+/// `WHERE` clause fragments and their bound parameters
+pub struct FilterSql {
+    /// Conditions joined with AND
+    clauses: Vec<String>,
+    /// Parameters referenced by the clauses
+    params: Vec<SqlParam>,
+    /// Placeholder index of the first filter parameter
+    first_param: usize,
+}
+
+impl FilterSql {
+    /// Start a filter whose placeholders follow the query's own `param_count` parameters
+    pub fn new(param_count: usize) -> Self {
+        Self {
+            clauses: Vec::new(),
+            params: Vec::new(),
+            first_param: param_count + 1,
+        }
+    }
+
+    /// Bind a parameter and return its placeholder (e.g. `$3`)
+    pub fn bind<T: ToSql + Sync + Send + 'static>(&mut self, value: T) -> String {
+        self.params.push(Box::new(value));
+        format!("${}", self.first_param + self.params.len() - 1)
+    }
+
+    /// Add a condition that must hold
+    pub fn push(&mut self, clause: impl Into<String>) {
+        self.clauses.push(clause.into());
+    }
+
+    /// Conditions prefixed with `AND`, ready to append to an existing `WHERE`
+    pub fn and_clause(&self) -> String {
+        self.clauses
+            .iter()
+            .map(|clause| format!(" AND {}", clause))
+            .collect()
+    }
+
+    /// Bound parameters in placeholder order
+    pub fn params(&self) -> impl Iterator<Item = &(dyn ToSql + Sync)> {
+        self.params.iter().map(|param| param.as_ref() as &(dyn ToSql + Sync))
+    }
+}
+
+/// Lowercased host of `posts.url`, skipping the scheme and any userinfo
+const URL_HOST_SQL: &str =
+    "lower(substring(url from '^[a-zA-Z][a-zA-Z0-9+.-]*://(?:[^/?#@]*@)?([^/?#:]+)'))";
+
+/// Compile search filters into SQL conditions on the `posts` table
+///
+/// Without a frozen condition anywhere in the expression frozen posts are
+/// excluded, matching the Redis KNN pre-filter; `frozen: true` selects only
+/// frozen posts. Date and author conditions are plain comparisons so the
+/// `idx_posts_date_gmt` and `idx_posts_author` indexes stay usable.
+pub fn compile_filters(filters: Option<&SearchFilters>, param_count: usize) -> FilterSql {
+    let mut sql = FilterSql::new(param_count);
+
+    if let Some(filters) = filters {
+        for condition in compile_conditions(filters, &mut sql) {
+            sql.push(condition);
+        }
+    }
+
+    if !filters.is_some_and(SearchFilters::mentions_frozen) {
+        sql.push("NOT frozen");
+    }
+
+    sql
+}
+
+/// Conditions of one filter node, all of which must hold
+fn compile_conditions(filters: &SearchFilters, sql: &mut FilterSql) -> Vec<String> {
+    let mut conditions = Vec::new();
+
+    if let Some(language) = &filters.language {
+        let placeholder = sql.bind(language.to_lowercase());
+        conditions.push(format!("lower(language) = {}", placeholder));
+    }
+
+    if let Some(frozen) = filters.frozen {
+        let placeholder = sql.bind(frozen);
+        conditions.push(format!("frozen = {}", placeholder));
+    }
+
+    if let Some(after) = filters.date_after {
+        let placeholder = sql.bind(after);
+        conditions.push(format!("date_gmt >= {}", placeholder));
+    }
+
+    if let Some(before) = filters.date_before {
+        let placeholder = sql.bind(before);
+        conditions.push(format!("date_gmt < {}", placeholder));
+    }
+
+    if let Some(authors) = &filters.authors {
+        let placeholder = sql.bind(authors.clone());
+        conditions.push(format!("author_name = ANY({})", placeholder));
+    }
+
+    if let Some(authors) = &filters.exclude_authors {
+        let placeholder = sql.bind(authors.clone());
+        conditions.push(format!("author_name <> ALL({})", placeholder));
+    }
+
+    if let Some(domains) = &filters.url_domains {
+        let subdomains: Vec<String> = domains
+            .iter()
+            .map(|domain| format!("%.{}", escape_like(domain)))
+            .collect();
+        let exact = sql.bind(domains.clone());
+        let suffix = sql.bind(subdomains);
+        conditions.push(format!(
+            "({host} = ANY({exact}) OR {host} LIKE ANY({suffix}))",
+            host = URL_HOST_SQL,
+            exact = exact,
+            suffix = suffix
+        ));
+    }
+
+    if let Some(prefix) = &filters.url_prefix {
+        let placeholder = sql.bind(prefix.clone());
+        conditions.push(format!("starts_with(url, {})", placeholder));
+    }
+
+    for condition in filters.metadata.iter().flatten() {
+        conditions.push(compile_metadata_condition(condition, sql));
+    }
+
+    for nested in filters.and.iter().flatten() {
+        conditions.extend(compile_conditions(nested, sql));
+    }
+
+    if let Some(alternatives) = &filters.or {
+        let alternatives: Vec<String> = alternatives
+            .iter()
+            .map(|nested| group(compile_conditions(nested, sql)))
+            .collect();
+        conditions.push(format!("({})", alternatives.join(" OR ")));
+    }
+
+    if let Some(nested) = &filters.not {
+        conditions.push(format!("NOT {}", group(compile_conditions(nested, sql))));
+    }
+
+    conditions
+}
+
+/// Compile one custom metadata condition
+///
+/// Equality, `in` and `contains` are JSONB containment tests so the
+/// `idx_posts_metadata` GIN index applies. Range comparisons only match
+/// numeric values; other values make the condition false instead of failing
+/// the cast. Dotted keys are paths into nested objects.
+fn compile_metadata_condition(condition: &MetadataFilter, sql: &mut FilterSql) -> String {
+    let path: Vec<&str> = condition.path().collect();
+    let mut parts = Vec::new();
+
+    if let Some(value) = &condition.eq {
+        parts.push(format!("metadata @> {}", sql.bind(nest(&path, value.clone()))));
+    }
+
+    if let Some(values) = &condition.r#in {
+        let alternatives: Vec<String> = values
+            .iter()
+            .map(|value| format!("metadata @> {}", sql.bind(nest(&path, value.clone()))))
+            .collect();
+        parts.push(format!("({})", alternatives.join(" OR ")));
+    }
+
+    if let Some(value) = &condition.contains {
+        parts.push(format!("metadata @> {}", sql.bind(nest(&path, json!([value])))));
+    }
+
+    if condition.has_range() {
+        // A single key keeps the plain `->` operators; paths use `#>` with a text array
+        let (json_op, text_op, key_placeholder) = if path.len() == 1 {
+            ("->", "->>", format!("{}::text", sql.bind(condition.key.clone())))
+        } else {
+            let keys: Vec<String> = path.iter().map(|key| key.to_string()).collect();
+            ("#>", "#>>", format!("{}::text[]", sql.bind(keys)))
+        };
+        let bounds = [(">", condition.gt), (">=", condition.gte), ("<", condition.lt), ("<=", condition.lte)];
+        let comparisons: Vec<String> = bounds
+            .into_iter()
+            .filter_map(|(operator, bound)| bound.map(|bound| (operator, bound)))
+            .map(|(operator, bound)| {
+                format!("(metadata {} {})::float8 {} {}", text_op, key_placeholder, operator, sql.bind(bound))
+            })
+            .collect();
+        parts.push(format!(
+            "CASE WHEN jsonb_typeof(metadata {} {}) = 'number' THEN {} ELSE false END",
+            json_op,
+            key_placeholder,
+            comparisons.join(" AND ")
+        ));
+    }
+
+    if parts.len() == 1 {
+        parts.remove(0)
+    } else {
+        group(parts)
+    }
+}
+
+/// JSON object holding `value` at `path`, for containment tests
+fn nest(path: &[&str], value: serde_json::Value) -> serde_json::Value {
+    path.iter().rev().fold(value, |value, key| {
+        let mut object = serde_json::Map::new();
+        object.insert(key.to_string(), value);
+        serde_json::Value::Object(object)
+    })
+}
+
+/// Parenthesized conjunction of conditions
+fn group(conditions: Vec<String>) -> String {
+    if conditions.is_empty() {
+        "TRUE".to_string()
+    } else {
+        format!("({})", conditions.join(" AND "))
+    }
+}
+
+/// Escape LIKE wildcards in a literal value
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_excludes_frozen() {
+        let sql = compile_filters(None, 2);
+        assert_eq!(sql.and_clause(), " AND NOT frozen");
+        assert_eq!(sql.params().count(), 0);
+    }
+
+    #[test]
+    fn test_filters_are_bound_after_query_params() {
+        let filters = SearchFilters {
+            language: Some("ES".to_string()),
+            frozen: Some(true),
+            ..Default::default()
+        };
+
+        let sql = compile_filters(Some(&filters), 2);
+        assert_eq!(sql.and_clause(), " AND lower(language) = $3 AND frozen = $4");
+        assert_eq!(sql.params().count(), 2);
+    }
+
+    #[test]
+    fn test_frozen_false_is_explicit() {
+        let filters = SearchFilters {
+            language: None,
+            frozen: Some(false),
+            ..Default::default()
+        };
+
+        let sql = compile_filters(Some(&filters), 1);
+        assert_eq!(sql.and_clause(), " AND frozen = $2");
+    }
+
+    #[test]
+    fn test_dates_and_authors() {
+        let filters = SearchFilters {
+            date_after: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+            date_before: Some("2025-01-01T00:00:00Z".parse().unwrap()),
+            authors: Some(vec!["Ada".to_string()]),
+            exclude_authors: Some(vec!["Bob".to_string()]),
+            ..Default::default()
+        };
+
+        let sql = compile_filters(Some(&filters), 2);
+        assert_eq!(
+            sql.and_clause(),
+            " AND date_gmt >= $3 AND date_gmt < $4 AND author_name = ANY($5) \
+             AND author_name <> ALL($6) AND NOT frozen"
+        );
+        assert_eq!(sql.params().count(), 4);
+    }
+
+    #[test]
+    fn test_url_filters() {
+        let filters = SearchFilters {
+            url_domains: Some(vec!["example.com".to_string()]),
+            url_prefix: Some("https://example.com/blog/".to_string()),
+            ..Default::default()
+        };
+
+        let sql = compile_filters(Some(&filters), 1);
+        let clause = sql.and_clause();
+        assert!(clause.contains(" = ANY($2) OR "));
+        assert!(clause.contains(" LIKE ANY($3))"));
+        assert!(clause.contains("starts_with(url, $4)"));
+        assert_eq!(sql.params().count(), 3);
+    }
+
+    #[test]
+    fn test_boolean_combinators() {
+        let filters = SearchFilters {
+            or: Some(vec![
+                SearchFilters {
+                    language: Some("en".to_string()),
+                    ..Default::default()
+                },
+                SearchFilters {
+                    language: Some("es".to_string()),
+                    frozen: Some(false),
+                    ..Default::default()
+                },
+            ]),
+            not: Some(Box::new(SearchFilters {
+                authors: Some(vec!["Bob".to_string()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        let sql = compile_filters(Some(&filters), 2);
+        assert_eq!(
+            sql.and_clause(),
+            " AND ((lower(language) = $3) OR (lower(language) = $4 AND frozen = $5)) \
+             AND NOT (author_name = ANY($6))"
+        );
+    }
+
+    #[test]
+    fn test_metadata_conditions() {
+        let filters = SearchFilters {
+            metadata: Some(vec![
+                MetadataFilter {
+                    key: "tags".to_string(),
+                    contains: Some(json!("rust")),
+                    ..Default::default()
+                },
+                MetadataFilter {
+                    key: "category".to_string(),
+                    r#in: Some(vec![json!("guides"), json!("news")]),
+                    ..Default::default()
+                },
+                MetadataFilter {
+                    key: "price".to_string(),
+                    gte: Some(10.0),
+                    lt: Some(100.0),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+
+        let sql = compile_filters(Some(&filters), 2);
+        assert_eq!(
+            sql.and_clause(),
+            " AND metadata @> $3 AND (metadata @> $4 OR metadata @> $5) \
+             AND CASE WHEN jsonb_typeof(metadata -> $6::text) = 'number' \
+             THEN (metadata ->> $6::text)::float8 >= $7 AND (metadata ->> $6::text)::float8 < $8 \
+             ELSE false END AND NOT frozen"
+        );
+        assert_eq!(sql.params().count(), 6);
+
+        // Dotted keys are paths into nested objects
+        let filters = SearchFilters {
+            metadata: Some(vec![MetadataFilter {
+                key: "product.category".to_string(),
+                eq: Some(json!("books")),
+                gt: Some(0.0),
+                ..Default::default()
+            }]),
+            frozen: Some(false),
+            ..Default::default()
+        };
+        let sql = compile_filters(Some(&filters), 0);
+        assert_eq!(
+            sql.and_clause(),
+            " AND frozen = $1 AND (metadata @> $2 AND CASE WHEN jsonb_typeof(metadata #> $3::text[]) = 'number' \
+             THEN (metadata #>> $3::text[])::float8 > $4 ELSE false END)"
+        );
+        assert_eq!(nest(&["product", "category"], json!("books")), json!({ "product": { "category": "books" } }));
+    }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("my_site.com"), "my\\_site.com");
+    }
+}
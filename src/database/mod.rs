@@ -3,6 +3,7 @@
 /// This module implements Postgres connection pooling and pgvector search functionality
 /// with IVFFlat indexing, connection management, and statement timeouts.
 
+mod filters;
 mod postgres_client;
 mod schema;
 
@@ -11,13 +12,13 @@ mod tests;
 
 use crate::config::DatabaseConfig;
 use crate::error::{SearchError, SearchResult};
-use crate::types::{Post, SearchCandidate, SearchSource};
+use crate::types::{ChunkCandidate, DeletionReceipt, Post, PostChunk, SearchCandidate, SearchFilters, SearchSource};
 use postgres_client::PostgresClient;
 use std::sync::Arc;
 use tracing::{debug, info};
 
 pub use postgres_client::PostgresStats;
-pub use schema::DatabaseSchema;
+pub use schema::{CollectionTables, DatabaseSchema};
 
 /// Database manager for Postgres operations
 pub struct DatabaseManager {
@@ -42,9 +43,48 @@ impl DatabaseManager {
         })
     }
 
+    /// Database manager for a named collection, sharing this manager's connection pool
+    ///
+    /// The collection's tables are created on first use, sized to `dimension`.
+    pub async fn for_collection(&self, name: &str, dimension: usize) -> SearchResult<Self> {
+        let postgres_client = self.postgres_client.for_collection(name, dimension);
+        postgres_client.initialize_schema().await?;
+
+        info!("Database manager ready for collection {} (table {})", name, postgres_client.tables().posts);
+
+        Ok(DatabaseManager {
+            postgres_client: Arc::new(postgres_client),
+        })
+    }
+
     /// Perform vector similarity search using pgvector
-    pub async fn vector_search(&self, query_embedding: &[f32], limit: usize) -> SearchResult<Vec<SearchCandidate>> {
-        self.postgres_client.vector_search(query_embedding, limit).await
+    pub async fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<SearchCandidate>> {
+        self.postgres_client.vector_search(query_embedding, limit, filters).await
+    }
+
+    /// Perform vector similarity search over passages of chunked posts
+    pub async fn chunk_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<ChunkCandidate>> {
+        self.postgres_client.chunk_search(query_embedding, limit, filters).await
+    }
+
+    /// Perform full-text search over post titles and content
+    pub async fn lexical_search(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<SearchCandidate>> {
+        self.postgres_client.lexical_search(query, limit, filters).await
     }
 
     /// Get post by ID
@@ -57,21 +97,41 @@ impl DatabaseManager {
         self.postgres_client.get_posts_by_ids(post_ids).await
     }
 
+    /// Page of posts that have no passages yet, ordered by `post_id`
+    pub async fn posts_without_passages(&self, after: Option<&str>, limit: i64) -> SearchResult<Vec<Post>> {
+        self.postgres_client.posts_without_passages(after, limit).await
+    }
+
+    /// Store backfilled passages without touching passages stored since
+    pub async fn store_backfilled_passages(&self, chunks: &[PostChunk]) -> SearchResult<u64> {
+        self.postgres_client.store_backfilled_passages(chunks).await
+    }
+
     /// Store post with vector embedding
     pub async fn store_post(&self, post: &Post) -> SearchResult<()> {
         self.postgres_client.store_post(post).await
     }
 
+    /// Store a batch of posts and their passages in a single transaction
+    pub async fn store_posts(&self, posts: &[Post], chunks: &[PostChunk]) -> SearchResult<()> {
+        self.postgres_client.store_posts(posts, chunks).await
+    }
+
     /// Update post embedding
     pub async fn update_post_embedding(&self, post_id: &str, embedding: &[f32]) -> SearchResult<()> {
         self.postgres_client.update_post_embedding(post_id, embedding).await
     }
 
-    /// Delete post (GDPR compliance)
-    pub async fn delete_post(&self, post_id: &str) -> SearchResult<()> {
+    /// Delete post (GDPR compliance), returning whether a row was removed
+    pub async fn delete_post(&self, post_id: &str) -> SearchResult<bool> {
         self.postgres_client.delete_post(post_id).await
     }
 
+    /// Write a GDPR erasure receipt to the audit log
+    pub async fn record_gdpr_audit(&self, receipt: &DeletionReceipt) -> SearchResult<()> {
+        self.postgres_client.record_gdpr_audit(receipt).await
+    }
+
     /// Get database statistics
     pub async fn get_stats(&self) -> SearchResult<PostgresStats> {
         self.postgres_client.get_stats().await
@@ -1,18 +1,91 @@
 use crate::config::DatabaseConfig;
 use crate::error::{SearchError, SearchResult};
-use crate::types::{Post, SearchCandidate, SearchSource};
+use crate::types::{ChunkCandidate, DeletionReceipt, PassageSpan, Post, PostChunk, SearchCandidate, SearchFilters, SearchSource};
+use super::filters::compile_filters;
+use super::schema::{CollectionTables, DatabaseSchema};
 use deadpool_postgres::{Config, Pool, Runtime};
 use std::time::Duration;
 use tokio::time::timeout;
+use tokio_postgres::types::ToSql;
 use tokio_postgres::{NoTls, Row};
 use tracing::{debug, info, warn};
 
+/// Insert a post or update every column of an existing one with the same post_id
+fn upsert_post_sql(tables: &CollectionTables) -> String {
+    format!("
+    INSERT INTO {} (id, post_id, title, content, author_name, language, frozen, date_gmt, url, embedding, metadata)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10::vector, $11)
+    ON CONFLICT (post_id) 
+    DO UPDATE SET 
+        title = EXCLUDED.title,
+        content = EXCLUDED.content,
+        author_name = EXCLUDED.author_name,
+        language = EXCLUDED.language,
+        frozen = EXCLUDED.frozen,
+        date_gmt = EXCLUDED.date_gmt,
+        url = EXCLUDED.url,
+        embedding = EXCLUDED.embedding,
+        metadata = EXCLUDED.metadata
+", tables.posts)
+}
+
+/// Insert one passage of a post; existing passages are deleted first
+fn insert_chunk_sql(tables: &CollectionTables) -> String {
+    format!("
+    INSERT INTO {} (post_id, chunk_index, start_offset, end_offset, content, embedding)
+    VALUES ($1, $2, $3, $4, $5, $6::vector)
+", tables.chunks)
+}
+
+/// SQL inserting a backfilled passage unless the post already has it
+fn insert_backfilled_chunk_sql(tables: &CollectionTables) -> String {
+    format!("
+    INSERT INTO {} (post_id, chunk_index, start_offset, end_offset, content, embedding)
+    VALUES ($1, $2, $3, $4, $5, $6::vector)
+    ON CONFLICT (post_id, chunk_index) DO NOTHING
+", tables.chunks)
+}
+
+/// IVFFlat lists probed by filtered vector searches
+///
+/// The index hands the `WHERE` clause only the rows of the probed lists, so a
+/// selective filter can leave fewer than `limit` matches at the default of 10.
+const FILTERED_IVFFLAT_PROBES: u32 = 40;
+
+/// Run a vector query, probing more IVFFlat lists when it is filtered
+///
+/// The probe count is raised with `SET LOCAL`, so it never leaks to other
+/// queries on the pooled connection.
+async fn query_vectors(
+    client: &mut tokio_postgres::Client,
+    sql: &str,
+    params: &[&(dyn ToSql + Sync)],
+    filtered: bool,
+) -> Result<Vec<Row>, tokio_postgres::Error> {
+    if !filtered {
+        return client.query(sql, params).await;
+    }
+
+    let transaction = client.transaction().await?;
+    transaction
+        .batch_execute(&format!("SET LOCAL ivfflat.probes = {}", FILTERED_IVFFLAT_PROBES))
+        .await?;
+    let rows = transaction.query(sql, params).await?;
+    transaction.commit().await?;
+    Ok(rows)
+}
+
 /// Postgres client wrapper with connection pooling and pgvector support
+///
+/// Each client reads and writes the tables of one collection; clients of
+/// other collections share the connection pool.
 pub struct PostgresClient {
     /// Connection pool for Postgres
     pool: Pool,
     /// Configuration
     config: DatabaseConfig,
+    /// Tables of the collection served by this client
+    tables: CollectionTables,
 }
 
 impl PostgresClient {
@@ -54,14 +127,47 @@ impl PostgresClient {
 
         info!("Postgres client connected successfully");
 
-        Ok(PostgresClient { pool, config })
+        Ok(PostgresClient {
+            pool,
+            config,
+            tables: CollectionTables::default(),
+        })
+    }
+
+    /// Client for another collection, sharing this client's connection pool
+    ///
+    /// `dimension` sizes the collection's embedding columns.
+    pub fn for_collection(&self, name: &str, dimension: usize) -> Self {
+        PostgresClient {
+            pool: self.pool.clone(),
+            config: DatabaseConfig {
+                vector_dimension: dimension,
+                ..self.config.clone()
+            },
+            tables: CollectionTables::for_collection(name),
+        }
+    }
+
+    /// Tables of the collection served by this client
+    pub fn tables(&self) -> &CollectionTables {
+        &self.tables
     }
 
     /// Perform vector similarity search using pgvector with IVFFlat
-    pub async fn vector_search(&self, query_embedding: &[f32], limit: usize) -> SearchResult<Vec<SearchCandidate>> {
+    ///
+    /// Filters are part of the `WHERE` clause, so up to `limit` matching posts
+    /// are returned; without a frozen filter frozen posts are excluded.
+    /// Filtered searches probe more index lists to keep recall up.
+    pub async fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<SearchCandidate>> {
         debug!("Performing Postgres vector search with limit: {}", limit);
+        self.check_dimension(query_embedding)?;
 
-        let client = self.pool
+        let mut client = self.pool
             .get()
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
@@ -76,18 +182,22 @@ impl PostgresClient {
 
         // Use cosine distance with IVFFlat index
         // The query uses the <=> operator for cosine distance
-        let query = "
+        let filter_sql = compile_filters(filters, 2);
+        let query = format!("
             SELECT post_id, (embedding <=> $1::vector) as distance
-            FROM posts 
-            WHERE embedding IS NOT NULL 
-              AND NOT frozen
+            FROM {} 
+            WHERE embedding IS NOT NULL{}
             ORDER BY embedding <=> $1::vector
             LIMIT $2
-        ";
+        ", self.tables.posts, filter_sql.and_clause());
+
+        let limit = limit as i64;
+        let mut params: Vec<&(dyn ToSql + Sync)> = vec![&embedding_str, &limit];
+        params.extend(filter_sql.params());
 
         let statement_timeout = Duration::from_millis(500); // 500ms timeout as per requirements
         
-        let rows = timeout(statement_timeout, client.query(query, &[&embedding_str, &(limit as i64)]))
+        let rows = timeout(statement_timeout, query_vectors(&mut client, query.as_str(), &params, filters.is_some()))
             .await
             .map_err(|_| SearchError::DatabaseError("Query timeout exceeded 500ms".to_string()))?
             .map_err(|e| SearchError::DatabaseError(format!("Vector search query failed: {}", e)))?;
@@ -104,6 +214,8 @@ impl PostgresClient {
                 post_id,
                 score,
                 source: SearchSource::Postgres,
+                passage: None,
+                merged_from: Vec::new(),
             });
         }
 
@@ -111,6 +223,122 @@ impl PostgresClient {
         Ok(candidates)
     }
 
+    /// Vector similarity search over passages of chunked posts
+    ///
+    /// Filters apply to the owning post. A post can contribute several
+    /// passages; aggregation back to posts happens in the search service.
+    pub async fn chunk_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<ChunkCandidate>> {
+        debug!("Performing Postgres passage search with limit: {}", limit);
+        self.check_dimension(query_embedding)?;
+
+        let mut client = self.pool
+            .get()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
+
+        let embedding_str = embedding_to_pgvector(query_embedding);
+
+        let filter_sql = compile_filters(filters, 2);
+        let query = format!("
+            SELECT c.post_id, c.chunk_index, c.start_offset, c.end_offset, (c.embedding <=> $1::vector)::real as distance
+            FROM {} c
+            JOIN {} posts ON posts.post_id = c.post_id
+            WHERE TRUE{}
+            ORDER BY c.embedding <=> $1::vector
+            LIMIT $2
+        ", self.tables.chunks, self.tables.posts, filter_sql.and_clause());
+
+        let limit = limit as i64;
+        let mut params: Vec<&(dyn ToSql + Sync)> = vec![&embedding_str, &limit];
+        params.extend(filter_sql.params());
+
+        let statement_timeout = Duration::from_millis(500);
+
+        let rows = timeout(statement_timeout, query_vectors(&mut client, query.as_str(), &params, filters.is_some()))
+            .await
+            .map_err(|_| SearchError::DatabaseError("Query timeout exceeded 500ms".to_string()))?
+            .map_err(|e| SearchError::DatabaseError(format!("Passage search query failed: {}", e)))?;
+
+        let candidates: Vec<ChunkCandidate> = rows
+            .into_iter()
+            .map(|row| {
+                let distance: f32 = row.get(4);
+                ChunkCandidate {
+                    post_id: row.get(0),
+                    chunk_index: row.get::<_, i32>(1) as u32,
+                    span: PassageSpan {
+                        start: row.get::<_, i32>(2) as u32,
+                        end: row.get::<_, i32>(3) as u32,
+                    },
+                    score: 1.0 - distance,
+                    source: SearchSource::Postgres,
+                }
+            })
+            .collect();
+
+        debug!("Postgres passage search returned {} candidates", candidates.len());
+        Ok(candidates)
+    }
+
+    /// Full-text search over post titles and content
+    ///
+    /// The query is parsed with `websearch_to_tsquery`, so quoted phrases and
+    /// `-term` exclusions work as users expect. Scores are `ts_rank_cd` with
+    /// normalization flag 32, which maps the raw rank into 0..1 as
+    /// rank / (rank + 1); the raw rank itself is never returned.
+    pub async fn lexical_search(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<SearchCandidate>> {
+        debug!("Performing Postgres full-text search with limit: {}", limit);
+
+        let client = self.pool
+            .get()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
+
+        let filter_sql = compile_filters(filters, 2);
+        let sql = format!("
+            SELECT post_id, ts_rank_cd(search_vector, query, 32)::real as rank
+            FROM {}, websearch_to_tsquery('simple', $1) query
+            WHERE search_vector @@ query{}
+            ORDER BY rank DESC
+            LIMIT $2
+        ", self.tables.posts, filter_sql.and_clause());
+
+        let limit = limit as i64;
+        let mut params: Vec<&(dyn ToSql + Sync)> = vec![&query, &limit];
+        params.extend(filter_sql.params());
+
+        let statement_timeout = Duration::from_millis(500);
+
+        let rows = timeout(statement_timeout, client.query(sql.as_str(), &params))
+            .await
+            .map_err(|_| SearchError::DatabaseError("Query timeout exceeded 500ms".to_string()))?
+            .map_err(|e| SearchError::DatabaseError(format!("Full-text search query failed: {}", e)))?;
+
+        let candidates: Vec<SearchCandidate> = rows
+            .into_iter()
+            .map(|row| SearchCandidate {
+                post_id: row.get(0),
+                score: row.get(1),
+                source: SearchSource::Lexical,
+                passage: None,
+                merged_from: Vec::new(),
+            })
+            .collect();
+
+        debug!("Postgres full-text search returned {} candidates", candidates.len());
+        Ok(candidates)
+    }
+
     /// Get post by ID
     pub async fn get_post_by_id(&self, post_id: &str) -> SearchResult<Option<Post>> {
         debug!("Retrieving post by ID: {}", post_id);
@@ -120,14 +348,14 @@ impl PostgresClient {
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
 
-        let query = "
-            SELECT id, post_id, title, content, author_name, language, frozen, date_gmt, url, embedding
-            FROM posts 
+        let query = format!("
+            SELECT id, post_id, title, content, author_name, language, frozen, date_gmt, url, embedding, metadata
+            FROM {} 
             WHERE post_id = $1
-        ";
+        ", self.tables.posts);
 
         let rows = client
-            .query(query, &[&post_id])
+            .query(query.as_str(), &[&post_id])
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to get post: {}", e)))?;
 
@@ -159,9 +387,10 @@ impl PostgresClient {
         // Create placeholders for the IN clause
         let placeholders: Vec<String> = (1..=post_ids.len()).map(|i| format!("${}", i)).collect();
         let query = format!(
-            "SELECT id, post_id, title, content, author_name, language, frozen, date_gmt, url, embedding
-             FROM posts 
+            "SELECT id, post_id, title, content, author_name, language, frozen, date_gmt, url, embedding, metadata
+             FROM {} 
              WHERE post_id IN ({})",
+            self.tables.posts,
             placeholders.join(", ")
         );
 
@@ -184,44 +413,100 @@ impl PostgresClient {
         Ok(posts)
     }
 
+    /// Page of posts that have no passages yet, ordered by `post_id`
+    ///
+    /// Pass the last `post_id` of the previous page as `after` to continue.
+    pub async fn posts_without_passages(&self, after: Option<&str>, limit: i64) -> SearchResult<Vec<Post>> {
+        let client = self.pool
+            .get()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
+
+        let query = format!("
+            SELECT id, post_id, title, content, author_name, language, frozen, date_gmt, url, embedding, metadata
+            FROM {} p
+            WHERE ($1::text IS NULL OR p.post_id > $1)
+              AND NOT EXISTS (SELECT 1 FROM {} c WHERE c.post_id = p.post_id)
+            ORDER BY p.post_id
+            LIMIT $2
+        ", self.tables.posts, self.tables.chunks);
+
+        let rows = client
+            .query(query.as_str(), &[&after, &limit])
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to list posts without passages: {}", e)))?;
+
+        rows.iter().map(|row| self.row_to_post(row)).collect()
+    }
+
+    /// Store passages computed for posts that were ingested without any
+    ///
+    /// Only the passage table is written, and passages an ingestion stored in
+    /// the meantime are kept, so a backfill never overwrites fresher data.
+    pub async fn store_backfilled_passages(&self, chunks: &[PostChunk]) -> SearchResult<u64> {
+        for chunk in chunks {
+            self.check_dimension(&chunk.embedding)?;
+        }
+
+        let mut client = self.pool
+            .get()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
+
+        let transaction = client
+            .transaction()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        let statement = transaction
+            .prepare(&insert_backfilled_chunk_sql(&self.tables))
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to prepare passage insert: {}", e)))?;
+
+        let mut inserted = 0;
+        for chunk in chunks {
+            let embedding_str = embedding_to_pgvector(&chunk.embedding);
+
+            inserted += transaction
+                .execute(&statement, &[
+                    &chunk.post_id,
+                    &(chunk.chunk_index as i32),
+                    &(chunk.span.start as i32),
+                    &(chunk.span.end as i32),
+                    &chunk.content,
+                    &embedding_str,
+                ])
+                .await
+                .map_err(|e| SearchError::DatabaseError(format!(
+                    "Failed to store passage {} of post {}: {}", chunk.chunk_index, chunk.post_id, e
+                )))?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to commit passages: {}", e)))?;
+
+        Ok(inserted)
+    }
+
     /// Store post with vector embedding
     pub async fn store_post(&self, post: &Post) -> SearchResult<()> {
         debug!("Storing post: {}", post.post_id);
+        if !post.embedding.is_empty() {
+            self.check_dimension(&post.embedding)?;
+        }
 
         let client = self.pool
             .get()
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
 
-        // Convert embedding to pgvector format
-        let embedding_str = if post.embedding.is_empty() {
-            None
-        } else {
-            Some(format!("[{}]", 
-                post.embedding.iter()
-                    .map(|f| f.to_string())
-                    .collect::<Vec<_>>()
-                    .join(",")
-            ))
-        };
-
-        let query = "
-            INSERT INTO posts (id, post_id, title, content, author_name, language, frozen, date_gmt, url, embedding)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10::vector)
-            ON CONFLICT (post_id) 
-            DO UPDATE SET 
-                title = EXCLUDED.title,
-                content = EXCLUDED.content,
-                author_name = EXCLUDED.author_name,
-                language = EXCLUDED.language,
-                frozen = EXCLUDED.frozen,
-                date_gmt = EXCLUDED.date_gmt,
-                url = EXCLUDED.url,
-                embedding = EXCLUDED.embedding
-        ";
+        let embedding_str = embedding_to_pgvector(&post.embedding);
+        let metadata = serde_json::Value::Object(post.metadata.clone());
 
         client
-            .execute(query, &[
+            .execute(upsert_post_sql(&self.tables).as_str(), &[
                 &post.id,
                 &post.post_id,
                 &post.title,
@@ -232,6 +517,7 @@ impl PostgresClient {
                 &post.date_gmt,
                 &post.url,
                 &embedding_str,
+                &metadata,
             ])
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to store post: {}", e)))?;
@@ -240,9 +526,103 @@ impl PostgresClient {
         Ok(())
     }
 
+    /// Store a batch of posts and their passages in a single transaction
+    ///
+    /// The passages of every post in the batch are replaced by `chunks`, so a
+    /// post that got shorter does not keep stale passages.
+    pub async fn store_posts(&self, posts: &[Post], chunks: &[PostChunk]) -> SearchResult<()> {
+        debug!("Storing batch of {} posts with {} passages", posts.len(), chunks.len());
+        for post in posts {
+            if !post.embedding.is_empty() {
+                self.check_dimension(&post.embedding)?;
+            }
+        }
+        for chunk in chunks {
+            self.check_dimension(&chunk.embedding)?;
+        }
+
+        let mut client = self.pool
+            .get()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
+
+        let transaction = client
+            .transaction()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        let statement = transaction
+            .prepare(&upsert_post_sql(&self.tables))
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to prepare upsert: {}", e)))?;
+
+        for post in posts {
+            let embedding_str = embedding_to_pgvector(&post.embedding);
+            let metadata = serde_json::Value::Object(post.metadata.clone());
+
+            transaction
+                .execute(&statement, &[
+                    &post.id,
+                    &post.post_id,
+                    &post.title,
+                    &post.content,
+                    &post.author_name,
+                    &post.language,
+                    &post.frozen,
+                    &post.date_gmt,
+                    &post.url,
+                    &embedding_str,
+                    &metadata,
+                ])
+                .await
+                .map_err(|e| SearchError::DatabaseError(format!("Failed to store post {}: {}", post.post_id, e)))?;
+        }
+
+        let post_ids: Vec<&str> = posts.iter().map(|post| post.post_id.as_str()).collect();
+        transaction
+            .execute(
+                format!("DELETE FROM {} WHERE post_id = ANY($1)", self.tables.chunks).as_str(),
+                &[&post_ids],
+            )
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to clear passages: {}", e)))?;
+
+        let chunk_statement = transaction
+            .prepare(&insert_chunk_sql(&self.tables))
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to prepare passage insert: {}", e)))?;
+
+        for chunk in chunks {
+            let embedding_str = embedding_to_pgvector(&chunk.embedding);
+
+            transaction
+                .execute(&chunk_statement, &[
+                    &chunk.post_id,
+                    &(chunk.chunk_index as i32),
+                    &(chunk.span.start as i32),
+                    &(chunk.span.end as i32),
+                    &chunk.content,
+                    &embedding_str,
+                ])
+                .await
+                .map_err(|e| SearchError::DatabaseError(format!(
+                    "Failed to store passage {} of post {}: {}", chunk.chunk_index, chunk.post_id, e
+                )))?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to commit posts: {}", e)))?;
+
+        info!("Stored batch of {} posts with {} passages", posts.len(), chunks.len());
+        Ok(())
+    }
+
     /// Update post embedding
     pub async fn update_post_embedding(&self, post_id: &str, embedding: &[f32]) -> SearchResult<()> {
         debug!("Updating embedding for post: {}", post_id);
+        self.check_dimension(embedding)?;
 
         let client = self.pool
             .get()
@@ -256,10 +636,10 @@ impl PostgresClient {
                 .join(",")
         );
 
-        let query = "UPDATE posts SET embedding = $1::vector WHERE post_id = $2";
+        let query = format!("UPDATE {} SET embedding = $1::vector WHERE post_id = $2", self.tables.posts);
 
         let rows_affected = client
-            .execute(query, &[&embedding_str, &post_id])
+            .execute(query.as_str(), &[&embedding_str, &post_id])
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to update embedding: {}", e)))?;
 
@@ -271,8 +651,8 @@ impl PostgresClient {
         Ok(())
     }
 
-    /// Delete post (GDPR compliance)
-    pub async fn delete_post(&self, post_id: &str) -> SearchResult<()> {
+    /// Delete post (GDPR compliance), returning whether a row was removed
+    pub async fn delete_post(&self, post_id: &str) -> SearchResult<bool> {
         debug!("Deleting post: {}", post_id);
 
         let client = self.pool
@@ -280,10 +660,10 @@ impl PostgresClient {
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
 
-        let query = "DELETE FROM posts WHERE post_id = $1";
+        let query = format!("DELETE FROM {} WHERE post_id = $1", self.tables.posts);
 
         let rows_affected = client
-            .execute(query, &[&post_id])
+            .execute(query.as_str(), &[&post_id])
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to delete post: {}", e)))?;
 
@@ -293,10 +673,49 @@ impl PostgresClient {
             info!("Successfully deleted post: {}", post_id);
         }
 
+        Ok(rows_affected > 0)
+    }
+
+    /// Write a GDPR erasure receipt to the audit log
+    pub async fn record_gdpr_audit(&self, receipt: &DeletionReceipt) -> SearchResult<()> {
+        let client = self.pool
+            .get()
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
+
+        let query = "
+            INSERT INTO gdpr_audit_log (
+                receipt_id, post_id, status, postgres_deleted, cache_keys_deleted,
+                topk_entries_purged, attempts, errors, requested_at, completed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (receipt_id) DO NOTHING
+        ";
+
+        client
+            .execute(query, &[
+                &receipt.receipt_id,
+                &receipt.post_id,
+                &receipt.status.as_str(),
+                &receipt.postgres_deleted,
+                &(receipt.cache_keys_deleted as i64),
+                &(receipt.topk_entries_purged as i64),
+                &(receipt.attempts as i32),
+                &receipt.errors,
+                &receipt.requested_at,
+                &receipt.completed_at,
+            ])
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to write GDPR audit record: {}", e)))?;
+
+        debug!("Recorded GDPR audit entry {} for post: {}", receipt.receipt_id, receipt.post_id);
         Ok(())
     }
 
     /// Get database statistics
+    ///
+    /// Post and passage counts cover this client's collection only; size and
+    /// connection figures are for the whole database.
     pub async fn get_stats(&self) -> SearchResult<PostgresStats> {
         let client = self.pool
             .get()
@@ -304,16 +723,17 @@ impl PostgresClient {
             .map_err(|e| SearchError::DatabaseError(format!("Failed to get connection: {}", e)))?;
 
         // Get basic database statistics
-        let query = "
+        let query = format!("
             SELECT 
-                (SELECT COUNT(*) FROM posts) as total_posts,
-                (SELECT COUNT(*) FROM posts WHERE embedding IS NOT NULL) as posts_with_embeddings,
-                (SELECT COUNT(*) FROM posts WHERE frozen = true) as frozen_posts,
-                (SELECT pg_database_size(current_database())) as database_size_bytes
-        ";
+                (SELECT COUNT(*) FROM {posts}) as total_posts,
+                (SELECT COUNT(*) FROM {posts} WHERE embedding IS NOT NULL) as posts_with_embeddings,
+                (SELECT COUNT(*) FROM {posts} WHERE frozen = true) as frozen_posts,
+                (SELECT pg_database_size(current_database())) as database_size_bytes,
+                (SELECT COUNT(*) FROM {chunks}) as total_passages
+        ", posts = self.tables.posts, chunks = self.tables.chunks);
 
         let rows = client
-            .query(query, &[])
+            .query(query.as_str(), &[])
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to get stats: {}", e)))?;
 
@@ -327,6 +747,7 @@ impl PostgresClient {
             posts_with_embeddings: row.get::<_, i64>(1) as u64,
             frozen_posts: row.get::<_, i64>(2) as u64,
             database_size_bytes: row.get::<_, i64>(3) as u64,
+            total_passages: row.get::<_, i64>(4) as u64,
             active_connections: self.pool.status().size as u32,
             max_connections: self.pool.status().max_size as u32,
         };
@@ -368,9 +789,9 @@ impl PostgresClient {
         Ok(())
     }
 
-    /// Initialize database schema and indexes
+    /// Initialize database schema and indexes for this client's collection
     pub async fn initialize_schema(&self) -> SearchResult<()> {
-        info!("Initializing database schema");
+        info!("Initializing database schema for table {}", self.tables.posts);
 
         let client = self.pool
             .get()
@@ -383,40 +804,49 @@ impl PostgresClient {
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to create vector extension: {}", e)))?;
 
-        // Create posts table if it doesn't exist
-        let create_table_query = "
-            CREATE TABLE IF NOT EXISTS posts (
-                id UUID PRIMARY KEY,
-                post_id VARCHAR(255) UNIQUE NOT NULL,
-                title TEXT NOT NULL,
-                content TEXT NOT NULL,
-                author_name VARCHAR(255) NOT NULL,
-                language VARCHAR(10) NOT NULL DEFAULT 'en',
-                frozen BOOLEAN NOT NULL DEFAULT false,
-                date_gmt TIMESTAMPTZ NOT NULL,
-                url TEXT NOT NULL,
-                embedding vector(384),
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                updated_at TIMESTAMPTZ DEFAULT NOW()
-            )
-        ";
+        // Create posts table if it doesn't exist, sized to the active embedding model
+        DatabaseSchema::validate_schema_requirements(self.config.vector_dimension)?;
+        let create_table_query = DatabaseSchema::create_posts_table_sql(&self.tables, self.config.vector_dimension);
+
+        client
+            .execute(create_table_query.as_str(), &[])
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to create {} table: {}", self.tables.posts, e)))?;
+
+        // Create the GDPR erasure audit log
+        client
+            .execute(DatabaseSchema::create_gdpr_audit_table_sql(), &[])
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to create GDPR audit table: {}", e)))?;
+
+        // Generated tsvector column for full-text search
+        client
+            .execute(DatabaseSchema::add_search_vector_column_sql(&self.tables).as_str(), &[])
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to add search_vector column: {}", e)))?;
+
+        // Custom JSONB metadata (tags, categories, ...)
+        client
+            .execute(DatabaseSchema::add_metadata_column_sql(&self.tables).as_str(), &[])
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to add metadata column: {}", e)))?;
 
+        // Passages of long posts, each with its own embedding
+        let create_chunks_query = DatabaseSchema::create_post_chunks_table_sql(&self.tables, self.config.vector_dimension);
         client
-            .execute(create_table_query, &[])
+            .execute(create_chunks_query.as_str(), &[])
             .await
-            .map_err(|e| SearchError::DatabaseError(format!("Failed to create posts table: {}", e)))?;
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to create {} table: {}", self.tables.chunks, e)))?;
 
         // Create indexes for common queries
-        let indexes = vec![
-            "CREATE INDEX IF NOT EXISTS idx_posts_post_id ON posts(post_id)",
-            "CREATE INDEX IF NOT EXISTS idx_posts_language ON posts(language)",
-            "CREATE INDEX IF NOT EXISTS idx_posts_frozen ON posts(frozen)",
-            "CREATE INDEX IF NOT EXISTS idx_posts_date_gmt ON posts(date_gmt)",
-        ];
+        let mut indexes = DatabaseSchema::create_indexes_sql(&self.tables);
+        indexes.push("CREATE INDEX IF NOT EXISTS idx_gdpr_audit_post_id ON gdpr_audit_log(post_id)".to_string());
+        indexes.push(DatabaseSchema::create_search_vector_index_sql(&self.tables));
+        indexes.push(DatabaseSchema::create_metadata_index_sql(&self.tables));
 
         for index_query in indexes {
             client
-                .execute(index_query, &[])
+                .execute(index_query.as_str(), &[])
                 .await
                 .map_err(|e| SearchError::DatabaseError(format!("Failed to create index: {}", e)))?;
         }
@@ -436,24 +866,36 @@ impl PostgresClient {
 
         // Drop existing vector index if it exists
         client
-            .execute("DROP INDEX IF EXISTS idx_posts_embedding_ivfflat", &[])
+            .execute(
+                format!("DROP INDEX IF EXISTS {}", self.tables.posts_index("embedding_ivfflat")).as_str(),
+                &[],
+            )
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to drop existing index: {}", e)))?;
 
         // Create IVFFlat index for vector similarity search
         // Using 100 lists as a reasonable default for moderate-sized datasets
-        let create_index_query = "
-            CREATE INDEX idx_posts_embedding_ivfflat 
-            ON posts 
-            USING ivfflat (embedding vector_cosine_ops) 
-            WITH (lists = 100)
-        ";
+        let create_index_query = DatabaseSchema::create_vector_index_sql(&self.tables);
 
         client
-            .execute(create_index_query, &[])
+            .execute(create_index_query.as_str(), &[])
             .await
             .map_err(|e| SearchError::DatabaseError(format!("Failed to create vector index: {}", e)))?;
 
+        // Same index for passage embeddings
+        client
+            .execute(
+                format!("DROP INDEX IF EXISTS {}", self.tables.chunks_index("embedding_ivfflat")).as_str(),
+                &[],
+            )
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to drop existing passage index: {}", e)))?;
+
+        client
+            .execute(DatabaseSchema::create_chunk_vector_index_sql(&self.tables).as_str(), &[])
+            .await
+            .map_err(|e| SearchError::DatabaseError(format!("Failed to create passage vector index: {}", e)))?;
+
         // Set probes for query optimization (affects recall vs speed tradeoff)
         client
             .execute("SET ivfflat.probes = 10", &[])
@@ -464,6 +906,19 @@ impl PostgresClient {
         Ok(())
     }
 
+    /// Ensure an embedding matches the dimension of the embedding column
+    fn check_dimension(&self, embedding: &[f32]) -> SearchResult<()> {
+        if embedding.len() != self.config.vector_dimension {
+            return Err(SearchError::DatabaseError(format!(
+                "Embedding has {} dimensions, expected {}",
+                embedding.len(),
+                self.config.vector_dimension
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Convert database row to Post struct
     fn row_to_post(&self, row: &Row) -> SearchResult<Post> {
         // Parse embedding from pgvector format
@@ -484,6 +939,11 @@ impl PostgresClient {
             Vec::new()
         };
 
+        let metadata = match row.get::<_, serde_json::Value>(10) {
+            serde_json::Value::Object(metadata) => metadata,
+            _ => serde_json::Map::new(),
+        };
+
         Ok(Post {
             id: row.get(0),
             post_id: row.get(1),
@@ -494,6 +954,7 @@ impl PostgresClient {
             frozen: row.get(6),
             date_gmt: row.get(7),
             url: row.get(8),
+            metadata,
             embedding,
         })
     }
@@ -505,11 +966,26 @@ pub struct PostgresStats {
     pub total_posts: u64,
     pub posts_with_embeddings: u64,
     pub frozen_posts: u64,
+    pub total_passages: u64,
     pub database_size_bytes: u64,
     pub active_connections: u32,
     pub max_connections: u32,
 }
 
+/// Format an embedding as a pgvector literal, or None when there is no embedding
+fn embedding_to_pgvector(embedding: &[f32]) -> Option<String> {
+    if embedding.is_empty() {
+        None
+    } else {
+        Some(format!("[{}]", 
+            embedding.iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ))
+    }
+}
+
 /// Sanitize URL for logging by masking credentials
 fn sanitize_url_for_logging(url: &str) -> String {
     if let Ok(parsed) = url::Url::parse(url) {
@@ -546,6 +1022,7 @@ mod tests {
             supabase_service_key: "test_key".to_string(),
             max_connections: 5,
             connection_timeout_secs: 10,
+            vector_dimension: 384,
         }
     }
 
@@ -561,6 +1038,7 @@ mod tests {
             date_gmt: Utc::now(),
             url: "https://example.com/test-post".to_string(),
             embedding: vec![0.1, 0.2, 0.3, 0.4],
+            metadata: serde_json::Map::new(),
         }
     }
 
@@ -620,6 +1098,12 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_embedding_to_pgvector() {
+        assert_eq!(embedding_to_pgvector(&[]), None);
+        assert_eq!(embedding_to_pgvector(&[0.5, -1.0]), Some("[0.5,-1]".to_string()));
+    }
+
     #[tokio::test]
     #[ignore = "requires Postgres connection"]
     async fn test_vector_search() {
@@ -629,7 +1113,7 @@ mod tests {
             let query_embedding = vec![0.1, 0.2, 0.3, 0.4];
             let limit = 10;
             
-            let search_result = client.vector_search(&query_embedding, limit).await;
+            let search_result = client.vector_search(&query_embedding, limit, None).await;
             assert!(search_result.is_ok(), "Vector search failed: {:?}", search_result);
             
             let candidates = search_result.unwrap();
@@ -637,6 +1121,26 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[ignore = "requires Postgres connection"]
+    async fn test_lexical_search() {
+        let config = create_test_database_config();
+        
+        if let Ok(client) = PostgresClient::new(config).await {
+            let filters = SearchFilters {
+                language: Some("en".to_string()),
+                frozen: Some(true),
+                ..Default::default()
+            };
+            let search_result = client.lexical_search("\"test post\" -draft", 10, Some(&filters)).await;
+            assert!(search_result.is_ok(), "Full-text search failed: {:?}", search_result);
+            
+            let candidates = search_result.unwrap();
+            assert!(candidates.len() <= 10);
+            assert!(candidates.iter().all(|c| c.source == SearchSource::Lexical));
+        }
+    }
+
     #[test]
     fn test_database_config_validation() {
         let valid_config = DatabaseConfig {
@@ -644,6 +1148,7 @@ mod tests {
             supabase_service_key: "test_key".to_string(),
             max_connections: 10,
             connection_timeout_secs: 30,
+            vector_dimension: 384,
         };
         
         assert!(valid_config.supabase_url.starts_with("postgresql://"));
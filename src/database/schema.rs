@@ -4,15 +4,66 @@
 /// and pgvector index configurations for optimal vector search performance.
 
 use crate::error::{SearchError, SearchResult};
+use crate::types::DEFAULT_COLLECTION;
+
+/// Table names of one collection
+///
+/// The default collection keeps the original `posts` and `post_chunks`
+/// tables; named collections get their own `{name}_posts` and
+/// `{name}_post_chunks`. Index names are derived from the table names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionTables {
+    /// Table holding the collection's posts
+    pub posts: String,
+    /// Table holding the passages of the collection's chunked posts
+    pub chunks: String,
+}
+
+impl CollectionTables {
+    /// Tables of the named collection
+    ///
+    /// The name must already be validated with `is_valid_collection_name`,
+    /// since it is interpolated into SQL.
+    pub fn for_collection(name: &str) -> Self {
+        if name == DEFAULT_COLLECTION {
+            return Self::default();
+        }
+
+        Self {
+            posts: format!("{}_posts", name),
+            chunks: format!("{}_post_chunks", name),
+        }
+    }
+
+    /// Name of an index on the posts table
+    pub fn posts_index(&self, suffix: &str) -> String {
+        format!("idx_{}_{}", self.posts, suffix)
+    }
+
+    /// Name of an index on the passage table
+    pub fn chunks_index(&self, suffix: &str) -> String {
+        format!("idx_{}_{}", self.chunks, suffix)
+    }
+}
+
+impl Default for CollectionTables {
+    fn default() -> Self {
+        Self {
+            posts: "posts".to_string(),
+            chunks: "post_chunks".to_string(),
+        }
+    }
+}
 
 /// Database schema manager
 pub struct DatabaseSchema;
 
 impl DatabaseSchema {
     /// Get the SQL for creating the posts table
-    pub fn create_posts_table_sql() -> &'static str {
-        "
-        CREATE TABLE IF NOT EXISTS posts (
+    /// The embedding column is sized to the active embedding model's dimension
+    pub fn create_posts_table_sql(tables: &CollectionTables, dimension: usize) -> String {
+        format!("
+        CREATE TABLE IF NOT EXISTS {} (
             id UUID PRIMARY KEY,
             post_id VARCHAR(255) UNIQUE NOT NULL,
             title TEXT NOT NULL,
@@ -22,32 +73,140 @@ impl DatabaseSchema {
             frozen BOOLEAN NOT NULL DEFAULT false,
             date_gmt TIMESTAMPTZ NOT NULL,
             url TEXT NOT NULL,
-            embedding vector(384),
+            embedding vector({}),
             created_at TIMESTAMPTZ DEFAULT NOW(),
             updated_at TIMESTAMPTZ DEFAULT NOW()
         )
+        ", tables.posts, dimension)
+    }
+
+    /// Get the SQL for creating the GDPR erasure audit table
+    ///
+    /// The audit log is shared by all collections.
+    pub fn create_gdpr_audit_table_sql() -> &'static str {
+        "
+        CREATE TABLE IF NOT EXISTS gdpr_audit_log (
+            receipt_id UUID PRIMARY KEY,
+            post_id VARCHAR(255) NOT NULL,
+            status VARCHAR(16) NOT NULL,
+            postgres_deleted BOOLEAN NOT NULL,
+            cache_keys_deleted BIGINT NOT NULL,
+            topk_entries_purged BIGINT NOT NULL,
+            attempts INTEGER NOT NULL,
+            errors TEXT[] NOT NULL DEFAULT '{}',
+            requested_at TIMESTAMPTZ NOT NULL,
+            completed_at TIMESTAMPTZ NOT NULL
+        )
         "
     }
 
+    /// Get the SQL for the full-text search column on posts
+    ///
+    /// Uses the `simple` configuration so product names and exact terms are
+    /// matched as written instead of being stemmed; titles rank above content.
+    pub fn add_search_vector_column_sql(tables: &CollectionTables) -> String {
+        format!("
+        ALTER TABLE {} ADD COLUMN IF NOT EXISTS search_vector tsvector
+        GENERATED ALWAYS AS (
+            setweight(to_tsvector('simple', coalesce(title, '')), 'A') ||
+            setweight(to_tsvector('simple', coalesce(content, '')), 'B')
+        ) STORED
+        ", tables.posts)
+    }
+
+    /// Get SQL for the GIN index backing full-text search
+    pub fn create_search_vector_index_sql(tables: &CollectionTables) -> String {
+        format!(
+            "CREATE INDEX IF NOT EXISTS {} ON {} USING GIN (search_vector)",
+            tables.posts_index("search_vector"),
+            tables.posts
+        )
+    }
+
+    /// Get the SQL for the custom metadata column on posts
+    pub fn add_metadata_column_sql(tables: &CollectionTables) -> String {
+        format!(
+            "ALTER TABLE {} ADD COLUMN IF NOT EXISTS metadata JSONB NOT NULL DEFAULT '{{}}'::jsonb",
+            tables.posts
+        )
+    }
+
+    /// Get SQL for the GIN index backing metadata containment filters
+    ///
+    /// `jsonb_path_ops` only supports `@>`, which is all the equality and
+    /// tag filters use, and is smaller than the default operator class.
+    pub fn create_metadata_index_sql(tables: &CollectionTables) -> String {
+        format!(
+            "CREATE INDEX IF NOT EXISTS {} ON {} USING GIN (metadata jsonb_path_ops)",
+            tables.posts_index("metadata"),
+            tables.posts
+        )
+    }
+
+    /// Get the SQL for the passage table of chunked posts
+    ///
+    /// Byte offsets locate each passage within `posts.content`; passages are
+    /// removed with their post.
+    pub fn create_post_chunks_table_sql(tables: &CollectionTables, dimension: usize) -> String {
+        format!("
+        CREATE TABLE IF NOT EXISTS {} (
+            post_id VARCHAR(255) NOT NULL REFERENCES {}(post_id) ON DELETE CASCADE,
+            chunk_index INTEGER NOT NULL,
+            start_offset INTEGER NOT NULL,
+            end_offset INTEGER NOT NULL,
+            content TEXT NOT NULL,
+            embedding vector({}) NOT NULL,
+            PRIMARY KEY (post_id, chunk_index)
+        )
+        ", tables.chunks, tables.posts, dimension)
+    }
+
+    /// Get SQL for the pgvector IVFFlat index on passage embeddings
+    pub fn create_chunk_vector_index_sql(tables: &CollectionTables) -> String {
+        format!("
+        CREATE INDEX IF NOT EXISTS {}
+        ON {}
+        USING ivfflat (embedding vector_cosine_ops)
+        WITH (lists = 100)
+        ", tables.chunks_index("embedding_ivfflat"), tables.chunks)
+    }
+
     /// Get SQL for creating standard indexes
-    pub fn create_indexes_sql() -> Vec<&'static str> {
-        vec![
-            "CREATE INDEX IF NOT EXISTS idx_posts_post_id ON posts(post_id)",
-            "CREATE INDEX IF NOT EXISTS idx_posts_language ON posts(language)",
-            "CREATE INDEX IF NOT EXISTS idx_posts_frozen ON posts(frozen)",
-            "CREATE INDEX IF NOT EXISTS idx_posts_date_gmt ON posts(date_gmt)",
-            "CREATE INDEX IF NOT EXISTS idx_posts_author ON posts(author_name)",
-        ]
+    pub fn create_indexes_sql(tables: &CollectionTables) -> Vec<String> {
+        ["post_id", "language", "frozen", "date_gmt"]
+            .iter()
+            .map(|column| {
+                format!("CREATE INDEX IF NOT EXISTS {} ON {}({})", tables.posts_index(column), tables.posts, column)
+            })
+            .chain(std::iter::once(format!(
+                "CREATE INDEX IF NOT EXISTS {} ON {}(author_name)",
+                tables.posts_index("author"),
+                tables.posts
+            )))
+            .chain(std::iter::once(Self::create_language_lower_index_sql(tables)))
+            .collect()
+    }
+
+    /// Get SQL for the index backing case-insensitive language filters
+    ///
+    /// Language filters compare `lower(language)`, which the plain column
+    /// index cannot serve.
+    pub fn create_language_lower_index_sql(tables: &CollectionTables) -> String {
+        format!(
+            "CREATE INDEX IF NOT EXISTS {} ON {}(lower(language))",
+            tables.posts_index("language_lower"),
+            tables.posts
+        )
     }
 
     /// Get SQL for creating pgvector IVFFlat index
-    pub fn create_vector_index_sql() -> &'static str {
-        "
-        CREATE INDEX IF NOT EXISTS idx_posts_embedding_ivfflat 
-        ON posts 
+    pub fn create_vector_index_sql(tables: &CollectionTables) -> String {
+        format!("
+        CREATE INDEX IF NOT EXISTS {} 
+        ON {} 
         USING ivfflat (embedding vector_cosine_ops) 
         WITH (lists = 100)
-        "
+        ", tables.posts_index("embedding_ivfflat"), tables.posts)
     }
 
     /// Get SQL for optimizing IVFFlat search parameters
@@ -63,16 +222,17 @@ impl DatabaseSchema {
         "CREATE EXTENSION IF NOT EXISTS vector"
     }
 
-    /// Validate schema requirements
-    pub fn validate_schema_requirements() -> SearchResult<()> {
-        // Check that embedding dimension matches expected size (384)
-        let expected_dimension = 384;
-        
-        // This would be used to validate the schema matches requirements
-        if expected_dimension != 384 {
-            return Err(SearchError::DatabaseError(
-                "Embedding dimension mismatch".to_string()
-            ));
+    /// Maximum dimension pgvector can index with IVFFlat
+    pub const MAX_INDEXED_DIMENSION: usize = 2000;
+
+    /// Validate schema requirements for the given embedding dimension
+    pub fn validate_schema_requirements(dimension: usize) -> SearchResult<()> {
+        if dimension == 0 || dimension > Self::MAX_INDEXED_DIMENSION {
+            return Err(SearchError::DatabaseError(format!(
+                "Embedding dimension {} is not supported by the IVFFlat index (1-{})",
+                dimension,
+                Self::MAX_INDEXED_DIMENSION
+            )));
         }
 
         Ok(())
@@ -121,19 +281,23 @@ impl Default for IVFFlatConfig {
 pub struct Migrations;
 
 impl Migrations {
-    /// Get all migration scripts in order
-    pub fn get_all_migrations() -> Vec<Migration> {
+    /// Get all migration scripts in order for the given embedding dimension
+    ///
+    /// Migrations cover the default collection; named collections create
+    /// their tables on startup.
+    pub fn get_all_migrations(dimension: usize) -> Vec<Migration> {
+        let tables = CollectionTables::default();
         vec![
             Migration {
                 version: 1,
                 name: "create_vector_extension",
-                up_sql: DatabaseSchema::create_vector_extension_sql(),
+                up_sql: DatabaseSchema::create_vector_extension_sql().to_string(),
                 down_sql: "DROP EXTENSION IF EXISTS vector CASCADE",
             },
             Migration {
                 version: 2,
                 name: "create_posts_table",
-                up_sql: DatabaseSchema::create_posts_table_sql(),
+                up_sql: DatabaseSchema::create_posts_table_sql(&tables, dimension),
                 down_sql: "DROP TABLE IF EXISTS posts CASCADE",
             },
             Migration {
@@ -143,7 +307,7 @@ impl Migrations {
                          CREATE INDEX IF NOT EXISTS idx_posts_language ON posts(language);
                          CREATE INDEX IF NOT EXISTS idx_posts_frozen ON posts(frozen);
                          CREATE INDEX IF NOT EXISTS idx_posts_date_gmt ON posts(date_gmt);
-                         CREATE INDEX IF NOT EXISTS idx_posts_author ON posts(author_name);",
+                         CREATE INDEX IF NOT EXISTS idx_posts_author ON posts(author_name);".to_string(),
                 down_sql: "
                     DROP INDEX IF EXISTS idx_posts_post_id;
                     DROP INDEX IF EXISTS idx_posts_language;
@@ -155,9 +319,64 @@ impl Migrations {
             Migration {
                 version: 4,
                 name: "create_vector_index",
-                up_sql: DatabaseSchema::create_vector_index_sql(),
+                up_sql: DatabaseSchema::create_vector_index_sql(&tables),
                 down_sql: "DROP INDEX IF EXISTS idx_posts_embedding_ivfflat",
             },
+            Migration {
+                version: 5,
+                name: "create_gdpr_audit_log",
+                up_sql: format!(
+                    "{};
+                     CREATE INDEX IF NOT EXISTS idx_gdpr_audit_post_id ON gdpr_audit_log(post_id);",
+                    DatabaseSchema::create_gdpr_audit_table_sql().trim()
+                ),
+                down_sql: "DROP TABLE IF EXISTS gdpr_audit_log",
+            },
+            Migration {
+                version: 6,
+                name: "add_full_text_search",
+                up_sql: format!(
+                    "{};
+                     {};",
+                    DatabaseSchema::add_search_vector_column_sql(&tables).trim(),
+                    DatabaseSchema::create_search_vector_index_sql(&tables)
+                ),
+                down_sql: "
+                    DROP INDEX IF EXISTS idx_posts_search_vector;
+                    ALTER TABLE posts DROP COLUMN IF EXISTS search_vector;
+                ",
+            },
+            Migration {
+                version: 7,
+                name: "add_post_metadata",
+                up_sql: format!(
+                    "{};
+                     {};",
+                    DatabaseSchema::add_metadata_column_sql(&tables),
+                    DatabaseSchema::create_metadata_index_sql(&tables)
+                ),
+                down_sql: "
+                    DROP INDEX IF EXISTS idx_posts_metadata;
+                    ALTER TABLE posts DROP COLUMN IF EXISTS metadata;
+                ",
+            },
+            Migration {
+                version: 8,
+                name: "create_post_chunks",
+                up_sql: format!(
+                    "{};
+                     {};",
+                    DatabaseSchema::create_post_chunks_table_sql(&tables, dimension).trim(),
+                    DatabaseSchema::create_chunk_vector_index_sql(&tables).trim()
+                ),
+                down_sql: "DROP TABLE IF EXISTS post_chunks",
+            },
+            Migration {
+                version: 9,
+                name: "create_language_lower_index",
+                up_sql: DatabaseSchema::create_language_lower_index_sql(&tables),
+                down_sql: "DROP INDEX IF EXISTS idx_posts_language_lower",
+            },
         ]
     }
 }
@@ -167,7 +386,7 @@ impl Migrations {
 pub struct Migration {
     pub version: u32,
     pub name: &'static str,
-    pub up_sql: &'static str,
+    pub up_sql: String,
     pub down_sql: &'static str,
 }
 
@@ -177,8 +396,46 @@ mod tests {
 
     #[test]
     fn test_schema_validation() {
-        let result = DatabaseSchema::validate_schema_requirements();
-        assert!(result.is_ok());
+        assert!(DatabaseSchema::validate_schema_requirements(384).is_ok());
+        assert!(DatabaseSchema::validate_schema_requirements(768).is_ok());
+        assert!(DatabaseSchema::validate_schema_requirements(0).is_err());
+        assert!(DatabaseSchema::validate_schema_requirements(4096).is_err());
+    }
+
+    #[test]
+    fn test_posts_table_follows_dimension() {
+        let tables = CollectionTables::default();
+        assert!(DatabaseSchema::create_posts_table_sql(&tables, 384).contains("embedding vector(384)"));
+        assert!(DatabaseSchema::create_posts_table_sql(&tables, 768).contains("embedding vector(768)"));
+
+        assert!(DatabaseSchema::create_post_chunks_table_sql(&tables, 768).contains("embedding vector(768) NOT NULL"));
+
+        let migrations = Migrations::get_all_migrations(768);
+        assert!(migrations[1].up_sql.contains("vector(768)"));
+        assert!(migrations[7].up_sql.contains("vector(768)"));
+    }
+
+    #[test]
+    fn test_collection_tables() {
+        // The default collection keeps the original table and index names
+        let tables = CollectionTables::for_collection(DEFAULT_COLLECTION);
+        assert_eq!(tables, CollectionTables::default());
+        assert!(DatabaseSchema::create_posts_table_sql(&tables, 384).contains("CREATE TABLE IF NOT EXISTS posts ("));
+        assert!(DatabaseSchema::create_search_vector_index_sql(&tables).contains("idx_posts_search_vector ON posts"));
+        assert!(DatabaseSchema::create_chunk_vector_index_sql(&tables).contains("idx_post_chunks_embedding_ivfflat"));
+        assert!(DatabaseSchema::create_indexes_sql(&tables)
+            .contains(&"CREATE INDEX IF NOT EXISTS idx_posts_author ON posts(author_name)".to_string()));
+        assert!(DatabaseSchema::create_indexes_sql(&tables)
+            .contains(&"CREATE INDEX IF NOT EXISTS idx_posts_language_lower ON posts(lower(language))".to_string()));
+
+        let tables = CollectionTables::for_collection("docs");
+        assert_eq!(tables.posts, "docs_posts");
+        assert_eq!(tables.chunks, "docs_post_chunks");
+        let chunks_sql = DatabaseSchema::create_post_chunks_table_sql(&tables, 768);
+        assert!(chunks_sql.contains("CREATE TABLE IF NOT EXISTS docs_post_chunks"));
+        assert!(chunks_sql.contains("REFERENCES docs_posts(post_id)"));
+        assert!(DatabaseSchema::create_vector_index_sql(&tables).contains("idx_docs_posts_embedding_ivfflat"));
+        assert!(DatabaseSchema::add_metadata_column_sql(&tables).contains("DEFAULT '{}'::jsonb"));
     }
 
     #[test]
@@ -201,7 +458,7 @@ mod tests {
 
     #[test]
     fn test_migration_order() {
-        let migrations = Migrations::get_all_migrations();
+        let migrations = Migrations::get_all_migrations(384);
         
         // Ensure migrations are in correct order
         for (i, migration) in migrations.iter().enumerate() {
@@ -209,20 +466,31 @@ mod tests {
         }
 
         // Ensure we have all expected migrations
-        assert_eq!(migrations.len(), 4);
+        assert_eq!(migrations.len(), 9);
         assert_eq!(migrations[0].name, "create_vector_extension");
         assert_eq!(migrations[1].name, "create_posts_table");
         assert_eq!(migrations[2].name, "create_standard_indexes");
         assert_eq!(migrations[3].name, "create_vector_index");
+        assert_eq!(migrations[4].name, "create_gdpr_audit_log");
+        assert_eq!(migrations[5].name, "add_full_text_search");
+        assert_eq!(migrations[6].name, "add_post_metadata");
+        assert_eq!(migrations[7].name, "create_post_chunks");
+        assert_eq!(migrations[8].name, "create_language_lower_index");
     }
 
     #[test]
     fn test_sql_statements_not_empty() {
-        assert!(!DatabaseSchema::create_posts_table_sql().trim().is_empty());
-        assert!(!DatabaseSchema::create_vector_index_sql().trim().is_empty());
+        let tables = CollectionTables::default();
+        assert!(!DatabaseSchema::create_posts_table_sql(&tables, 384).trim().is_empty());
+        assert!(!DatabaseSchema::create_vector_index_sql(&tables).trim().is_empty());
         assert!(!DatabaseSchema::create_vector_extension_sql().trim().is_empty());
+        assert!(!DatabaseSchema::create_gdpr_audit_table_sql().trim().is_empty());
+        assert!(DatabaseSchema::add_search_vector_column_sql(&tables).contains("tsvector"));
+        assert!(DatabaseSchema::create_search_vector_index_sql(&tables).contains("USING GIN"));
+        assert!(DatabaseSchema::add_metadata_column_sql(&tables).contains("JSONB"));
+        assert!(DatabaseSchema::create_metadata_index_sql(&tables).contains("jsonb_path_ops"));
         
-        let indexes = DatabaseSchema::create_indexes_sql();
+        let indexes = DatabaseSchema::create_indexes_sql(&tables);
         assert!(!indexes.is_empty());
         for index_sql in indexes {
             assert!(!index_sql.trim().is_empty());
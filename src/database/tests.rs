@@ -14,6 +14,7 @@ fn create_test_database_config() -> DatabaseConfig {
         supabase_service_key: "test_service_key".to_string(),
         max_connections: 5,
         connection_timeout_secs: 10,
+        vector_dimension: 384,
     }
 }
 
@@ -29,7 +30,8 @@ fn create_test_post(post_id: &str) -> Post {
         frozen: false,
         date_gmt: Utc::now(),
         url: format!("https://example.com/post/{}", post_id),
-        embedding: vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8], // 8-dim for testing
+        embedding: vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8], // 8-dim for testing,
+        metadata: serde_json::Map::new(),
     }
 }
 
@@ -205,7 +207,7 @@ async fn test_vector_search_functionality() {
         
         // Test vector search
         let query_embedding = vec![1.0, 0.0, 0.0, 0.0]; // Should be most similar to vector_1
-        let search_result = db_manager.vector_search(&query_embedding, 10).await;
+        let search_result = db_manager.vector_search(&query_embedding, 10, None).await;
         assert!(search_result.is_ok(), "Vector search failed: {:?}", search_result);
         
         let candidates = search_result.unwrap();
@@ -327,6 +329,7 @@ fn test_database_config_validation() {
         supabase_service_key: "test_key".to_string(),
         max_connections: 12,
         connection_timeout_secs: 30,
+        vector_dimension: 384,
     };
     
     assert!(valid_config.supabase_url.starts_with("postgresql://"));
@@ -339,6 +342,7 @@ fn test_database_config_validation() {
         supabase_service_key: "test_key".to_string(),
         max_connections: 12,
         connection_timeout_secs: 30,
+        vector_dimension: 384,
     };
     
     assert!(postgres_config.supabase_url.starts_with("postgres://"));
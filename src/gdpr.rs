@@ -0,0 +1,180 @@
+//! GDPR erasure orchestration
+//!
+//! Ties together the Postgres delete, the Redis vector/metadata purge, the
+//! top-k cache purge and the audit log so a "right to be forgotten" request is
+//! handled in one place. Every step is retried independently; steps that still
+//! fail are reported on the receipt instead of being silently skipped.
+
+use crate::cache::CacheManager;
+use crate::database::DatabaseManager;
+use crate::error::{SearchError, SearchResult};
+use crate::observability::LoggingService;
+use crate::search::{RetryConfig, RetryExecutor};
+use crate::types::{DeletionReceipt, DeletionStatus};
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use tracing::{info, instrument, warn};
+
+/// Service that erases posts across every store and records an audit entry
+pub struct GdprService {
+    /// Cache manager for Redis entries
+    cache_manager: Arc<CacheManager>,
+    /// Database manager for posts and the audit log
+    database_manager: Arc<DatabaseManager>,
+    /// Retry policy applied to each erasure step
+    retry_executor: RetryExecutor,
+    /// Structured audit logging
+    logging: LoggingService,
+}
+
+impl GdprService {
+    /// Create a new GDPR service with the default retry policy
+    pub fn new(cache_manager: Arc<CacheManager>, database_manager: Arc<DatabaseManager>) -> Self {
+        Self::with_retry_config(cache_manager, database_manager, RetryConfig::default())
+    }
+
+    /// Create a new GDPR service with a custom retry policy
+    pub fn with_retry_config(
+        cache_manager: Arc<CacheManager>,
+        database_manager: Arc<DatabaseManager>,
+        retry_config: RetryConfig,
+    ) -> Self {
+        Self {
+            cache_manager,
+            database_manager,
+            retry_executor: RetryExecutor::with_config(retry_config),
+            logging: LoggingService::new(),
+        }
+    }
+
+    /// Erase a post from Postgres and Redis and return a deletion receipt
+    ///
+    /// Erasure is idempotent: deleting an unknown post still purges any cached
+    /// copies and produces a completed receipt.
+    #[instrument(skip(self))]
+    pub async fn delete_post(&self, post_id: &str) -> SearchResult<DeletionReceipt> {
+        validate_post_id(post_id)?;
+
+        info!("Starting GDPR erasure for post: {}", post_id);
+
+        let mut receipt = DeletionReceipt {
+            receipt_id: uuid::Uuid::new_v4(),
+            post_id: post_id.to_string(),
+            status: DeletionStatus::Completed,
+            postgres_deleted: false,
+            cache_keys_deleted: 0,
+            topk_entries_purged: 0,
+            attempts: 0,
+            errors: Vec::new(),
+            requested_at: chrono::Utc::now(),
+            completed_at: chrono::Utc::now(),
+        };
+        let attempts = AtomicU32::new(0);
+
+        // Step 1: Source of truth
+        match self.retry("postgres", &attempts, || self.database_manager.delete_post(post_id)).await {
+            Ok(deleted) => receipt.postgres_deleted = deleted,
+            Err(e) => receipt.errors.push(format!("postgres: {}", e)),
+        }
+
+        // Step 2: Vector and metadata cache entries
+        match self.retry("redis", &attempts, || self.cache_manager.invalidate_post_data(post_id)).await {
+            Ok(deleted) => receipt.cache_keys_deleted = deleted,
+            Err(e) => receipt.errors.push(format!("redis: {}", e)),
+        }
+
+        // Step 3: Cached result lists that still show the post
+        match self.retry("topk", &attempts, || self.cache_manager.purge_top_k_for_post(post_id)).await {
+            Ok(purged) => receipt.topk_entries_purged = purged,
+            Err(e) => receipt.errors.push(format!("topk: {}", e)),
+        }
+
+        receipt.attempts = attempts.load(Ordering::Relaxed);
+        receipt.completed_at = chrono::Utc::now();
+        receipt.status = status_for(&receipt.errors);
+
+        // Step 4: Durable audit record of the outcome
+        let audit_result = self
+            .retry("audit", &attempts, || self.database_manager.record_gdpr_audit(&receipt))
+            .await;
+        if let Err(e) = audit_result {
+            receipt.errors.push(format!("audit: {}", e));
+            receipt.status = DeletionStatus::Partial;
+        }
+        receipt.attempts = attempts.load(Ordering::Relaxed);
+
+        let success = receipt.status == DeletionStatus::Completed;
+        self.logging.log_gdpr_deletion(post_id, "erase", success);
+
+        if success {
+            info!(
+                "GDPR erasure completed for post: {} (postgres: {}, cache keys: {}, top-k entries: {})",
+                post_id, receipt.postgres_deleted, receipt.cache_keys_deleted, receipt.topk_entries_purged
+            );
+        } else {
+            warn!("GDPR erasure incomplete for post: {}: {}", post_id, receipt.errors.join("; "));
+        }
+
+        Ok(receipt)
+    }
+
+    /// Run one erasure step with retries, counting every attempt
+    async fn retry<F, Fut, T>(&self, step: &str, attempts: &AtomicU32, operation: F) -> SearchResult<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = SearchResult<T>>,
+    {
+        let result = self.retry_executor
+            .execute(|| {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                operation()
+            })
+            .await;
+
+        if let Err(e) = &result {
+            warn!("GDPR erasure step '{}' failed after retries: {}", step, e);
+        }
+
+        result
+    }
+}
+
+/// Overall status given the errors collected from the erasure steps
+fn status_for(errors: &[String]) -> DeletionStatus {
+    if errors.is_empty() {
+        DeletionStatus::Completed
+    } else {
+        DeletionStatus::Partial
+    }
+}
+
+/// Validate the post identifier of an erasure request
+fn validate_post_id(post_id: &str) -> SearchResult<()> {
+    crate::types::validate_post_id(post_id).map_err(SearchError::InvalidRequest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_for_errors() {
+        assert_eq!(status_for(&[]), DeletionStatus::Completed);
+        assert_eq!(status_for(&["redis: timeout".to_string()]), DeletionStatus::Partial);
+    }
+
+    #[test]
+    fn test_validate_post_id() {
+        assert!(validate_post_id("post_123").is_ok());
+        assert!(matches!(validate_post_id(""), Err(SearchError::InvalidRequest(_))));
+        assert!(matches!(validate_post_id("post 123"), Err(SearchError::InvalidRequest(_))));
+        assert!(matches!(validate_post_id(&"a".repeat(256)), Err(SearchError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_deletion_status_serialization() {
+        assert_eq!(serde_json::to_string(&DeletionStatus::Partial).unwrap(), "\"partial\"");
+        assert_eq!(DeletionStatus::Completed.as_str(), "completed");
+    }
+}
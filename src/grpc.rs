@@ -1,77 +1,97 @@
+use futures::StreamExt;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Server;
 use tonic::{Request, Response, Status};
 use tracing::{info, error, warn};
 
+use crate::collections::{Collection, CollectionRegistry};
 use crate::error::{SearchError, SearchResult};
-use crate::search::SearchService;
-
-// Simplified gRPC types for this implementation
-// In production, these would be generated from protobuf files
-
-#[derive(Debug, Clone)]
-pub struct GrpcSearchRequest {
-    pub query: String,
-    pub k: u32,
-    pub min_score: Option<f32>,
-    pub rerank: bool,
-    pub filters: Option<GrpcSearchFilters>,
-}
+use crate::search::MAX_CURSOR_LENGTH;
+use crate::types::{validate_batch_queries, validate_post_id, PassageAggregation, RetrievalMode, ScoreExplanation, SearchMode, SearchSource, DEFAULT_ANSWER_SOURCES, DEFAULT_PASSAGE_TOP_N, DEFAULT_SIMILAR_POSTS, MAX_PASSAGE_TOP_N, SIMILAR_POSTS_QUERY};
 
-#[derive(Debug, Clone)]
-pub struct GrpcSearchFilters {
-    pub language: Option<String>,
-    pub frozen: Option<bool>,
-}
+/// Code generated by `build.rs` from `proto/search.proto`
+pub mod proto {
+    tonic::include_proto!("search");
 
-#[derive(Debug, Clone)]
-pub struct GrpcSearchResponse {
-    pub post_id: String,
-    pub title: String,
-    pub snippet: String,
-    pub score: f32,
-    pub meta: Option<GrpcPostMetadata>,
+    /// Encoded descriptor set served by the reflection service
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("search_descriptor");
 }
 
-#[derive(Debug, Clone)]
-pub struct GrpcPostMetadata {
-    pub author_name: String,
-    pub url: String,
-    pub date: String,
-    pub language: String,
-    pub frozen: bool,
-}
+use proto::semantic_search_service_server::{SemanticSearchService, SemanticSearchServiceServer};
 
-#[derive(Debug, Clone)]
-pub struct HealthCheckRequest {
-    pub service: String,
-}
+// Generated message types under the names used throughout the crate
+pub use proto::{
+    AnswerCitations as GrpcAnswerCitations,
+    AnswerDone as GrpcAnswerDone,
+    AnswerEvent as GrpcAnswerEvent,
+    AnswerRequest as GrpcAnswerRequest,
+    BatchSearchRequest as GrpcBatchSearchRequest,
+    BatchSearchResponse as GrpcBatchSearchResponse,
+    BatchSearchResult as GrpcBatchSearchResult,
+    BatchUpsertPostsRequest as GrpcBatchUpsertPostsRequest,
+    BatchUpsertPostsResponse as GrpcBatchUpsertPostsResponse,
+    Citation as GrpcCitation,
+    DeletePostRequest as GrpcDeletePostRequest,
+    DeletionReceipt as GrpcDeletionReceipt,
+    Explanation as GrpcExplanation,
+    FusionExplanation as GrpcFusionExplanation,
+    HealthCheckRequest, HealthCheckResponse, HealthStatus,
+    HighlightSpan as GrpcHighlightSpan,
+    MetadataFilter as GrpcMetadataFilter,
+    MetadataValue as GrpcMetadataValue,
+    PostMetadata as GrpcPostMetadata,
+    SearchFilters as GrpcSearchFilters,
+    SearchRequest as GrpcSearchRequest,
+    SearchResponse as GrpcSearchResponse,
+    SimilarPostsRequest as GrpcSimilarPostsRequest,
+    SimilarPostsResponse as GrpcSimilarPostsResponse,
+    UpsertPostRequest as GrpcUpsertPostRequest,
+    UpsertPostResponse as GrpcUpsertPostResponse,
+};
 
-#[derive(Debug, Clone)]
-pub struct HealthCheckResponse {
-    pub status: i32,
-    pub message: String,
-    pub timestamp: String,
-}
+/// How often the grpc.health.v1 status is refreshed from the search service
+const HEALTH_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(i32)]
-pub enum HealthStatus {
-    Unknown = 0,
-    Serving = 1,
-    NotServing = 2,
-    ServiceUnknown = 3,
-}
+/// Batch search concurrency used until `with_batch_search` sets the configured one
+const DEFAULT_BATCH_SEARCH_CONCURRENCY: usize = 8;
+
+/// Per-query deadline of batch searches used until `with_batch_search` sets the configured one
+const DEFAULT_BATCH_QUERY_TIMEOUT: Duration = Duration::from_millis(500);
 
 /// gRPC service implementation
+#[derive(Clone)]
 pub struct GrpcSearchService {
-    search_service: Arc<SearchService>,
+    /// Services of every collection, resolved per request
+    collections: Arc<CollectionRegistry>,
+    /// Queries of a batch search that run at the same time
+    batch_search_concurrency: usize,
+    /// Deadline of each query of a batch search
+    batch_query_timeout: Duration,
 }
 
 impl GrpcSearchService {
     /// Create a new gRPC service instance
-    pub fn new(search_service: Arc<SearchService>) -> Self {
-        Self { search_service }
+    pub fn new(collections: Arc<CollectionRegistry>) -> Self {
+        Self {
+            collections,
+            batch_search_concurrency: DEFAULT_BATCH_SEARCH_CONCURRENCY,
+            batch_query_timeout: DEFAULT_BATCH_QUERY_TIMEOUT,
+        }
+    }
+
+    /// Run batch search queries `concurrency` at a time, each within `query_timeout`
+    pub fn with_batch_search(mut self, concurrency: usize, query_timeout: Duration) -> Self {
+        self.batch_search_concurrency = concurrency;
+        self.batch_query_timeout = query_timeout;
+        self
+    }
+
+    /// Resolve the collection named by a request
+    fn collection(&self, name: Option<&str>) -> SearchResult<Arc<Collection>> {
+        self.collections.get(name.filter(|name| !name.is_empty()))
     }
 
     /// Perform streaming semantic search
@@ -94,16 +114,24 @@ impl GrpcSearchService {
         let internal_request = match convert_grpc_to_internal_request(request) {
             Ok(req) => req,
             Err(e) => {
-                error!("Failed to convert gRPC request: {}", e);
-                return Err(Status::internal("Request conversion failed"));
+                warn!("Failed to convert gRPC request: {}", e);
+                return Err(convert_search_error_to_grpc_status(e));
             }
         };
 
+        // Nested filter expressions are validated on the internal form
+        if let Some(Err(validation_error)) = internal_request.filters.as_ref().map(|f| f.validate()) {
+            warn!("Invalid gRPC filters: {}", validation_error);
+            return Err(Status::invalid_argument(validation_error));
+        }
+
+        let collection = self.collection(internal_request.collection.as_deref()).map_err(convert_collection_error_to_grpc_status)?;
+
         // Create a channel for streaming responses
         let (tx, rx) = tokio::sync::mpsc::channel(128);
 
         // Clone the search service for the async task
-        let search_service = self.search_service.clone();
+        let search_service = collection.search_service().clone();
 
         // Spawn async task to perform search and stream results
         tokio::spawn(async move {
@@ -120,10 +148,6 @@ impl GrpcSearchService {
                             warn!("gRPC client disconnected during streaming");
                             break;
                         }
-                        
-                        // Add small delay between results to demonstrate streaming
-                        // In production, this could be removed or made configurable
-                        tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
                     }
                 }
                 Err(e) => {
@@ -143,6 +167,221 @@ impl GrpcSearchService {
         Ok(ReceiverStream::new(rx))
     }
 
+    /// Run a batch of searches with one embedding call
+    ///
+    /// Each query gets its own entry in the response, holding its results or
+    /// why it was rejected or failed, without failing the rest of the batch.
+    pub async fn batch_semantic_search(
+        &self,
+        request: GrpcBatchSearchRequest,
+    ) -> Result<GrpcBatchSearchResponse, Status> {
+        info!("gRPC batch search request for {} queries", request.queries.len());
+
+        let batch_collection = request.collection.filter(|collection| !collection.is_empty());
+        validate_batch_queries(
+            batch_collection.as_deref(),
+            request
+                .queries
+                .iter()
+                .map(|query| query.collection.as_deref().filter(|collection| !collection.is_empty())),
+        )
+        .map_err(Status::invalid_argument)?;
+        let collection = self.collection(batch_collection.as_deref()).map_err(convert_collection_error_to_grpc_status)?;
+
+        // Invalid queries are answered without being searched
+        let queries = request
+            .queries
+            .into_iter()
+            .map(|query| convert_grpc_batch_query(query).map_err(SearchError::InvalidRequest))
+            .collect();
+
+        let queries = collection
+            .search_service()
+            .semantic_search_batch(queries, self.batch_search_concurrency, self.batch_query_timeout)
+            .await
+            .into_iter()
+            .map(|outcome| match outcome {
+                Ok(results) => GrpcBatchSearchResult {
+                    results: results.into_iter().map(convert_internal_to_grpc_response).collect(),
+                    error: None,
+                },
+                Err(e) => {
+                    error!("gRPC batch search query failed: {}", e);
+                    GrpcBatchSearchResult {
+                        results: Vec::new(),
+                        error: Some(convert_search_error_to_grpc_status(e).message().to_string()),
+                    }
+                }
+            })
+            .collect();
+
+        Ok(GrpcBatchSearchResponse { queries })
+    }
+
+    /// Find posts similar to a stored post; unknown posts are NOT_FOUND
+    pub async fn similar_posts(
+        &self,
+        request: GrpcSimilarPostsRequest,
+    ) -> Result<GrpcSimilarPostsResponse, Status> {
+        info!("gRPC similar posts request for post: {}", request.post_id);
+
+        let internal_request = convert_grpc_to_internal_similar_posts_request(request).map_err(|e| {
+            warn!("Invalid gRPC similar posts request: {}", e);
+            convert_search_error_to_grpc_status(e)
+        })?;
+        let collection = self.collection(internal_request.collection.as_deref()).map_err(convert_collection_error_to_grpc_status)?;
+
+        match collection.search_service().similar_posts(&internal_request).await {
+            Ok(Some(results)) => Ok(GrpcSimilarPostsResponse {
+                results: results.into_iter().map(convert_internal_to_grpc_response).collect(),
+            }),
+            Ok(None) => Err(Status::not_found(format!("Post '{}' not found", internal_request.post_id))),
+            Err(e) => {
+                error!("gRPC similar posts search failed: {}", e);
+                Err(convert_search_error_to_grpc_status(e))
+            }
+        }
+    }
+
+    /// Answer a question from search results, streaming the answer
+    ///
+    /// The stream starts with the citations, continues with the answer text
+    /// as it is generated and ends with a done event; a generation failure
+    /// ends it with an error status instead.
+    pub async fn answer_stream(
+        &self,
+        request: GrpcAnswerRequest,
+    ) -> Result<ReceiverStream<Result<GrpcAnswerEvent, Status>>, Status> {
+        let collection = self.collection(request.collection.as_deref()).map_err(convert_collection_error_to_grpc_status)?;
+        let answer_service = collection
+            .answer_service()
+            .cloned()
+            .ok_or_else(|| Status::unimplemented("No answer generator is configured"))?;
+
+        info!("gRPC answer request: query='{}', k={}", request.query, request.k);
+
+        let internal_request = convert_grpc_to_internal_answer_request(request).map_err(|e| {
+            warn!("Invalid gRPC answer request: {}", e);
+            convert_search_error_to_grpc_status(e)
+        })?;
+
+        let answer = answer_service.answer_stream(internal_request).await.map_err(|e| {
+            error!("gRPC answer failed: {}", e);
+            convert_search_error_to_grpc_status(e)
+        })?;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(32);
+
+        tokio::spawn(async move {
+            let citations = GrpcAnswerCitations {
+                citations: answer.citations.into_iter().map(convert_internal_to_grpc_citation).collect(),
+            };
+            if tx.send(Ok(answer_event(proto::answer_event::Event::Citations(citations)))).await.is_err() {
+                return;
+            }
+
+            let mut tokens = answer.tokens;
+            while let Some(token) = tokens.next().await {
+                let event = match token {
+                    Ok(text) => Ok(answer_event(proto::answer_event::Event::Token(text))),
+                    Err(e) => {
+                        error!("gRPC answer generation failed: {}", e);
+                        let _ = tx.send(Err(convert_search_error_to_grpc_status(e))).await;
+                        return;
+                    }
+                };
+
+                if tx.send(event).await.is_err() {
+                    // Client disconnected, stop generating
+                    warn!("gRPC client disconnected during answer streaming");
+                    return;
+                }
+            }
+
+            let done = GrpcAnswerDone { model: answer.model };
+            let _ = tx.send(Ok(answer_event(proto::answer_event::Event::Done(done)))).await;
+        });
+
+        Ok(ReceiverStream::new(rx))
+    }
+
+    /// Insert or update a single post
+    pub async fn upsert_post(
+        &self,
+        request: GrpcUpsertPostRequest,
+    ) -> Result<GrpcUpsertPostResponse, Status> {
+        info!("gRPC upsert request for post: {}", request.post_id);
+
+        let internal_request = convert_grpc_to_internal_upsert(request)
+            .map_err(Status::invalid_argument)?;
+        let collection = self.collection(internal_request.collection.as_deref()).map_err(convert_collection_error_to_grpc_status)?;
+
+        collection
+            .ingestion_service()
+            .upsert_post(internal_request)
+            .await
+            .map(convert_internal_to_grpc_upsert_response)
+            .map_err(|e| {
+                error!("gRPC upsert failed: {}", e);
+                convert_search_error_to_grpc_status(e)
+            })
+    }
+
+    /// Insert or update a batch of posts
+    pub async fn batch_upsert_posts(
+        &self,
+        request: GrpcBatchUpsertPostsRequest,
+    ) -> Result<GrpcBatchUpsertPostsResponse, Status> {
+        info!("gRPC batch upsert request for {} posts", request.posts.len());
+
+        let internal_request = crate::types::BatchUpsertPostsRequest {
+            posts: request.posts
+                .into_iter()
+                .map(convert_grpc_to_internal_upsert)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Status::invalid_argument)?,
+            collection: request.collection.filter(|collection| !collection.is_empty()),
+        };
+        internal_request.validate_collection().map_err(Status::invalid_argument)?;
+        let collection = self.collection(internal_request.collection.as_deref()).map_err(convert_collection_error_to_grpc_status)?;
+
+        let posts = collection
+            .ingestion_service()
+            .upsert_posts(internal_request.posts)
+            .await
+            .map_err(|e| {
+                error!("gRPC batch upsert failed: {}", e);
+                convert_search_error_to_grpc_status(e)
+            })?;
+
+        Ok(GrpcBatchUpsertPostsResponse {
+            posts: posts.into_iter().map(convert_internal_to_grpc_upsert_response).collect(),
+        })
+    }
+
+    /// Erase a post from every store and return the deletion receipt
+    ///
+    /// A partial erasure is still returned as a receipt; callers check its
+    /// status and retry the request.
+    pub async fn delete_post(
+        &self,
+        request: GrpcDeletePostRequest,
+    ) -> Result<GrpcDeletionReceipt, Status> {
+        info!("gRPC GDPR erasure request for post: {}", request.post_id);
+
+        let collection = self.collection(request.collection.as_deref()).map_err(convert_collection_error_to_grpc_status)?;
+
+        collection
+            .gdpr_service()
+            .delete_post(&request.post_id)
+            .await
+            .map(convert_internal_to_grpc_receipt)
+            .map_err(|e| {
+                error!("gRPC GDPR erasure failed: {}", e);
+                convert_search_error_to_grpc_status(e)
+            })
+    }
+
     /// Health check endpoint
     pub async fn health_check(
         &self,
@@ -150,8 +389,8 @@ impl GrpcSearchService {
     ) -> Result<HealthCheckResponse, Status> {
         info!("gRPC health check request for service: '{}'", request.service);
 
-        // Perform health check on the search service
-        let health_result = self.search_service.health_check().await;
+        // Perform health check on the search service; the stores are shared by every collection
+        let health_result = self.collections.default_collection().search_service().health_check().await;
         
         let (status, message) = match health_result {
             Ok(_) => (HealthStatus::Serving, "Service is healthy".to_string()),
@@ -171,6 +410,119 @@ impl GrpcSearchService {
     }
 }
 
+#[tonic::async_trait]
+impl SemanticSearchService for GrpcSearchService {
+    type SemanticSearchStream = ReceiverStream<Result<GrpcSearchResponse, Status>>;
+
+    async fn semantic_search(
+        &self,
+        request: Request<GrpcSearchRequest>,
+    ) -> Result<Response<Self::SemanticSearchStream>, Status> {
+        self.semantic_search_stream(request.into_inner()).await.map(Response::new)
+    }
+
+    async fn batch_semantic_search(
+        &self,
+        request: Request<GrpcBatchSearchRequest>,
+    ) -> Result<Response<GrpcBatchSearchResponse>, Status> {
+        GrpcSearchService::batch_semantic_search(self, request.into_inner()).await.map(Response::new)
+    }
+
+    async fn similar_posts(
+        &self,
+        request: Request<GrpcSimilarPostsRequest>,
+    ) -> Result<Response<GrpcSimilarPostsResponse>, Status> {
+        GrpcSearchService::similar_posts(self, request.into_inner()).await.map(Response::new)
+    }
+
+    async fn health_check(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        GrpcSearchService::health_check(self, request.into_inner()).await.map(Response::new)
+    }
+
+    async fn upsert_post(
+        &self,
+        request: Request<GrpcUpsertPostRequest>,
+    ) -> Result<Response<GrpcUpsertPostResponse>, Status> {
+        GrpcSearchService::upsert_post(self, request.into_inner()).await.map(Response::new)
+    }
+
+    async fn batch_upsert_posts(
+        &self,
+        request: Request<GrpcBatchUpsertPostsRequest>,
+    ) -> Result<Response<GrpcBatchUpsertPostsResponse>, Status> {
+        GrpcSearchService::batch_upsert_posts(self, request.into_inner()).await.map(Response::new)
+    }
+
+    async fn delete_post(
+        &self,
+        request: Request<GrpcDeletePostRequest>,
+    ) -> Result<Response<GrpcDeletionReceipt>, Status> {
+        GrpcSearchService::delete_post(self, request.into_inner()).await.map(Response::new)
+    }
+
+    type AnswerStream = ReceiverStream<Result<GrpcAnswerEvent, Status>>;
+
+    async fn answer(
+        &self,
+        request: Request<GrpcAnswerRequest>,
+    ) -> Result<Response<Self::AnswerStream>, Status> {
+        self.answer_stream(request.into_inner()).await.map(Response::new)
+    }
+}
+
+/// Serve the search service over gRPC together with the standard
+/// `grpc.health.v1` health service and server reflection
+pub async fn serve(service: GrpcSearchService, addr: SocketAddr) -> SearchResult<()> {
+    let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
+    health_reporter
+        .set_serving::<SemanticSearchServiceServer<GrpcSearchService>>()
+        .await;
+
+    // Keep the health service in sync with the backing stores
+    let search_service = service.collections.default_collection().search_service().clone();
+    let health_task = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(HEALTH_REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            match search_service.health_check().await {
+                Ok(_) => {
+                    health_reporter
+                        .set_serving::<SemanticSearchServiceServer<GrpcSearchService>>()
+                        .await
+                }
+                Err(e) => {
+                    warn!("gRPC health check failed: {}", e);
+                    health_reporter
+                        .set_not_serving::<SemanticSearchServiceServer<GrpcSearchService>>()
+                        .await
+                }
+            }
+        }
+    });
+
+    let reflection_service = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build()
+        .map_err(|e| SearchError::ConfigError(format!("Failed to build gRPC reflection service: {}", e)))?;
+
+    info!("gRPC server listening on {}", addr);
+
+    let result = Server::builder()
+        .add_service(health_service)
+        .add_service(reflection_service)
+        .add_service(SemanticSearchServiceServer::new(service))
+        .serve(addr)
+        .await
+        .map_err(|e| SearchError::Internal(format!("gRPC server error: {}", e)));
+
+    health_task.abort();
+    result
+}
+
 /// Validate gRPC search request
 fn validate_grpc_search_request(request: &GrpcSearchRequest) -> Result<(), String> {
     // Validate query
@@ -207,6 +559,34 @@ fn validate_grpc_search_request(request: &GrpcSearchRequest) -> Result<(), Strin
         }
     }
     
+    // Validate fusion weight (only used by hybrid retrieval)
+    if let Some(weight) = request.fusion_weight {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err("Parameter 'fusion_weight' must be between 0.0 and 1.0".to_string());
+        }
+    }
+    
+    // Validate MMR diversification weight
+    if let Some(lambda) = request.mmr_lambda {
+        if !(0.0..=1.0).contains(&lambda) {
+            return Err("Parameter 'mmr_lambda' must be between 0.0 and 1.0".to_string());
+        }
+    }
+    
+    // Validate passage aggregation
+    if let Some(top_n) = request.passage_top_n {
+        if top_n == 0 || top_n > MAX_PASSAGE_TOP_N {
+            return Err(format!("Parameter 'passage_top_n' must be between 1 and {}", MAX_PASSAGE_TOP_N));
+        }
+    }
+    
+    // Cursors are verified by the search service; reject obvious garbage early
+    if let Some(cursor) = &request.cursor {
+        if !is_well_formed_cursor(cursor) {
+            return Err("Parameter 'cursor' is not a valid cursor".to_string());
+        }
+    }
+    
     // Validate filters
     if let Some(filters) = &request.filters {
         if let Some(language) = &filters.language {
@@ -292,6 +672,11 @@ fn contains_malicious_patterns(text: &str) -> bool {
     false
 }
 
+/// Check the shape of a pagination cursor (hex, bounded length)
+fn is_well_formed_cursor(cursor: &str) -> bool {
+    cursor.len() <= MAX_CURSOR_LENGTH && cursor.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 /// Validate language code format (reused from HTTP server)
 fn is_valid_language_code(language: &str) -> bool {
     // Check basic format (2-10 characters, lowercase letters and hyphens only)
@@ -305,10 +690,7 @@ fn is_valid_language_code(language: &str) -> bool {
 
 /// Convert gRPC request to internal request format
 fn convert_grpc_to_internal_request(grpc_request: GrpcSearchRequest) -> SearchResult<crate::types::SearchRequest> {
-    let filters = grpc_request.filters.map(|f| crate::types::SearchFilters {
-        language: f.language,
-        frozen: f.frozen,
-    });
+    let filters = grpc_request.filters.map(convert_grpc_filters).transpose()?;
 
     Ok(crate::types::SearchRequest {
         query: grpc_request.query,
@@ -316,7 +698,192 @@ fn convert_grpc_to_internal_request(grpc_request: GrpcSearchRequest) -> SearchRe
         min_score: grpc_request.min_score,
         rerank: grpc_request.rerank,
         filters,
+        mode: convert_grpc_retrieval_mode(grpc_request.mode),
+        fusion_weight: grpc_request.fusion_weight,
+        mmr_lambda: grpc_request.mmr_lambda,
+        passage_aggregation: convert_grpc_passage_aggregation(grpc_request.passage_aggregation, grpc_request.passage_top_n),
+        cursor: grpc_request.cursor.filter(|cursor| !cursor.is_empty()),
+        explain: grpc_request.explain,
+        collection: grpc_request.collection.filter(|collection| !collection.is_empty()),
+    })
+}
+
+/// Validate and convert one query of a gRPC batch search
+fn convert_grpc_batch_query(grpc_request: GrpcSearchRequest) -> Result<crate::types::SearchRequest, String> {
+    validate_grpc_search_request(&grpc_request)?;
+
+    let request = convert_grpc_to_internal_request(grpc_request).map_err(|e| match e {
+        SearchError::InvalidRequest(message) => message,
+        other => other.to_string(),
+    })?;
+    if let Some(filters) = &request.filters {
+        filters.validate()?;
+    }
+
+    Ok(request)
+}
+
+/// Convert and validate a gRPC similar posts request
+///
+/// The post id is checked as an identifier, and the search parameters like a
+/// search with a fixed query.
+fn convert_grpc_to_internal_similar_posts_request(
+    grpc_request: GrpcSimilarPostsRequest,
+) -> SearchResult<crate::types::SimilarPostsRequest> {
+    let k = if grpc_request.k == 0 { DEFAULT_SIMILAR_POSTS } else { grpc_request.k };
+
+    validate_post_id(&grpc_request.post_id).map_err(SearchError::InvalidRequest)?;
+    validate_grpc_search_request(&GrpcSearchRequest {
+        query: SIMILAR_POSTS_QUERY.to_string(),
+        k,
+        min_score: grpc_request.min_score,
+        filters: grpc_request.filters.clone(),
+        ..Default::default()
+    })
+    .map_err(SearchError::InvalidRequest)?;
+
+    let request = crate::types::SimilarPostsRequest {
+        post_id: grpc_request.post_id,
+        k,
+        min_score: grpc_request.min_score,
+        filters: grpc_request.filters.map(convert_grpc_filters).transpose()?,
+        exclude_near_duplicates: grpc_request.exclude_near_duplicates,
+        collection: grpc_request.collection.filter(|collection| !collection.is_empty()),
+    };
+
+    if let Some(filters) = &request.filters {
+        filters.validate().map_err(SearchError::InvalidRequest)?;
+    }
+
+    Ok(request)
+}
+
+/// Convert and validate a gRPC answer request
+///
+/// The search part is checked with the search request rules.
+fn convert_grpc_to_internal_answer_request(grpc_request: GrpcAnswerRequest) -> SearchResult<crate::types::AnswerRequest> {
+    let k = if grpc_request.k == 0 { DEFAULT_ANSWER_SOURCES } else { grpc_request.k };
+
+    validate_grpc_search_request(&GrpcSearchRequest {
+        query: grpc_request.query.clone(),
+        k,
+        min_score: grpc_request.min_score,
+        rerank: grpc_request.rerank,
+        filters: grpc_request.filters.clone(),
+        mode: grpc_request.mode,
+        ..Default::default()
     })
+    .map_err(SearchError::InvalidRequest)?;
+
+    let request = crate::types::AnswerRequest {
+        query: grpc_request.query,
+        k,
+        min_score: grpc_request.min_score,
+        rerank: grpc_request.rerank,
+        filters: grpc_request.filters.map(convert_grpc_filters).transpose()?,
+        mode: convert_grpc_retrieval_mode(grpc_request.mode),
+        max_context_tokens: grpc_request.max_context_tokens,
+        max_answer_tokens: grpc_request.max_answer_tokens,
+        stream: true,
+        collection: grpc_request.collection.filter(|collection| !collection.is_empty()),
+    };
+
+    request.validate().map_err(SearchError::InvalidRequest)?;
+    if let Some(filters) = &request.filters {
+        filters.validate().map_err(SearchError::InvalidRequest)?;
+    }
+
+    Ok(request)
+}
+
+/// Convert gRPC search filters, including nested combinators
+fn convert_grpc_filters(filters: GrpcSearchFilters) -> SearchResult<crate::types::SearchFilters> {
+    let non_empty = |values: Vec<String>| if values.is_empty() { None } else { Some(values) };
+
+    let nested = |filters: Vec<GrpcSearchFilters>| -> SearchResult<Option<Vec<crate::types::SearchFilters>>> {
+        if filters.is_empty() {
+            return Ok(None);
+        }
+        filters.into_iter().map(convert_grpc_filters).collect::<SearchResult<Vec<_>>>().map(Some)
+    };
+
+    Ok(crate::types::SearchFilters {
+        language: filters.language,
+        frozen: filters.frozen,
+        date_after: filters.date_after.as_deref().map(parse_grpc_filter_date).transpose()?,
+        date_before: filters.date_before.as_deref().map(parse_grpc_filter_date).transpose()?,
+        authors: non_empty(filters.authors),
+        exclude_authors: non_empty(filters.exclude_authors),
+        url_domains: non_empty(filters.url_domains),
+        url_prefix: filters.url_prefix,
+        and: nested(filters.and)?,
+        or: nested(filters.or)?,
+        not: filters.not.map(|not| convert_grpc_filters(*not)).transpose()?.map(Box::new),
+        metadata: if filters.metadata.is_empty() {
+            None
+        } else {
+            Some(filters.metadata.into_iter().map(convert_grpc_metadata_filter).collect::<SearchResult<_>>()?)
+        },
+    })
+}
+
+/// Convert a gRPC condition on custom post metadata
+fn convert_grpc_metadata_filter(filter: GrpcMetadataFilter) -> SearchResult<crate::types::MetadataFilter> {
+    Ok(crate::types::MetadataFilter {
+        key: filter.key,
+        eq: filter.eq.map(convert_grpc_metadata_value).transpose()?,
+        r#in: if filter.r#in.is_empty() {
+            None
+        } else {
+            Some(filter.r#in.into_iter().map(convert_grpc_metadata_value).collect::<SearchResult<_>>()?)
+        },
+        contains: filter.contains.map(convert_grpc_metadata_value).transpose()?,
+        gt: filter.gt,
+        gte: filter.gte,
+        lt: filter.lt,
+        lte: filter.lte,
+    })
+}
+
+/// Convert a gRPC scalar metadata value to JSON
+fn convert_grpc_metadata_value(value: GrpcMetadataValue) -> SearchResult<serde_json::Value> {
+    use proto::metadata_value::Kind;
+
+    match value.kind {
+        Some(Kind::StringValue(value)) => Ok(serde_json::Value::String(value)),
+        Some(Kind::NumberValue(value)) => serde_json::Number::from_f64(value)
+            .map(serde_json::Value::Number)
+            .ok_or_else(|| SearchError::InvalidRequest("Metadata filter numbers must be finite".to_string())),
+        Some(Kind::BoolValue(value)) => Ok(serde_json::Value::Bool(value)),
+        None => Err(SearchError::InvalidRequest("Metadata filter value is empty".to_string())),
+    }
+}
+
+/// Parse an RFC 3339 date from a gRPC filter
+fn parse_grpc_filter_date(date: &str) -> SearchResult<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(date)
+        .map(|date| date.with_timezone(&chrono::Utc))
+        .map_err(|e| SearchError::InvalidRequest(format!("Invalid filter date '{}': {}", date, e)))
+}
+
+/// Convert a gRPC retrieval mode; unknown values fall back to vector search
+fn convert_grpc_retrieval_mode(mode: i32) -> RetrievalMode {
+    match proto::RetrievalMode::try_from(mode) {
+        Ok(proto::RetrievalMode::Lexical) => RetrievalMode::Lexical,
+        Ok(proto::RetrievalMode::Hybrid) => RetrievalMode::Hybrid,
+        _ => RetrievalMode::Vector,
+    }
+}
+
+/// Convert the gRPC passage aggregation; `passage_top_n` only applies to MEAN_TOP_N
+fn convert_grpc_passage_aggregation(aggregation: i32, top_n: Option<u32>) -> Option<PassageAggregation> {
+    match proto::PassageAggregation::try_from(aggregation) {
+        Ok(proto::PassageAggregation::Max) => Some(PassageAggregation::Max),
+        Ok(proto::PassageAggregation::MeanTopN) => {
+            Some(PassageAggregation::MeanTopN(top_n.unwrap_or(DEFAULT_PASSAGE_TOP_N)))
+        }
+        _ => None,
+    }
 }
 
 /// Convert internal response to gRPC response format
@@ -325,6 +892,14 @@ fn convert_internal_to_grpc_response(internal_response: crate::types::SearchResp
         post_id: internal_response.post_id,
         title: internal_response.title,
         snippet: internal_response.snippet,
+        highlights: internal_response
+            .highlights
+            .into_iter()
+            .map(|span| GrpcHighlightSpan {
+                start: span.start,
+                end: span.end,
+            })
+            .collect(),
         score: internal_response.score,
         meta: Some(GrpcPostMetadata {
             author_name: internal_response.meta.author_name,
@@ -332,13 +907,160 @@ fn convert_internal_to_grpc_response(internal_response: crate::types::SearchResp
             date: internal_response.meta.date.to_rfc3339(),
             language: internal_response.meta.language,
             frozen: internal_response.meta.frozen,
+            metadata_json: encode_grpc_post_metadata(&internal_response.meta.metadata),
+        }),
+        next_cursor: internal_response.next_cursor.unwrap_or_default(),
+        explanation: internal_response.explanation.map(convert_internal_to_grpc_explanation),
+    }
+}
+
+/// Convert a score explanation to gRPC format
+fn convert_internal_to_grpc_explanation(explanation: ScoreExplanation) -> GrpcExplanation {
+    let search_mode = match explanation.search_mode {
+        SearchMode::Full => proto::SearchMode::Full,
+        SearchMode::PostgresOnly => proto::SearchMode::PostgresOnly,
+        SearchMode::CacheOnly => proto::SearchMode::CacheOnly,
+        SearchMode::Degraded => proto::SearchMode::Degraded,
+    };
+
+    GrpcExplanation {
+        search_mode: search_mode as i32,
+        sources: explanation
+            .sources
+            .into_iter()
+            .map(|source| {
+                let source = match source {
+                    SearchSource::Redis => proto::SearchSource::Redis,
+                    SearchSource::Postgres => proto::SearchSource::Postgres,
+                    SearchSource::Lexical => proto::SearchSource::Lexical,
+                    SearchSource::Hybrid => proto::SearchSource::Hybrid,
+                };
+                source as i32
+            })
+            .collect(),
+        vector_score: explanation.vector_score,
+        lexical_score: explanation.lexical_score,
+        fusion: explanation.fusion.map(|fusion| GrpcFusionExplanation {
+            vector_weight: fusion.vector_weight,
+            vector_rank: fusion.vector_rank,
+            lexical_rank: fusion.lexical_rank,
+            vector_contribution: fusion.vector_contribution,
+            lexical_contribution: fusion.lexical_contribution,
         }),
+        retrieval_score: explanation.retrieval_score,
+        rerank_score: explanation.rerank_score,
+        filters_passed: explanation.filters_passed,
+    }
+}
+
+/// Convert an answer citation to gRPC format
+fn convert_internal_to_grpc_citation(citation: crate::types::Citation) -> GrpcCitation {
+    GrpcCitation {
+        index: citation.index,
+        post_id: citation.post_id,
+        title: citation.title,
+        url: citation.url,
+        score: citation.score,
+    }
+}
+
+/// Wrap one event of a streamed answer
+fn answer_event(event: proto::answer_event::Event) -> GrpcAnswerEvent {
+    GrpcAnswerEvent { event: Some(event) }
+}
+
+/// Convert gRPC ingestion request to internal request format
+fn convert_grpc_to_internal_upsert(grpc_request: GrpcUpsertPostRequest) -> Result<crate::types::UpsertPostRequest, String> {
+    let meta = grpc_request.meta
+        .ok_or_else(|| format!("Missing metadata for post {}", grpc_request.post_id))?;
+
+    let date = chrono::DateTime::parse_from_rfc3339(&meta.date)
+        .map_err(|e| format!("Invalid date for post {}: {}", grpc_request.post_id, e))?
+        .with_timezone(&chrono::Utc);
+
+    let metadata = parse_grpc_post_metadata(&meta.metadata_json)
+        .map_err(|e| format!("Invalid metadata_json for post {}: {}", grpc_request.post_id, e))?;
+
+    Ok(crate::types::UpsertPostRequest {
+        post_id: grpc_request.post_id,
+        title: grpc_request.title,
+        content: grpc_request.content,
+        meta: crate::types::PostMetadata {
+            author_name: meta.author_name,
+            url: meta.url,
+            date,
+            language: meta.language,
+            frozen: meta.frozen,
+            metadata,
+        },
+        collection: grpc_request.collection.filter(|collection| !collection.is_empty()),
+    })
+}
+
+/// Parse the JSON object carried in `PostMetadata.metadata_json`; empty means none
+fn parse_grpc_post_metadata(json: &str) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    if json.trim().is_empty() {
+        return Ok(serde_json::Map::new());
+    }
+
+    match serde_json::from_str(json) {
+        Ok(serde_json::Value::Object(metadata)) => Ok(metadata),
+        Ok(_) => Err("expected a JSON object".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Encode custom post metadata for `PostMetadata.metadata_json`
+fn encode_grpc_post_metadata(metadata: &serde_json::Map<String, serde_json::Value>) -> String {
+    if metadata.is_empty() {
+        String::new()
+    } else {
+        serde_json::Value::Object(metadata.clone()).to_string()
+    }
+}
+
+/// Convert internal ingestion result to gRPC response format
+fn convert_internal_to_grpc_upsert_response(response: crate::types::UpsertPostResponse) -> GrpcUpsertPostResponse {
+    GrpcUpsertPostResponse {
+        post_id: response.post_id,
+        embedding_model: response.embedding_model,
+        cached: response.cached,
+    }
+}
+
+/// Convert internal deletion receipt to gRPC format
+fn convert_internal_to_grpc_receipt(receipt: crate::types::DeletionReceipt) -> GrpcDeletionReceipt {
+    let status = match receipt.status {
+        crate::types::DeletionStatus::Completed => proto::DeletionStatus::Completed,
+        crate::types::DeletionStatus::Partial => proto::DeletionStatus::Partial,
+    };
+
+    GrpcDeletionReceipt {
+        receipt_id: receipt.receipt_id.to_string(),
+        post_id: receipt.post_id,
+        status: status as i32,
+        postgres_deleted: receipt.postgres_deleted,
+        cache_keys_deleted: receipt.cache_keys_deleted,
+        topk_entries_purged: receipt.topk_entries_purged,
+        attempts: receipt.attempts,
+        errors: receipt.errors,
+        requested_at: receipt.requested_at.to_rfc3339(),
+        completed_at: receipt.completed_at.to_rfc3339(),
+    }
+}
+
+/// Convert a collection lookup error; unknown collections are NOT_FOUND
+fn convert_collection_error_to_grpc_status(error: SearchError) -> Status {
+    match error {
+        SearchError::InvalidRequest(message) => Status::not_found(message),
+        other => convert_search_error_to_grpc_status(other),
     }
 }
 
 /// Convert search error to gRPC status
 fn convert_search_error_to_grpc_status(error: SearchError) -> Status {
     match error {
+        SearchError::InvalidRequest(msg) => Status::invalid_argument(msg),
         SearchError::ModelError(msg) => Status::unavailable(format!("ML service unavailable: {}", msg)),
         SearchError::RedisError(msg) => Status::unavailable(format!("Cache service unavailable: {}", msg)),
         SearchError::DatabaseError(msg) => Status::unavailable(format!("Database service unavailable: {}", msg)),
@@ -364,6 +1086,14 @@ mod tests {
             min_score: Some(0.5),
             rerank: false,
             filters: None,
+            mode: proto::RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: proto::PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
         
         assert!(validate_grpc_search_request(&request).is_ok());
@@ -377,6 +1107,14 @@ mod tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: proto::RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: proto::PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
         
         let result = validate_grpc_search_request(&request);
@@ -392,6 +1130,14 @@ mod tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: proto::RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: proto::PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
         
         let result = validate_grpc_search_request(&request);
@@ -407,6 +1153,14 @@ mod tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: proto::RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: proto::PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
         
         let result = validate_grpc_search_request(&request);
@@ -422,6 +1176,14 @@ mod tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: proto::RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: proto::PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
         
         let result = validate_grpc_search_request(&request);
@@ -437,6 +1199,14 @@ mod tests {
             min_score: Some(-0.1),
             rerank: false,
             filters: None,
+            mode: proto::RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: proto::PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
         
         let result = validate_grpc_search_request(&request);
@@ -452,6 +1222,14 @@ mod tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: proto::RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: proto::PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
         
         let result = validate_grpc_search_request(&request);
@@ -469,7 +1247,16 @@ mod tests {
             filters: Some(GrpcSearchFilters {
                 language: Some("en".to_string()),
                 frozen: Some(false),
+                ..Default::default()
             }),
+            mode: proto::RetrievalMode::Hybrid as i32,
+            fusion_weight: Some(0.7),
+            mmr_lambda: Some(0.6),
+            passage_aggregation: proto::PassageAggregation::MeanTopN as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: true,
+            collection: Some("docs".to_string()),
         };
         
         let internal_request = convert_grpc_to_internal_request(grpc_request).unwrap();
@@ -478,18 +1265,71 @@ mod tests {
         assert_eq!(internal_request.k, 10);
         assert_eq!(internal_request.min_score, Some(0.5));
         assert!(internal_request.rerank);
+        assert_eq!(internal_request.mode, RetrievalMode::Hybrid);
+        assert_eq!(internal_request.fusion_weight, Some(0.7));
+        assert_eq!(internal_request.mmr_lambda, Some(0.6));
+        assert!(internal_request.explain);
+        assert_eq!(internal_request.collection.as_deref(), Some("docs"));
+        assert_eq!(
+            internal_request.passage_aggregation,
+            Some(PassageAggregation::MeanTopN(DEFAULT_PASSAGE_TOP_N))
+        );
         
         let filters = internal_request.filters.unwrap();
         assert_eq!(filters.language, Some("en".to_string()));
         assert_eq!(filters.frozen, Some(false));
     }
 
+    #[test]
+    fn test_convert_grpc_similar_posts_request() {
+        let request = convert_grpc_to_internal_similar_posts_request(GrpcSimilarPostsRequest {
+            post_id: "post_1".to_string(),
+            exclude_near_duplicates: true,
+            collection: Some(String::new()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(request.post_id, "post_1");
+        assert_eq!(request.k, DEFAULT_SIMILAR_POSTS);
+        assert!(request.exclude_near_duplicates);
+        assert!(request.collection.is_none());
+
+        let result = convert_grpc_to_internal_similar_posts_request(GrpcSimilarPostsRequest {
+            post_id: "post_1".to_string(),
+            k: 51,
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(SearchError::InvalidRequest(_))));
+
+        // Slugs are identifiers, not query text
+        let request = convert_grpc_to_internal_similar_posts_request(GrpcSimilarPostsRequest {
+            post_id: "guides/../$gt".to_string(),
+            ..Default::default()
+        });
+        assert!(request.is_ok());
+    }
+
+    #[test]
+    fn test_convert_grpc_batch_query() {
+        let query = GrpcSearchRequest {
+            query: "test query".to_string(),
+            k: 5,
+            ..Default::default()
+        };
+        assert_eq!(convert_grpc_batch_query(query.clone()).unwrap().query, "test query");
+
+        // Rejected queries carry the validation message for their batch entry
+        let error = convert_grpc_batch_query(GrpcSearchRequest { k: 0, ..query }).unwrap_err();
+        assert!(error.contains("'k'"));
+    }
+
     #[test]
     fn test_convert_internal_to_grpc_response() {
         let internal_response = crate::types::SearchResponse {
             post_id: "test_post".to_string(),
             title: "Test Title".to_string(),
             snippet: "Test snippet".to_string(),
+            highlights: vec![crate::types::HighlightSpan { start: 5, end: 12 }],
             score: 0.85,
             meta: PostMetadata {
                 author_name: "Test Author".to_string(),
@@ -497,7 +1337,19 @@ mod tests {
                 date: Utc::now(),
                 language: "en".to_string(),
                 frozen: false,
+                metadata: serde_json::Map::new(),
             },
+            next_cursor: None,
+            explanation: Some(ScoreExplanation {
+                search_mode: SearchMode::PostgresOnly,
+                sources: vec![SearchSource::Postgres, SearchSource::Lexical],
+                vector_score: Some(0.8),
+                lexical_score: Some(0.3),
+                fusion: None,
+                retrieval_score: 0.8,
+                rerank_score: Some(0.85),
+                filters_passed: vec!["language".to_string()],
+            }),
         };
         
         let grpc_response = convert_internal_to_grpc_response(internal_response);
@@ -505,6 +1357,7 @@ mod tests {
         assert_eq!(grpc_response.post_id, "test_post");
         assert_eq!(grpc_response.title, "Test Title");
         assert_eq!(grpc_response.snippet, "Test snippet");
+        assert_eq!(grpc_response.highlights, vec![GrpcHighlightSpan { start: 5, end: 12 }]);
         assert_eq!(grpc_response.score, 0.85);
         
         let meta = grpc_response.meta.unwrap();
@@ -512,6 +1365,16 @@ mod tests {
         assert_eq!(meta.url, "https://example.com/test");
         assert_eq!(meta.language, "en");
         assert!(!meta.frozen);
+
+        let explanation = grpc_response.explanation.unwrap();
+        assert_eq!(explanation.search_mode, proto::SearchMode::PostgresOnly as i32);
+        assert_eq!(
+            explanation.sources,
+            vec![proto::SearchSource::Postgres as i32, proto::SearchSource::Lexical as i32]
+        );
+        assert_eq!(explanation.rerank_score, Some(0.85));
+        assert!(explanation.fusion.is_none());
+        assert_eq!(explanation.filters_passed, vec!["language"]);
     }
 
     #[test]
@@ -542,6 +1405,63 @@ mod tests {
         assert!(status.message().contains("Internal error"));
     }
 
+    #[test]
+    fn test_convert_internal_to_grpc_receipt() {
+        let receipt = crate::types::DeletionReceipt {
+            receipt_id: uuid::Uuid::new_v4(),
+            post_id: "post_1".to_string(),
+            status: crate::types::DeletionStatus::Partial,
+            postgres_deleted: true,
+            cache_keys_deleted: 2,
+            topk_entries_purged: 3,
+            attempts: 5,
+            errors: vec!["redis: timeout".to_string()],
+            requested_at: chrono::Utc::now(),
+            completed_at: chrono::Utc::now(),
+        };
+
+        let grpc_receipt = convert_internal_to_grpc_receipt(receipt.clone());
+        assert_eq!(grpc_receipt.receipt_id, receipt.receipt_id.to_string());
+        assert_eq!(grpc_receipt.status, proto::DeletionStatus::Partial as i32);
+        assert_eq!(grpc_receipt.topk_entries_purged, 3);
+        assert_eq!(grpc_receipt.errors, receipt.errors);
+    }
+
+    #[test]
+    fn test_reflection_descriptor_set_is_embedded() {
+        assert!(!proto::FILE_DESCRIPTOR_SET.is_empty());
+    }
+
+    #[test]
+    fn test_convert_grpc_to_internal_upsert() {
+        let grpc_request = GrpcUpsertPostRequest {
+            post_id: "post_1".to_string(),
+            title: "Title".to_string(),
+            content: "Content".to_string(),
+            meta: Some(GrpcPostMetadata {
+                author_name: "Test Author".to_string(),
+                url: "https://example.com/post_1".to_string(),
+                date: "2024-01-15T10:30:00Z".to_string(),
+                language: "en".to_string(),
+                frozen: false,
+                metadata_json: String::new(),
+            }),
+            collection: None,
+        };
+
+        let internal = convert_grpc_to_internal_upsert(grpc_request.clone()).unwrap();
+        assert_eq!(internal.post_id, "post_1");
+        assert_eq!(internal.meta.date.to_rfc3339(), "2024-01-15T10:30:00+00:00");
+
+        let mut bad_date = grpc_request.clone();
+        bad_date.meta.as_mut().unwrap().date = "yesterday".to_string();
+        assert!(convert_grpc_to_internal_upsert(bad_date).unwrap_err().contains("Invalid date"));
+
+        let mut missing_meta = grpc_request;
+        missing_meta.meta = None;
+        assert!(convert_grpc_to_internal_upsert(missing_meta).unwrap_err().contains("Missing metadata"));
+    }
+
     #[test]
     fn test_contains_malicious_patterns() {
         // Test SQL injection patterns
@@ -586,4 +1506,101 @@ mod tests {
         assert!(!is_valid_language_code("en123")); // numbers
         assert!(!is_valid_language_code("toolongcode")); // too long
     }
+    #[test]
+    fn test_convert_grpc_filters() {
+        let filters = GrpcSearchFilters {
+            date_after: Some("2024-01-01T00:00:00+02:00".to_string()),
+            authors: vec!["Ada".to_string()],
+            not: Some(Box::new(GrpcSearchFilters {
+                url_domains: vec!["example.com".to_string()],
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        let converted = convert_grpc_filters(filters).unwrap();
+        assert_eq!(converted.date_after.unwrap().to_rfc3339(), "2023-12-31T22:00:00+00:00");
+        assert_eq!(converted.authors, Some(vec!["Ada".to_string()]));
+        assert!(converted.exclude_authors.is_none());
+        assert!(converted.or.is_none());
+        assert_eq!(converted.not.unwrap().url_domains, Some(vec!["example.com".to_string()]));
+
+        let invalid = GrpcSearchFilters {
+            date_before: Some("yesterday".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(convert_grpc_filters(invalid), Err(SearchError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_convert_grpc_metadata_filters() {
+        use proto::metadata_value::Kind;
+
+        let value = |kind| GrpcMetadataValue { kind: Some(kind) };
+        let filters = GrpcSearchFilters {
+            metadata: vec![GrpcMetadataFilter {
+                key: "category".to_string(),
+                r#in: vec![value(Kind::StringValue("guides".to_string())), value(Kind::NumberValue(3.0))],
+                gte: Some(1.5),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let converted = convert_grpc_filters(filters).unwrap().metadata.unwrap();
+        assert_eq!(converted[0].r#in, Some(vec![serde_json::json!("guides"), serde_json::json!(3.0)]));
+        assert_eq!(converted[0].gte, Some(1.5));
+        assert!(converted[0].eq.is_none());
+
+        assert!(convert_grpc_metadata_value(GrpcMetadataValue { kind: None }).is_err());
+        assert_eq!(
+            parse_grpc_post_metadata(r#"{"tags":["rust"]}"#).unwrap()["tags"],
+            serde_json::json!(["rust"])
+        );
+        assert!(parse_grpc_post_metadata("[1]").is_err());
+        assert!(parse_grpc_post_metadata("").unwrap().is_empty());
+    }
+    #[test]
+    fn test_convert_grpc_answer_request() {
+        let request = GrpcAnswerRequest {
+            query: "what is ownership?".to_string(),
+            mode: proto::RetrievalMode::Hybrid as i32,
+            max_context_tokens: Some(1000),
+            ..Default::default()
+        };
+
+        let internal = convert_grpc_to_internal_answer_request(request.clone()).unwrap();
+        assert_eq!(internal.k, DEFAULT_ANSWER_SOURCES);
+        assert_eq!(internal.mode, RetrievalMode::Hybrid);
+        assert_eq!(internal.max_context_tokens, Some(1000));
+        assert!(internal.stream);
+
+        // Search rules and answer limits both apply
+        let empty_query = GrpcAnswerRequest { query: String::new(), ..request.clone() };
+        assert!(matches!(
+            convert_grpc_to_internal_answer_request(empty_query),
+            Err(SearchError::InvalidRequest(_))
+        ));
+
+        let too_many = GrpcAnswerRequest { k: 21, ..request.clone() };
+        assert!(convert_grpc_to_internal_answer_request(too_many).is_err());
+
+        let no_budget = GrpcAnswerRequest { max_context_tokens: Some(0), ..request };
+        assert!(convert_grpc_to_internal_answer_request(no_budget).is_err());
+    }
+
+    #[test]
+    fn test_convert_internal_to_grpc_citation() {
+        let citation = convert_internal_to_grpc_citation(crate::types::Citation {
+            index: 2,
+            post_id: "post_2".to_string(),
+            title: "Ownership".to_string(),
+            url: "https://example.com/post_2".to_string(),
+            score: 0.8,
+        });
+
+        assert_eq!(citation.index, 2);
+        assert_eq!(citation.post_id, "post_2");
+        assert_eq!(citation.url, "https://example.com/post_2");
+    }
 }
\ No newline at end of file
@@ -2,6 +2,7 @@
 mod integration_tests {
     use crate::grpc::{
         GrpcSearchRequest, GrpcSearchFilters, 
+        proto::{PassageAggregation, RetrievalMode},
         validate_grpc_search_request, 
         convert_grpc_to_internal_request, 
         convert_internal_to_grpc_response
@@ -22,6 +23,14 @@ mod integration_tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
 
         let result = validate_grpc_search_request(&request);
@@ -38,6 +47,14 @@ mod integration_tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
 
         let result = validate_grpc_search_request(&request);
@@ -51,6 +68,14 @@ mod integration_tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
 
         let result = validate_grpc_search_request(&request);
@@ -67,6 +92,14 @@ mod integration_tests {
             min_score: Some(-0.1),
             rerank: false,
             filters: None,
+            mode: RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
 
         let result = validate_grpc_search_request(&request);
@@ -80,6 +113,14 @@ mod integration_tests {
             min_score: Some(1.1),
             rerank: false,
             filters: None,
+            mode: RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
 
         let result = validate_grpc_search_request(&request);
@@ -95,6 +136,14 @@ mod integration_tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
 
         let result = validate_grpc_search_request(&request);
@@ -112,7 +161,16 @@ mod integration_tests {
             filters: Some(GrpcSearchFilters {
                 language: Some("INVALID123".to_string()),
                 frozen: None,
+                ..Default::default()
             }),
+            mode: RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
 
         let result = validate_grpc_search_request(&request);
@@ -130,7 +188,16 @@ mod integration_tests {
             filters: Some(GrpcSearchFilters {
                 language: Some("en".to_string()),
                 frozen: Some(false),
+                ..Default::default()
             }),
+            mode: RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
 
         let result = validate_grpc_search_request(&request);
@@ -147,7 +214,16 @@ mod integration_tests {
             filters: Some(GrpcSearchFilters {
                 language: Some("en".to_string()),
                 frozen: Some(false),
+                ..Default::default()
             }),
+            mode: RetrievalMode::Vector as i32,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: PassageAggregation::None as i32,
+            passage_top_n: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
 
         let internal_request = convert_grpc_to_internal_request(grpc_request).unwrap();
@@ -171,6 +247,7 @@ mod integration_tests {
             post_id: "test_post".to_string(),
             title: "Test Title".to_string(),
             snippet: "Test snippet".to_string(),
+            highlights: Vec::new(),
             score: 0.85,
             meta: PostMetadata {
                 author_name: "Test Author".to_string(),
@@ -178,7 +255,10 @@ mod integration_tests {
                 date: Utc::now(),
                 language: "en".to_string(),
                 frozen: false,
+                metadata: serde_json::Map::new(),
             },
+            next_cursor: None,
+            explanation: None,
         };
 
         let grpc_response = convert_internal_to_grpc_response(internal_response);
@@ -0,0 +1,474 @@
+//! Post ingestion service
+//!
+//! Accepts posts without embeddings, computes vectors with the active embedding
+//! model, upserts them into Postgres (the source of truth) and then refreshes the
+//! Redis vector/metadata entries and the top-k cache. Post contents are also
+//! split into passages that are embedded and indexed for passage-level search.
+
+use crate::cache::CacheManager;
+use crate::database::DatabaseManager;
+use crate::error::{SearchError, SearchResult};
+use crate::ml::MLService;
+use crate::types::{is_valid_metadata_key, validate_post_id, Post, PostChunk, PostMetadata, UpsertPostRequest, UpsertPostResponse};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{debug, info, instrument, warn};
+
+/// Maximum number of posts accepted in one batch
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Maximum post content size in bytes
+const MAX_CONTENT_BYTES: usize = 100_000;
+
+/// Maximum post title length
+const MAX_TITLE_LENGTH: usize = 1000;
+
+/// Maximum serialized size of custom post metadata in bytes
+const MAX_METADATA_BYTES: usize = 16_384;
+
+/// Passages embedded per model call
+const PASSAGE_EMBEDDING_BATCH: usize = 32;
+
+/// Posts read per page when backfilling passages
+const BACKFILL_PAGE_SIZE: i64 = 50;
+
+/// Service that embeds and stores posts across Postgres and Redis
+pub struct IngestionService {
+    /// ML service for document embeddings
+    ml_service: Arc<MLService>,
+    /// Cache manager for Redis vector, metadata and top-k entries
+    cache_manager: Arc<CacheManager>,
+    /// Database manager for post storage
+    database_manager: Arc<DatabaseManager>,
+}
+
+impl IngestionService {
+    /// Create a new ingestion service
+    pub fn new(
+        ml_service: Arc<MLService>,
+        cache_manager: Arc<CacheManager>,
+        database_manager: Arc<DatabaseManager>,
+    ) -> Self {
+        Self {
+            ml_service,
+            cache_manager,
+            database_manager,
+        }
+    }
+
+    /// Embed and upsert a single post
+    pub async fn upsert_post(&self, request: UpsertPostRequest) -> SearchResult<UpsertPostResponse> {
+        let mut responses = self.upsert_posts(vec![request]).await?;
+        Ok(responses.remove(0))
+    }
+
+    /// Embed and upsert a batch of posts
+    ///
+    /// Postgres is written in one transaction, so either every post is stored
+    /// or none is. Redis failures are logged and reported per post because
+    /// searches fall back to Postgres for anything missing from the cache.
+    #[instrument(skip(self, requests), fields(count = requests.len()))]
+    pub async fn upsert_posts(&self, requests: Vec<UpsertPostRequest>) -> SearchResult<Vec<UpsertPostResponse>> {
+        validate_upsert_batch(&requests)?;
+
+        info!("Ingesting {} posts", requests.len());
+
+        // Step 1: Embed all posts in one batch
+        let texts: Vec<String> = requests.iter().map(embedding_text).collect();
+        let embeddings = self.ml_service.generate_embeddings_batch(&texts).await?;
+
+        if embeddings.len() != requests.len() {
+            return Err(SearchError::ModelError(format!(
+                "Expected {} embeddings, got {}",
+                requests.len(),
+                embeddings.len()
+            )));
+        }
+
+        let posts: Vec<Post> = requests
+            .into_iter()
+            .zip(embeddings)
+            .map(|(request, embedding)| Post {
+                id: uuid::Uuid::new_v4(),
+                post_id: request.post_id,
+                title: request.title,
+                content: request.content,
+                author_name: request.meta.author_name,
+                language: request.meta.language,
+                frozen: request.meta.frozen,
+                date_gmt: request.meta.date,
+                url: request.meta.url,
+                metadata: request.meta.metadata,
+                embedding,
+            })
+            .collect();
+
+        // Step 2: Split contents into passages and embed them
+        let passages = self.embed_passages(&posts).await?;
+
+        // Step 3: Upsert posts and passages into Postgres
+        self.database_manager.store_posts(&posts, &passages.concat()).await?;
+
+        // Step 4: Refresh Redis vector, passage and metadata entries
+        let cache_writes = posts
+            .iter()
+            .zip(&passages)
+            .map(|(post, chunks)| self.cache_post(post, chunks));
+        let cached = futures::future::join_all(cache_writes).await;
+
+        // Step 5: Any cached query may now rank these posts differently
+        if let Err(e) = self.cache_manager.invalidate_top_k_cache().await {
+            warn!("Failed to invalidate top-k cache after ingestion: {}", e);
+        }
+
+        let embedding_model = self.ml_service.embedding_model_id().to_string();
+        let responses: Vec<UpsertPostResponse> = posts
+            .into_iter()
+            .zip(cached)
+            .map(|(post, cached)| UpsertPostResponse {
+                post_id: post.post_id,
+                embedding_model: embedding_model.clone(),
+                cached,
+            })
+            .collect();
+
+        info!("Ingested {} posts", responses.len());
+        Ok(responses)
+    }
+
+    /// Compute passages for posts stored before passage indexing existed
+    ///
+    /// Pages through every post without passages, embeds their passages and
+    /// stores them in Postgres and Redis. Returns the number of posts visited.
+    #[instrument(skip(self))]
+    pub async fn backfill_passages(&self) -> SearchResult<usize> {
+        let mut after: Option<String> = None;
+        let mut backfilled = 0;
+
+        loop {
+            let posts = self
+                .database_manager
+                .posts_without_passages(after.as_deref(), BACKFILL_PAGE_SIZE)
+                .await?;
+            let Some(last) = posts.last() else {
+                break;
+            };
+            // Posts without any passage (e.g. empty content) stay unchunked,
+            // so paging by post_id keeps them from being revisited forever
+            after = Some(last.post_id.clone());
+
+            let passages = self.embed_passages(&posts).await?;
+            self.database_manager
+                .store_backfilled_passages(&passages.concat())
+                .await?;
+
+            for (post, chunks) in posts.iter().zip(&passages) {
+                if let Err(e) = self
+                    .cache_manager
+                    .set_post_chunks(&post.post_id, chunks, &post_metadata(post))
+                    .await
+                {
+                    warn!("Failed to cache backfilled passages of post {}: {}", post.post_id, e);
+                }
+            }
+
+            backfilled += posts.len();
+            debug!("Backfilled passages for {} posts so far", backfilled);
+        }
+
+        if backfilled > 0 {
+            // Passage search may now find posts it previously missed
+            if let Err(e) = self.cache_manager.invalidate_top_k_cache().await {
+                warn!("Failed to invalidate top-k cache after passage backfill: {}", e);
+            }
+        }
+
+        info!("Backfilled passages for {} posts", backfilled);
+        Ok(backfilled)
+    }
+
+    /// Split each post's content into passages and embed them in batches
+    ///
+    /// Returns the passages of every post, in the order of `posts`.
+    async fn embed_passages(&self, posts: &[Post]) -> SearchResult<Vec<Vec<PostChunk>>> {
+        let mut passages = Vec::with_capacity(posts.len());
+        for post in posts {
+            let chunks: Vec<PostChunk> = self
+                .ml_service
+                .chunk_passages(&post.content)?
+                .into_iter()
+                .filter_map(|span| {
+                    let content = post.content.get(span.start as usize..span.end as usize)?;
+                    Some((span, content.to_string()))
+                })
+                .enumerate()
+                .map(|(index, (span, content))| PostChunk {
+                    post_id: post.post_id.clone(),
+                    chunk_index: index as u32,
+                    span,
+                    content,
+                    embedding: Vec::new(),
+                })
+                .collect();
+            passages.push(chunks);
+        }
+
+        let mut pending: Vec<&mut PostChunk> = passages.iter_mut().flatten().collect();
+        debug!("Embedding {} passages for {} posts", pending.len(), posts.len());
+
+        for batch in pending.chunks_mut(PASSAGE_EMBEDDING_BATCH) {
+            let texts: Vec<String> = batch.iter().map(|chunk| chunk.content.clone()).collect();
+            let embeddings = self.ml_service.generate_embeddings_batch(&texts).await?;
+
+            if embeddings.len() != batch.len() {
+                return Err(SearchError::ModelError(format!(
+                    "Expected {} passage embeddings, got {}",
+                    batch.len(),
+                    embeddings.len()
+                )));
+            }
+
+            for (chunk, embedding) in batch.iter_mut().zip(embeddings) {
+                chunk.embedding = embedding;
+            }
+        }
+
+        Ok(passages)
+    }
+
+    /// Write the vector, passage and metadata entries for a stored post
+    ///
+    /// On failure the post's entries are dropped so Redis never serves a
+    /// previous version of the post.
+    async fn cache_post(&self, post: &Post, chunks: &[PostChunk]) -> bool {
+        let metadata = post_metadata(post);
+
+        let result: SearchResult<()> = async {
+            self.cache_manager
+                .set_indexed_vector(&post.post_id, &post.embedding, &metadata)
+                .await?;
+            self.cache_manager
+                .set_post_chunks(&post.post_id, chunks, &metadata)
+                .await?;
+            self.cache_manager
+                .set_metadata_cache(&post.post_id, &metadata)
+                .await
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                debug!("Cached vector, {} passages and metadata for post: {}", chunks.len(), post.post_id);
+                true
+            }
+            Err(e) => {
+                warn!("Failed to cache post {}: {}", post.post_id, e);
+                if let Err(e) = self.cache_manager.invalidate_post_data(&post.post_id).await {
+                    warn!("Failed to drop stale cache entries for post {}: {}", post.post_id, e);
+                }
+                false
+            }
+        }
+    }
+}
+
+/// Cached metadata of a stored post
+fn post_metadata(post: &Post) -> PostMetadata {
+    PostMetadata {
+        author_name: post.author_name.clone(),
+        url: post.url.clone(),
+        date: post.date_gmt,
+        language: post.language.clone(),
+        frozen: post.frozen,
+        metadata: post.metadata.clone(),
+    }
+}
+
+/// Text fed to the embedding model for a post
+fn embedding_text(request: &UpsertPostRequest) -> String {
+    let title = request.title.trim();
+    if title.is_empty() {
+        request.content.trim().to_string()
+    } else {
+        format!("{}\n\n{}", title, request.content.trim())
+    }
+}
+
+/// Validate a batch of ingestion requests
+pub fn validate_upsert_batch(requests: &[UpsertPostRequest]) -> SearchResult<()> {
+    if requests.is_empty() {
+        return Err(SearchError::InvalidRequest("At least one post is required".to_string()));
+    }
+
+    if requests.len() > MAX_BATCH_SIZE {
+        return Err(SearchError::InvalidRequest(format!(
+            "Batch too large ({} posts, maximum {})",
+            requests.len(),
+            MAX_BATCH_SIZE
+        )));
+    }
+
+    let mut seen = HashSet::new();
+    for request in requests {
+        validate_upsert_request(request)?;
+
+        if !seen.insert(request.post_id.as_str()) {
+            return Err(SearchError::InvalidRequest(format!(
+                "Duplicate post_id in batch: {}",
+                request.post_id
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate a single ingestion request
+pub fn validate_upsert_request(request: &UpsertPostRequest) -> SearchResult<()> {
+    let post_id = &request.post_id;
+    validate_post_id(post_id).map_err(SearchError::InvalidRequest)?;
+
+    if request.title.len() > MAX_TITLE_LENGTH {
+        return Err(SearchError::InvalidRequest(format!(
+            "Title too long (maximum {} characters allowed)",
+            MAX_TITLE_LENGTH
+        )));
+    }
+
+    if request.content.trim().is_empty() {
+        return Err(SearchError::InvalidRequest(format!(
+            "Content cannot be empty for post {}",
+            post_id
+        )));
+    }
+
+    if request.content.len() > MAX_CONTENT_BYTES {
+        return Err(SearchError::InvalidRequest(format!(
+            "Content too large for post {} (maximum {} bytes)",
+            post_id, MAX_CONTENT_BYTES
+        )));
+    }
+
+    let language = &request.meta.language;
+    if language.len() < 2
+        || language.len() > 10
+        || !language.chars().all(|c| c.is_ascii_lowercase() || c == '-')
+    {
+        return Err(SearchError::InvalidRequest(format!(
+            "Invalid language code '{}' for post {}",
+            language, post_id
+        )));
+    }
+
+    match url::Url::parse(&request.meta.url) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+        _ => {
+            return Err(SearchError::InvalidRequest(format!(
+                "Invalid URL for post {}: must be an absolute http(s) URL",
+                post_id
+            )));
+        }
+    }
+
+    let metadata = &request.meta.metadata;
+    if let Some(key) = metadata.keys().find(|key| !is_valid_metadata_key(key)) {
+        return Err(SearchError::InvalidRequest(format!(
+            "Invalid metadata key '{}' for post {}",
+            key, post_id
+        )));
+    }
+
+    if serde_json::to_vec(metadata).map_or(0, |bytes| bytes.len()) > MAX_METADATA_BYTES {
+        return Err(SearchError::InvalidRequest(format!(
+            "Metadata too large for post {} (maximum {} bytes)",
+            post_id, MAX_METADATA_BYTES
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn create_request(post_id: &str) -> UpsertPostRequest {
+        UpsertPostRequest {
+            post_id: post_id.to_string(),
+            title: "Rust ownership".to_string(),
+            content: "Ownership rules govern how memory is managed.".to_string(),
+            meta: PostMetadata {
+                author_name: "Author".to_string(),
+                url: format!("https://example.com/{}", post_id),
+                date: Utc::now(),
+                language: "en".to_string(),
+                frozen: false,
+                metadata: serde_json::Map::new(),
+            },
+            collection: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_batch() {
+        let requests = vec![create_request("post_1"), create_request("post_2")];
+        assert!(validate_upsert_batch(&requests).is_ok());
+    }
+
+    #[test]
+    fn test_batch_limits() {
+        assert!(matches!(validate_upsert_batch(&[]), Err(SearchError::InvalidRequest(_))));
+
+        let requests: Vec<_> = (0..=MAX_BATCH_SIZE).map(|i| create_request(&format!("post_{}", i))).collect();
+        assert!(matches!(validate_upsert_batch(&requests), Err(SearchError::InvalidRequest(_))));
+
+        let duplicates = vec![create_request("post_1"), create_request("post_1")];
+        let result = validate_upsert_batch(&duplicates);
+        assert!(matches!(result, Err(SearchError::InvalidRequest(msg)) if msg.contains("Duplicate")));
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let mut request = create_request("post 1");
+        assert!(validate_upsert_request(&request).is_err());
+
+        request = create_request("post_1");
+        request.content = "   ".to_string();
+        assert!(validate_upsert_request(&request).is_err());
+
+        request = create_request("post_1");
+        request.meta.language = "EN".to_string();
+        assert!(validate_upsert_request(&request).is_err());
+
+        request = create_request("post_1");
+        request.meta.url = "ftp://example.com/post".to_string();
+        assert!(validate_upsert_request(&request).is_err());
+
+        request = create_request("post_1");
+        request.meta.metadata.insert("bad key".to_string(), serde_json::json!("x"));
+        assert!(validate_upsert_request(&request).is_err());
+
+        // Dots separate the keys of filter paths, so stored keys cannot contain them
+        request = create_request("post_1");
+        request.meta.metadata.insert("product.category".to_string(), serde_json::json!("x"));
+        assert!(validate_upsert_request(&request).is_err());
+
+        request = create_request("post_1");
+        request.meta.metadata.insert("notes".to_string(), serde_json::json!("x".repeat(MAX_METADATA_BYTES)));
+        assert!(validate_upsert_request(&request).is_err());
+    }
+
+    #[test]
+    fn test_embedding_text() {
+        let request = create_request("post_1");
+        assert_eq!(
+            embedding_text(&request),
+            "Rust ownership\n\nOwnership rules govern how memory is managed."
+        );
+
+        let mut untitled = create_request("post_2");
+        untitled.title = String::new();
+        assert_eq!(embedding_text(&untitled), "Ownership rules govern how memory is managed.");
+    }
+}
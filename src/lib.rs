@@ -2,7 +2,11 @@ pub mod server;
 pub mod grpc;
 pub mod ml;
 pub mod search;
+pub mod ingestion;
+pub mod rag;
+pub mod gdpr;
 pub mod cache;
+pub mod collections;
 pub mod database;
 pub mod error;
 pub mod types;
@@ -16,11 +20,12 @@ pub use config::Config;
 pub use ml::TokenizerService;
 pub use cache::CacheManager;
 pub use database::DatabaseManager;
+pub use collections::{Collection, CollectionRegistry};
 pub use search::{
     VectorSearchService, SearchStats,
     CircuitBreaker, CircuitBreakerConfig, CircuitBreakerStats, CircuitState,
     RetryExecutor, RetryConfig, RetryStrategy,
     FallbackSearchService, FallbackHealthStatus,
-    RerankingService, RerankingConfig,
+    RerankingService, RerankingConfig, ScoreNormalization,
     SearchService, SearchServiceHealth, SearchServiceStats
 };
\ No newline at end of file
@@ -2,11 +2,16 @@ mod server;
 mod grpc;
 mod ml;
 mod search;
+mod ingestion;
+mod rag;
+mod gdpr;
 mod cache;
+mod collections;
 mod database;
 mod error;
 mod types;
 mod config;
+mod observability;
 
 use crate::server::SearchServer;
 use crate::error::SearchError;
@@ -26,12 +31,11 @@ async fn main() -> Result<(), SearchError> {
     let config = Config::from_env()?;
     tracing::info!("Configuration loaded successfully");
     tracing::info!("Server will listen on {}:{}", config.server.host, config.server.port);
+    tracing::info!("gRPC server will listen on {}:{}", config.server.host, config.server.grpc_port);
 
     let server = SearchServer::new(config).await?;
-    
-    // For now, just run the HTTP server
-    // gRPC functionality is available via the GrpcSearchService
-    tracing::info!("Starting HTTP server (gRPC service available programmatically)");
+
+    tracing::info!("Starting HTTP and gRPC servers");
     server.run().await?;
 
     Ok(())
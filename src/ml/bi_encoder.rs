@@ -1,82 +1,151 @@
 use crate::error::{SearchError, SearchResult};
+use crate::ml::embedder::{Embedder, EmbeddingModel};
+use crate::ml::onnx::OnnxSession;
 use crate::ml::tokenizer::TokenizerService;
+use async_trait::async_trait;
 use std::path::PathBuf;
 use tracing::{debug, instrument};
 
 /// BiEncoder service for generating text embeddings
-/// Uses all-MiniLM-L6-v2 ONNX model to generate 384-dimensional embeddings
+/// Runs a mean-pooled sentence-transformers ONNX model (e.g. 384-d MiniLM, 768-d BERT)
 pub struct BiEncoder {
     model_path: PathBuf,
     tokenizer: TokenizerService,
+    session: OnnxSession,
+    model: EmbeddingModel,
 }
 
 impl BiEncoder {
     /// Create a new BiEncoder with model path and tokenizer
-    pub fn new(model_path: PathBuf, tokenizer: TokenizerService) -> Self {
-        Self { model_path, tokenizer }
+    /// Loads the ONNX session eagerly so a broken model fails at startup
+    pub fn new(model_path: PathBuf, tokenizer: TokenizerService, model: EmbeddingModel) -> SearchResult<Self> {
+        let session = OnnxSession::load(&model_path)?;
+        Ok(Self { model_path, tokenizer, session, model })
     }
 
+    /// Get the model path for this encoder
+    pub fn model_path(&self) -> &PathBuf {
+        &self.model_path
+    }
+}
+
+#[async_trait]
+impl Embedder for BiEncoder {
     /// Generate embedding for a single text query
-    /// Returns 384-dimensional vector normalized to unit length
+    /// Returns a vector of `dimension()` values normalized to unit length
     #[instrument(skip(self), fields(query_len = query.len()))]
-    pub async fn encode(&self, query: &str) -> SearchResult<Vec<f32>> {
-        // For now, return a placeholder implementation
-        // In production, this would use the actual ONNX model at self.model_path
-        
+    async fn encode(&self, query: &str) -> SearchResult<Vec<f32>> {
         if query.trim().is_empty() {
             return Err(SearchError::ModelError("Empty query for encoding".to_string()));
         }
 
-        debug!("Encoding query: {} (using model at {})", query, self.model_path.display());
-
-        // Generate a deterministic but pseudo-random embedding based on query content
-        // This is just for testing - real implementation would use ONNX inference
-        let mut embedding = vec![0.0f32; 384];
-        let query_bytes = query.as_bytes();
-        
-        for (i, &byte) in query_bytes.iter().enumerate() {
-            let idx = i % 384;
-            embedding[idx] += (byte as f32) / 255.0;
-        }
-        
-        // Normalize to unit length
-        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 0.0 {
-            for value in &mut embedding {
-                *value /= norm;
-            }
-        }
-
-        debug!("Generated embedding with {} dimensions", embedding.len());
-        Ok(embedding)
+        let mut embeddings = self.encode_batch(&[query.to_string()]).await?;
+        embeddings
+            .pop()
+            .ok_or_else(|| SearchError::ModelError("Model returned no embedding".to_string()))
     }
 
     /// Generate embeddings for multiple texts in batch
-    /// More efficient for processing multiple queries at once
+    /// All texts are padded into a single tensor and run in one inference call
     #[instrument(skip(self), fields(batch_size = texts.len()))]
-    pub async fn encode_batch(&self, texts: &[String]) -> SearchResult<Vec<Vec<f32>>> {
+    async fn encode_batch(&self, texts: &[String]) -> SearchResult<Vec<Vec<f32>>> {
         if texts.is_empty() {
             return Ok(vec![]);
         }
 
-        // For now, process sequentially. In production, this could be optimized
-        // to use actual batch processing with padded sequences
-        let mut embeddings = Vec::with_capacity(texts.len());
-        
-        for text in texts {
-            let embedding = self.encode(text).await?;
-            embeddings.push(embedding);
+        if texts.iter().any(|text| text.trim().is_empty()) {
+            return Err(SearchError::ModelError("Empty query for encoding".to_string()));
+        }
+
+        let batch = self.tokenizer.tokenize_batch(texts)?;
+        let attention_mask = batch.attention_mask.clone();
+        let (batch_size, seq_len) = (batch.batch_size, batch.seq_len);
+
+        debug!(
+            "Running bi-encoder on batch of {} (seq_len {}) using model at {}",
+            batch_size,
+            seq_len,
+            self.model_path.display()
+        );
+
+        let output = self.session.run(batch).await?;
+
+        // Token embeddings come back as [batch, seq_len, hidden]
+        if output.shape.len() != 3 || output.shape[0] != batch_size || output.shape[1] != seq_len {
+            return Err(SearchError::ModelError(format!(
+                "Unexpected bi-encoder output shape {:?} for batch [{}, {}]",
+                output.shape, batch_size, seq_len
+            )));
+        }
+
+        if output.shape[2] != self.model.dimension() {
+            return Err(SearchError::ModelError(format!(
+                "Model {} produced {}-dimensional embeddings, expected {}",
+                self.model.model_id(),
+                output.shape[2],
+                self.model.dimension()
+            )));
         }
 
+        let embeddings = mean_pool_and_normalize(&output.data, &attention_mask, batch_size, seq_len, output.shape[2]);
+
+        debug!("Generated {} embeddings with {} dimensions", embeddings.len(), output.shape[2]);
         Ok(embeddings)
     }
 
-    /// Get the model path for this encoder
-    pub fn model_path(&self) -> &PathBuf {
-        &self.model_path
+    fn dimension(&self) -> usize {
+        self.model.dimension()
+    }
+
+    fn model_id(&self) -> &str {
+        self.model.model_id()
     }
 }
 
+/// Mean-pool token embeddings over the attention mask and L2-normalize each row
+fn mean_pool_and_normalize(
+    token_embeddings: &[f32],
+    attention_mask: &[i64],
+    batch_size: usize,
+    seq_len: usize,
+    hidden_size: usize,
+) -> Vec<Vec<f32>> {
+    (0..batch_size)
+        .map(|row| {
+            let mut pooled = vec![0.0f32; hidden_size];
+            let mut token_count = 0.0f32;
+
+            for token in 0..seq_len {
+                if attention_mask[row * seq_len + token] == 0 {
+                    continue;
+                }
+
+                let offset = (row * seq_len + token) * hidden_size;
+                for (value, &embedding) in pooled.iter_mut().zip(&token_embeddings[offset..offset + hidden_size]) {
+                    *value += embedding;
+                }
+                token_count += 1.0;
+            }
+
+            if token_count > 0.0 {
+                for value in &mut pooled {
+                    *value /= token_count;
+                }
+            }
+
+            // Normalize to unit length
+            let norm = pooled.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                for value in &mut pooled {
+                    *value /= norm;
+                }
+            }
+
+            pooled
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,38 +180,47 @@ mod tests {
 
     #[test]
     fn test_mean_pool_and_normalize() {
-        // Create a mock BiEncoder for testing pooling logic
-        // This would require proper initialization in a real test
-        
-        let embeddings = vec![
-            vec![1.0, 2.0, 3.0],
-            vec![4.0, 5.0, 6.0],
-            vec![7.0, 8.0, 9.0],
+        // One sequence of three tokens with hidden size 3
+        let token_embeddings = vec![
+            1.0, 2.0, 3.0,
+            4.0, 5.0, 6.0,
+            7.0, 8.0, 9.0,
         ];
-        
+
         let attention_mask = vec![1, 1, 0]; // Third token is masked out
-        
+
+        let pooled = mean_pool_and_normalize(&token_embeddings, &attention_mask, 1, 3, 3);
+
         // Expected pooled result: (1+4)/2, (2+5)/2, (3+6)/2 = [2.5, 3.5, 4.5]
-        let expected_mean = vec![2.5, 3.5, 4.5];
-        
-        // Calculate expected norm and normalized values
         let norm = (2.5*2.5 + 3.5*3.5 + 4.5*4.5_f32).sqrt();
-        let expected_normalized = vec![2.5/norm, 3.5/norm, 4.5/norm];
-        
-        // This demonstrates the expected behavior
-        // Recalculate the expected values: norm = sqrt(2.5^2 + 3.5^2 + 4.5^2) = sqrt(6.25 + 12.25 + 20.25) = sqrt(38.75) ≈ 6.225
-        let norm = (2.5*2.5 + 3.5*3.5 + 4.5*4.5_f32).sqrt();
-        assert!((expected_normalized[0] - 2.5/norm).abs() < 0.001);
-        assert!((expected_normalized[1] - 3.5/norm).abs() < 0.001);
-        assert!((expected_normalized[2] - 4.5/norm).abs() < 0.001);
+        assert_eq!(pooled.len(), 1);
+        assert!((pooled[0][0] - 2.5/norm).abs() < 0.001);
+        assert!((pooled[0][1] - 3.5/norm).abs() < 0.001);
+        assert!((pooled[0][2] - 4.5/norm).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_mean_pool_batch_rows_are_independent() {
+        // Two sequences of two tokens, second row padded after the first token
+        let token_embeddings = vec![
+            1.0, 0.0,   0.0, 1.0,
+            3.0, 4.0,   9.0, 9.0,
+        ];
+        let attention_mask = vec![1, 1, 1, 0];
+
+        let pooled = mean_pool_and_normalize(&token_embeddings, &attention_mask, 2, 2, 2);
+
+        let inv_sqrt2 = 1.0 / 2.0_f32.sqrt();
+        assert!((pooled[0][0] - inv_sqrt2).abs() < 0.001);
+        assert!((pooled[0][1] - inv_sqrt2).abs() < 0.001);
+        assert!((pooled[1][0] - 0.6).abs() < 0.001);
+        assert!((pooled[1][1] - 0.8).abs() < 0.001);
     }
 
     #[test]
     fn test_embedding_dimensions() {
-        // Test that we expect 384-dimensional embeddings for all-MiniLM-L6-v2
-        const EXPECTED_EMBEDDING_DIM: usize = 384;
-        
-        // This would be verified in integration tests with actual model
-        assert_eq!(EXPECTED_EMBEDDING_DIM, 384);
+        // MiniLM produces 384-dimensional embeddings, BERT-base 768
+        assert_eq!(EmbeddingModel::MiniLmL6V2.dimension(), 384);
+        assert_eq!(EmbeddingModel::BertBaseNliMeanTokens.dimension(), 768);
     }
 }
\ No newline at end of file
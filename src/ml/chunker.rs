@@ -0,0 +1,104 @@
+//! Passage chunking of long posts
+//!
+//! The bi-encoder truncates its input at the model's sequence length, so a
+//! single embedding only covers the start of a long post. Posts are split into
+//! overlapping token windows that each fit the model, and every window is
+//! embedded and searched as its own passage.
+
+use crate::error::SearchResult;
+use crate::ml::tokenizer::TokenizerService;
+use crate::types::{PassageSpan, MAX_CHUNKS_PER_POST};
+
+/// Splits text into overlapping token windows
+#[derive(Clone)]
+pub struct Chunker {
+    tokenizer: TokenizerService,
+    /// Tokens per passage
+    window_tokens: usize,
+    /// Tokens shared by consecutive passages
+    overlap_tokens: usize,
+}
+
+impl Chunker {
+    /// Create a chunker; the overlap is capped below the window size
+    pub fn new(tokenizer: TokenizerService, window_tokens: usize, overlap_tokens: usize) -> Self {
+        let window_tokens = window_tokens.max(1);
+        Self {
+            tokenizer,
+            window_tokens,
+            overlap_tokens: overlap_tokens.min(window_tokens - 1),
+        }
+    }
+
+    /// Byte spans of the passages of `text`
+    ///
+    /// Any non-empty text yields at least one passage, so short posts are
+    /// searchable at passage level too. Long texts stop at
+    /// `MAX_CHUNKS_PER_POST` passages.
+    pub fn chunk(&self, text: &str) -> SearchResult<Vec<PassageSpan>> {
+        let offsets = self.tokenizer.token_offsets(text)?;
+        Ok(token_windows(&offsets, self.window_tokens, self.overlap_tokens, MAX_CHUNKS_PER_POST))
+    }
+}
+
+/// Group token offsets into windows of `window` tokens overlapping by `overlap`
+fn token_windows(offsets: &[(usize, usize)], window: usize, overlap: usize, max_windows: usize) -> Vec<PassageSpan> {
+    let step = window.saturating_sub(overlap).max(1);
+    let mut spans = Vec::new();
+    let mut start = 0;
+
+    while start < offsets.len() && spans.len() < max_windows {
+        let end = (start + window).min(offsets.len());
+        spans.push(PassageSpan {
+            start: offsets[start].0 as u32,
+            end: offsets[end - 1].1 as u32,
+        });
+
+        if end == offsets.len() {
+            break;
+        }
+        start += step;
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One 4-byte token per word: "w0 w1 w2 ..."
+    fn offsets(tokens: usize) -> Vec<(usize, usize)> {
+        (0..tokens).map(|i| (i * 4, i * 4 + 3)).collect()
+    }
+
+    fn span(start: u32, end: u32) -> PassageSpan {
+        PassageSpan { start, end }
+    }
+
+    #[test]
+    fn test_short_text_is_one_passage() {
+        assert_eq!(token_windows(&offsets(3), 8, 2, 10), vec![span(0, 11)]);
+        assert!(token_windows(&[], 8, 2, 10).is_empty());
+    }
+
+    #[test]
+    fn test_windows_overlap() {
+        // Windows of 4 tokens starting every 3 tokens
+        let spans = token_windows(&offsets(10), 4, 1, 10);
+        assert_eq!(spans, vec![span(0, 15), span(12, 27), span(24, 39)]);
+    }
+
+    #[test]
+    fn test_window_count_is_capped() {
+        let spans = token_windows(&offsets(100), 4, 0, 5);
+        assert_eq!(spans.len(), 5);
+        assert_eq!(spans[4], span(64, 79));
+    }
+
+    #[test]
+    fn test_chunk_without_tokenizer() {
+        let chunker = Chunker::new(TokenizerService::new_sync().unwrap(), 8, 2);
+        assert!(chunker.chunk("hello world").unwrap_err().is_model_error());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::error::{SearchError, SearchResult};
+use crate::ml::onnx::OnnxSession;
 use crate::ml::tokenizer::TokenizerService;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
 use tracing::{debug, instrument};
 
 /// CrossEncoder service for reranking search results
@@ -9,6 +12,8 @@ use tracing::{debug, instrument};
 pub struct CrossEncoder {
     model_path: PathBuf,
     tokenizer: TokenizerService,
+    /// ONNX session, loaded by `warm_up` or on first use
+    session: Arc<OnceCell<OnnxSession>>,
 }
 
 /// Query-document pair for reranking
@@ -28,66 +33,79 @@ pub struct RerankResult {
 impl CrossEncoder {
     /// Create a new CrossEncoder with model path and tokenizer
     pub fn new(model_path: PathBuf, tokenizer: TokenizerService) -> Self {
-        Self { model_path, tokenizer }
+        Self {
+            model_path,
+            tokenizer,
+            session: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Load the ONNX session ahead of the first reranking request
+    pub async fn warm_up(&self) -> SearchResult<()> {
+        self.session().await.map(|_| ())
     }
 
     /// Score a single query-document pair
     /// Returns relevance score between 0.0 and 1.0
     #[instrument(skip(self), fields(query_len = pair.query.len(), doc_len = pair.document.len()))]
     pub async fn score(&self, pair: &QueryDocumentPair) -> SearchResult<f32> {
-        // For now, return a placeholder implementation
-        // In production, this would use the actual ONNX model at self.model_path
-        
-        if pair.query.trim().is_empty() || pair.document.trim().is_empty() {
-            return Err(SearchError::ModelError("Empty query or document for cross-encoder".to_string()));
-        }
-
-        debug!("Scoring query-document pair (using model at {})", self.model_path.display());
-
-        // Generate a simple relevance score based on text similarity
-        // This is just for testing - real implementation would use ONNX inference
-        let query_lower = pair.query.to_lowercase();
-        let doc_lower = pair.document.to_lowercase();
-        let query_words: Vec<&str> = query_lower.split_whitespace().collect();
-        let doc_words: Vec<&str> = doc_lower.split_whitespace().collect();
-        
-        let mut matches = 0;
-        for query_word in &query_words {
-            if doc_words.contains(query_word) {
-                matches += 1;
-            }
-        }
-        
-        let score = if query_words.is_empty() {
-            0.0
-        } else {
-            (matches as f32) / (query_words.len() as f32)
-        };
-        
-        // Apply sigmoid to get a more realistic distribution
-        let sigmoid_score = self.sigmoid(score * 4.0 - 2.0); // Scale and shift for better range
-
-        debug!("Cross-encoder score: {:.4}", sigmoid_score);
-        Ok(sigmoid_score)
+        let mut scores = self.score_batch(std::slice::from_ref(pair)).await?;
+        scores
+            .pop()
+            .ok_or_else(|| SearchError::ModelError("Cross-encoder returned no score".to_string()))
     }
 
     /// Score multiple query-document pairs in batch
-    /// Returns scores in the same order as input pairs
+    /// All pairs are padded into a single tensor and run in one inference call.
+    /// Returns relevance probabilities in the same order as input pairs
     #[instrument(skip(self), fields(batch_size = pairs.len()))]
     pub async fn score_batch(&self, pairs: &[QueryDocumentPair]) -> SearchResult<Vec<f32>> {
         if pairs.is_empty() {
             return Ok(vec![]);
         }
 
-        // For now, process sequentially. In production, this could be optimized
-        // to use actual batch processing with padded sequences
-        let mut scores = Vec::with_capacity(pairs.len());
-        
-        for pair in pairs {
-            let score = self.score(pair).await?;
-            scores.push(score);
+        if pairs.iter().any(|pair| pair.query.trim().is_empty() || pair.document.trim().is_empty()) {
+            return Err(SearchError::ModelError("Empty query or document for cross-encoder".to_string()));
         }
 
+        let text_pairs: Vec<(&str, &str)> = pairs
+            .iter()
+            .map(|pair| (pair.query.as_str(), pair.document.as_str()))
+            .collect();
+        let batch = self.tokenizer.tokenize_pair_batch(&text_pairs)?;
+        let batch_size = batch.batch_size;
+
+        debug!(
+            "Scoring {} query-document pairs (seq_len {}) using model at {}",
+            batch_size,
+            batch.seq_len,
+            self.model_path.display()
+        );
+
+        let output = self.session().await?.run(batch).await?;
+
+        // ms-marco exports emit one relevance logit per pair; two-class heads emit [irrelevant, relevant]
+        let num_labels = match output.shape.as_slice() {
+            [rows] if *rows == batch_size => 1,
+            [rows, labels] if *rows == batch_size && *labels > 0 => *labels,
+            _ => {
+                return Err(SearchError::ModelError(format!(
+                    "Unexpected cross-encoder output shape {:?} for batch of {}",
+                    output.shape, batch_size
+                )))
+            }
+        };
+
+        let scores: Vec<f32> = output
+            .data
+            .chunks(num_labels)
+            .map(|logits| match logits {
+                [logit] => self.sigmoid(*logit),
+                _ => *self.softmax(logits).last().unwrap_or(&0.0),
+            })
+            .collect();
+
+        debug!("Cross-encoder produced {} scores", scores.len());
         Ok(scores)
     }
 
@@ -130,6 +148,13 @@ impl CrossEncoder {
         &self.model_path
     }
 
+    /// Get the ONNX session, loading it from disk on first use
+    async fn session(&self) -> SearchResult<&OnnxSession> {
+        self.session
+            .get_or_try_init(|| async { OnnxSession::load(&self.model_path) })
+            .await
+    }
+
     /// Apply sigmoid activation function
     fn sigmoid(&self, x: f32) -> f32 {
         1.0 / (1.0 + (-x).exp())
@@ -154,10 +179,10 @@ mod tests {
         use std::path::PathBuf;
         use crate::ml::tokenizer::TokenizerService;
         
-        let cross_encoder = CrossEncoder {
-            model_path: PathBuf::from("test_model.onnx"),
-            tokenizer: TokenizerService::new_sync().unwrap(),
-        };
+        let cross_encoder = CrossEncoder::new(
+            PathBuf::from("test_model.onnx"),
+            TokenizerService::new_sync().unwrap(),
+        );
 
         // Test sigmoid function
         assert!((cross_encoder.sigmoid(0.0) - 0.5).abs() < 0.001);
@@ -170,10 +195,10 @@ mod tests {
         use std::path::PathBuf;
         use crate::ml::tokenizer::TokenizerService;
         
-        let cross_encoder = CrossEncoder {
-            model_path: PathBuf::from("test_model.onnx"),
-            tokenizer: TokenizerService::new_sync().unwrap(),
-        };
+        let cross_encoder = CrossEncoder::new(
+            PathBuf::from("test_model.onnx"),
+            TokenizerService::new_sync().unwrap(),
+        );
 
         // Test softmax function
         let logits = vec![1.0, 2.0, 3.0];
@@ -210,12 +235,37 @@ mod tests {
         assert!((result.score - 0.85).abs() < 0.001);
     }
 
-    #[test]
-    fn test_empty_rerank() {
-        // Test that empty document list returns empty results
-        let documents: Vec<String> = vec![];
-        assert!(documents.is_empty());
-        
-        // This would be tested with actual CrossEncoder in integration tests
+    #[tokio::test]
+    async fn test_empty_rerank() {
+        use std::path::PathBuf;
+        use crate::ml::tokenizer::TokenizerService;
+
+        let cross_encoder = CrossEncoder::new(
+            PathBuf::from("test_model.onnx"),
+            TokenizerService::new_sync().unwrap(),
+        );
+
+        // Empty document list should not touch the model
+        let results = cross_encoder.rerank("test query", &[]).await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_score_batch_rejects_empty_document() {
+        use std::path::PathBuf;
+        use crate::ml::tokenizer::TokenizerService;
+
+        let cross_encoder = CrossEncoder::new(
+            PathBuf::from("test_model.onnx"),
+            TokenizerService::new_sync().unwrap(),
+        );
+
+        let pairs = vec![QueryDocumentPair {
+            query: "test query".to_string(),
+            document: "   ".to_string(),
+        }];
+
+        let result = cross_encoder.score_batch(&pairs).await;
+        assert!(result.unwrap_err().is_model_error());
     }
 }
\ No newline at end of file
@@ -0,0 +1,111 @@
+//! Pluggable embedding model abstraction
+//!
+//! The search pipeline only depends on the `Embedder` trait, so the active
+//! embedding model (and therefore the vector dimension used by Postgres and
+//! Redis) is selected from configuration rather than hardcoded.
+
+use crate::error::{SearchError, SearchResult};
+use async_trait::async_trait;
+
+/// Text embedding model used for query and document vectors
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Generate a unit-length embedding for a single text
+    async fn encode(&self, text: &str) -> SearchResult<Vec<f32>>;
+
+    /// Generate embeddings for multiple texts in one batch
+    async fn encode_batch(&self, texts: &[String]) -> SearchResult<Vec<Vec<f32>>>;
+
+    /// Number of dimensions in each embedding
+    fn dimension(&self) -> usize;
+
+    /// Stable identifier of the underlying model
+    fn model_id(&self) -> &str;
+}
+
+/// Supported ONNX embedding models
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddingModel {
+    /// sentence-transformers/all-MiniLM-L6-v2 (384 dimensions)
+    #[default]
+    MiniLmL6V2,
+    /// sentence-transformers/bert-base-nli-mean-tokens (768 dimensions)
+    BertBaseNliMeanTokens,
+}
+
+impl EmbeddingModel {
+    /// All supported models
+    pub const ALL: [EmbeddingModel; 2] = [EmbeddingModel::MiniLmL6V2, EmbeddingModel::BertBaseNliMeanTokens];
+
+    /// Look up a model by its identifier
+    pub fn from_model_id(model_id: &str) -> SearchResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|model| model.model_id() == model_id)
+            .ok_or_else(|| {
+                let supported: Vec<&str> = Self::ALL.iter().map(|model| model.model_id()).collect();
+                SearchError::ConfigError(format!(
+                    "Unsupported embedding model '{}', expected one of: {}",
+                    model_id,
+                    supported.join(", ")
+                ))
+            })
+    }
+
+    /// Model identifier, also used as the base name of its files
+    pub fn model_id(&self) -> &'static str {
+        match self {
+            EmbeddingModel::MiniLmL6V2 => "all-MiniLM-L6-v2",
+            EmbeddingModel::BertBaseNliMeanTokens => "bert-base-nli-mean-tokens",
+        }
+    }
+
+    /// Embedding dimension produced by the model
+    pub fn dimension(&self) -> usize {
+        match self {
+            EmbeddingModel::MiniLmL6V2 => 384,
+            EmbeddingModel::BertBaseNliMeanTokens => 768,
+        }
+    }
+
+    /// Default ONNX model file name
+    pub fn model_filename(&self) -> String {
+        format!("{}.onnx", self.model_id())
+    }
+
+    /// Default tokenizer.json file name
+    pub fn tokenizer_filename(&self) -> String {
+        format!("{}.tokenizer.json", self.model_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_lookup() {
+        assert_eq!(EmbeddingModel::from_model_id("all-MiniLM-L6-v2").unwrap(), EmbeddingModel::MiniLmL6V2);
+        assert_eq!(
+            EmbeddingModel::from_model_id("bert-base-nli-mean-tokens").unwrap(),
+            EmbeddingModel::BertBaseNliMeanTokens
+        );
+
+        let result = EmbeddingModel::from_model_id("unknown-model");
+        assert!(matches!(result, Err(SearchError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_model_dimensions() {
+        assert_eq!(EmbeddingModel::MiniLmL6V2.dimension(), 384);
+        assert_eq!(EmbeddingModel::BertBaseNliMeanTokens.dimension(), 768);
+        assert_eq!(EmbeddingModel::default(), EmbeddingModel::MiniLmL6V2);
+    }
+
+    #[test]
+    fn test_model_filenames() {
+        let model = EmbeddingModel::BertBaseNliMeanTokens;
+        assert_eq!(model.model_filename(), "bert-base-nli-mean-tokens.onnx");
+        assert_eq!(model.tokenizer_filename(), "bert-base-nli-mean-tokens.tokenizer.json");
+    }
+}
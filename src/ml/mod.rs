@@ -2,31 +2,41 @@
 /// 
 /// This module contains:
 /// - TokenizerService for text preprocessing and tokenization
-/// - BiEncoder for generating embeddings using all-MiniLM-L6-v2
+/// - Embedder trait with the ONNX BiEncoder implementation (MiniLM, BERT)
 /// - CrossEncoder for reranking using ms-marco-MiniLM-L-6-v2
+/// - Chunker for splitting long posts into passages
 /// - ModelLoader for downloading and verifying models from GCS
 
 pub mod tokenizer;
 pub mod model_loader;
 pub mod bi_encoder;
+pub mod embedder;
 pub mod cross_encoder;
+pub mod chunker;
+mod onnx;
 
 #[cfg(test)]
 mod tests;
 
 use crate::error::{SearchError, SearchResult};
-pub use tokenizer::TokenizerService;
-pub use model_loader::{ModelLoader, ModelConfig};
+use crate::types::PassageSpan;
+pub use tokenizer::{TokenizerService, TokenizedBatch};
+pub use model_loader::{ModelLoader, ModelConfig, ModelFiles};
 pub use bi_encoder::BiEncoder;
+pub use embedder::{Embedder, EmbeddingModel};
 pub use cross_encoder::{CrossEncoder, QueryDocumentPair, RerankResult};
+pub use chunker::Chunker;
 
 use std::sync::Arc;
 use tracing::{info, error};
 
 /// Complete ML service with ONNX model inference capabilities
 pub struct MLService {
-    bi_encoder: Arc<BiEncoder>,
+    embedder: Arc<dyn Embedder>,
     cross_encoder: Arc<CrossEncoder>,
+    chunker: Chunker,
+    /// Embedding model tokenizer, used to measure text against token budgets
+    tokenizer: TokenizerService,
 }
 
 impl MLService {
@@ -39,15 +49,18 @@ impl MLService {
     pub async fn new_with_config(config: ModelConfig) -> SearchResult<Self> {
         info!("Initializing ML service with ONNX models...");
 
+        let embedding_model = config.embedding_model;
+
         // Initialize model loader
         let model_loader = ModelLoader::new(config)?;
 
-        // Initialize tokenizer service
-        let tokenizer = TokenizerService::new().await?;
-
         // Load bi-encoder model with SHA256 verification
-        info!("Loading bi-encoder model (all-MiniLM-L6-v2)...");
-        let bi_encoder_path = model_loader.load_bi_encoder().await
+        info!(
+            "Loading bi-encoder model ({}, {} dimensions)...",
+            embedding_model.model_id(),
+            embedding_model.dimension()
+        );
+        let bi_encoder_files = model_loader.load_bi_encoder().await
             .map_err(|e| {
                 error!("Failed to load bi-encoder model: {}", e);
                 // Crash on model verification failure as per requirements
@@ -59,7 +72,7 @@ impl MLService {
 
         // Load cross-encoder model with SHA256 verification
         info!("Loading cross-encoder model (ms-marco-MiniLM-L-6-v2)...");
-        let cross_encoder_path = model_loader.load_cross_encoder().await
+        let cross_encoder_files = model_loader.load_cross_encoder().await
             .map_err(|e| {
                 error!("Failed to load cross-encoder model: {}", e);
                 // Crash on model verification failure as per requirements
@@ -69,26 +82,43 @@ impl MLService {
                 e
             })?;
 
+        // Each model ships its own vocabulary, so load a tokenizer per model
+        let max_sequence_length = model_loader.max_sequence_length();
+        let bi_encoder_tokenizer = TokenizerService::from_file(&bi_encoder_files.tokenizer_path, max_sequence_length)?;
+        let cross_encoder_tokenizer = TokenizerService::from_file(&cross_encoder_files.tokenizer_path, max_sequence_length)?;
+
+        // Passages leave room for [CLS] and [SEP] and overlap by a quarter window
+        let window_tokens = max_sequence_length.saturating_sub(2).max(1);
+        let chunker = Chunker::new(bi_encoder_tokenizer.clone(), window_tokens, window_tokens / 4);
+        let tokenizer = bi_encoder_tokenizer.clone();
+
         // Create encoder services
-        let bi_encoder = Arc::new(BiEncoder::new(bi_encoder_path, tokenizer.clone()));
-        let cross_encoder = Arc::new(CrossEncoder::new(cross_encoder_path, tokenizer));
+        let embedder: Arc<dyn Embedder> = Arc::new(BiEncoder::new(
+            bi_encoder_files.model_path,
+            bi_encoder_tokenizer,
+            embedding_model,
+        )?);
+        let cross_encoder = Arc::new(CrossEncoder::new(cross_encoder_files.model_path, cross_encoder_tokenizer));
+        cross_encoder.warm_up().await?;
 
         info!("ML service initialized successfully");
 
         Ok(MLService {
-            bi_encoder,
+            embedder,
             cross_encoder,
+            chunker,
+            tokenizer,
         })
     }
 
-    /// Generate embedding for a query using bi-encoder
-    /// Returns 384-dimensional normalized vector
+    /// Generate embedding for a query using the active embedder
+    /// Returns a normalized vector of `embedding_dimension()` values
     pub async fn generate_embedding(&self, query: &str) -> SearchResult<Vec<f32>> {
         if query.trim().is_empty() {
             return Err(SearchError::ModelError("Empty query for embedding generation".to_string()));
         }
 
-        self.bi_encoder.encode(query).await
+        self.embedder.encode(query).await
     }
 
     /// Generate embeddings for multiple queries in batch
@@ -97,7 +127,17 @@ impl MLService {
             return Ok(vec![]);
         }
 
-        self.bi_encoder.encode_batch(queries).await
+        self.embedder.encode_batch(queries).await
+    }
+
+    /// Split a post's content into passages sized for the embedding model
+    pub fn chunk_passages(&self, text: &str) -> SearchResult<Vec<PassageSpan>> {
+        self.chunker.chunk(text)
+    }
+
+    /// Count the tokens of a text with the embedding model's tokenizer
+    pub fn count_tokens(&self, text: &str) -> SearchResult<usize> {
+        self.tokenizer.count_tokens(text)
     }
 
     /// Rerank search results using cross-encoder
@@ -128,9 +168,19 @@ impl MLService {
         self.cross_encoder.score(&pair).await
     }
 
-    /// Get reference to bi-encoder for advanced usage
-    pub fn bi_encoder(&self) -> &BiEncoder {
-        &self.bi_encoder
+    /// Get reference to the active embedder for advanced usage
+    pub fn embedder(&self) -> &dyn Embedder {
+        self.embedder.as_ref()
+    }
+
+    /// Dimension of the active embedding model
+    pub fn embedding_dimension(&self) -> usize {
+        self.embedder.dimension()
+    }
+
+    /// Identifier of the active embedding model
+    pub fn embedding_model_id(&self) -> &str {
+        self.embedder.model_id()
     }
 
     /// Get reference to cross-encoder for advanced usage
@@ -1,4 +1,6 @@
+use crate::config::MLConfig;
 use crate::error::{SearchError, SearchResult};
+use crate::ml::embedder::EmbeddingModel;
 use reqwest::Client;
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
@@ -7,30 +9,83 @@ use tokio::io::AsyncWriteExt;
 use tracing::{info, warn};
 
 /// Configuration for model loading
+///
+/// Artifacts missing from their configured path are downloaded from
+/// `gcs_base_url` under the path's file name. A file is only verified when
+/// its SHA256 hash is configured.
 #[derive(Debug, Clone)]
 pub struct ModelConfig {
     /// GCS bucket URL for model storage
     pub gcs_base_url: String,
-    /// Local directory for model cache
-    pub model_cache_dir: PathBuf,
+    /// Embedding model served by the bi-encoder
+    pub embedding_model: EmbeddingModel,
+    /// Path to the bi-encoder model
+    pub bi_encoder_path: PathBuf,
+    /// Path to the bi-encoder tokenizer.json
+    pub bi_encoder_tokenizer_path: PathBuf,
+    /// Path to the cross-encoder model
+    pub cross_encoder_path: PathBuf,
+    /// Path to the cross-encoder tokenizer.json
+    pub cross_encoder_tokenizer_path: PathBuf,
     /// Expected SHA256 hash for bi-encoder model
-    pub bi_encoder_hash: String,
+    pub bi_encoder_hash: Option<String>,
+    /// Expected SHA256 hash for bi-encoder tokenizer
+    pub bi_encoder_tokenizer_hash: Option<String>,
     /// Expected SHA256 hash for cross-encoder model
-    pub cross_encoder_hash: String,
+    pub cross_encoder_hash: Option<String>,
+    /// Expected SHA256 hash for cross-encoder tokenizer
+    pub cross_encoder_tokenizer_hash: Option<String>,
+    /// Maximum sequence length the tokenizers truncate to
+    pub max_sequence_length: usize,
+}
+
+impl ModelConfig {
+    /// Build a model configuration from the application ML config
+    pub fn from_ml_config(ml: &MLConfig) -> SearchResult<Self> {
+        Ok(Self {
+            embedding_model: EmbeddingModel::from_model_id(&ml.embedding_model)?,
+            bi_encoder_path: PathBuf::from(&ml.embedding_model_path),
+            bi_encoder_tokenizer_path: PathBuf::from(&ml.embedding_tokenizer_path),
+            cross_encoder_path: PathBuf::from(&ml.rerank_model_path),
+            cross_encoder_tokenizer_path: PathBuf::from(&ml.rerank_tokenizer_path),
+            bi_encoder_hash: ml.embedding_model_sha256.clone(),
+            bi_encoder_tokenizer_hash: ml.embedding_tokenizer_sha256.clone(),
+            cross_encoder_hash: ml.rerank_model_sha256.clone(),
+            cross_encoder_tokenizer_hash: ml.rerank_tokenizer_sha256.clone(),
+            max_sequence_length: ml.max_sequence_length,
+            ..Self::default()
+        })
+    }
 }
 
 impl Default for ModelConfig {
     fn default() -> Self {
+        let models = Path::new("./models");
         Self {
             gcs_base_url: "https://storage.googleapis.com/prod-models/v1".to_string(),
-            model_cache_dir: PathBuf::from("./models"),
-            // These would be the actual SHA256 hashes of the production models
-            bi_encoder_hash: "placeholder_bi_encoder_hash".to_string(),
-            cross_encoder_hash: "placeholder_cross_encoder_hash".to_string(),
+            embedding_model: EmbeddingModel::default(),
+            bi_encoder_path: models.join(EmbeddingModel::default().model_filename()),
+            bi_encoder_tokenizer_path: models.join(EmbeddingModel::default().tokenizer_filename()),
+            cross_encoder_path: models.join("ms-marco-MiniLM-L-6-v2.onnx"),
+            cross_encoder_tokenizer_path: models.join("ms-marco-MiniLM-L-6-v2.tokenizer.json"),
+            bi_encoder_hash: None,
+            bi_encoder_tokenizer_hash: None,
+            cross_encoder_hash: None,
+            cross_encoder_tokenizer_hash: None,
+            max_sequence_length: 512,
         }
     }
 }
 
+/// Verified local files for a model and its tokenizer
+#[derive(Debug, Clone)]
+pub struct ModelFiles {
+    /// Path to the ONNX model
+    pub model_path: PathBuf,
+    /// Path to the HuggingFace tokenizer.json
+    pub tokenizer_path: PathBuf,
+}
+
 /// Model loader responsible for downloading and verifying ONNX models
 pub struct ModelLoader {
     config: ModelConfig,
@@ -51,71 +106,99 @@ impl ModelLoader {
         })
     }
 
-    /// Load bi-encoder model with verification
-    /// Returns the path to the verified model file
-    pub async fn load_bi_encoder(&self) -> SearchResult<PathBuf> {
-        self.ensure_model_available(
-            "all-MiniLM-L6-v2.onnx",
-            &self.config.bi_encoder_hash,
-        ).await
+    /// Load bi-encoder model and tokenizer with verification
+    /// Returns the paths to the verified files
+    pub async fn load_bi_encoder(&self) -> SearchResult<ModelFiles> {
+        Ok(ModelFiles {
+            model_path: self.ensure_model_available(
+                &self.config.bi_encoder_path,
+                self.config.bi_encoder_hash.as_deref(),
+            ).await?,
+            tokenizer_path: self.ensure_model_available(
+                &self.config.bi_encoder_tokenizer_path,
+                self.config.bi_encoder_tokenizer_hash.as_deref(),
+            ).await?,
+        })
+    }
+
+    /// Load cross-encoder model and tokenizer with verification
+    /// Returns the paths to the verified files
+    pub async fn load_cross_encoder(&self) -> SearchResult<ModelFiles> {
+        Ok(ModelFiles {
+            model_path: self.ensure_model_available(
+                &self.config.cross_encoder_path,
+                self.config.cross_encoder_hash.as_deref(),
+            ).await?,
+            tokenizer_path: self.ensure_model_available(
+                &self.config.cross_encoder_tokenizer_path,
+                self.config.cross_encoder_tokenizer_hash.as_deref(),
+            ).await?,
+        })
     }
 
-    /// Load cross-encoder model with verification
-    /// Returns the path to the verified model file
-    pub async fn load_cross_encoder(&self) -> SearchResult<PathBuf> {
-        self.ensure_model_available(
-            "ms-marco-MiniLM-L-6-v2.onnx",
-            &self.config.cross_encoder_hash,
-        ).await
+    /// Maximum sequence length for tokenizers created from these models
+    pub fn max_sequence_length(&self) -> usize {
+        self.config.max_sequence_length
     }
 
-    /// Ensure model is available locally, download if necessary
+    /// Ensure a model file is available locally, downloading it if it is missing
+    ///
+    /// Existing files are never deleted or replaced: a file that does not
+    /// match its configured hash is an error for the operator to resolve.
+    /// Downloads go to a temporary file that is only moved into place once
+    /// verified. Without a configured hash the file is used unverified.
     async fn ensure_model_available(
         &self,
-        model_filename: &str,
-        expected_hash: &str,
+        model_path: &Path,
+        expected_hash: Option<&str>,
     ) -> SearchResult<PathBuf> {
-        // Create cache directory if it doesn't exist
-        fs::create_dir_all(&self.config.model_cache_dir)
-            .await?;
-
-        let model_path = self.config.model_cache_dir.join(model_filename);
+        let model_filename = model_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| SearchError::ConfigError(format!("Invalid model path: {}", model_path.display())))?;
 
-        // Check if model exists and has correct hash
         if model_path.exists() {
-            match self.verify_model_hash(&model_path, expected_hash).await {
-                Ok(true) => {
-                    info!("Model {} found with correct hash", model_filename);
-                    return Ok(model_path);
+            return match expected_hash {
+                None => {
+                    info!("Model {} found; no SHA256 configured, skipping verification", model_path.display());
+                    Ok(model_path.to_path_buf())
                 }
-                Ok(false) => {
-                    warn!("Model {} has incorrect hash, re-downloading", model_filename);
-                    fs::remove_file(&model_path)
-                        .await?;
+                Some(expected_hash) if self.verify_model_hash(model_path, expected_hash).await? => {
+                    info!("Model {} found with correct hash", model_path.display());
+                    Ok(model_path.to_path_buf())
                 }
-                Err(e) => {
-                    warn!("Failed to verify model hash: {}, re-downloading", e);
-                    let _ = fs::remove_file(&model_path).await; // Ignore errors
-                }
-            }
+                Some(expected_hash) => Err(SearchError::ModelError(format!(
+                    "Model {} does not match its configured SHA256 hash {}; replace the file or correct the hash",
+                    model_path.display(),
+                    expected_hash
+                ))),
+            };
         }
 
-        // Download model from GCS
-        self.download_model(model_filename, &model_path).await?;
+        if let Some(dir) = model_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).await?;
+        }
 
-        // Verify downloaded model
-        if !self.verify_model_hash(&model_path, expected_hash).await? {
-            fs::remove_file(&model_path)
-                .await?;
-            
-            return Err(SearchError::ModelError(format!(
-                "Downloaded model {} has incorrect SHA256 hash. Expected: {}, service will crash to prevent using corrupted model.",
-                model_filename, expected_hash
-            )));
+        let download_path = model_path.with_file_name(format!("{}.download", model_filename));
+        if let Err(e) = self.download_model(&model_filename, &download_path).await {
+            let _ = fs::remove_file(&download_path).await;
+            return Err(e);
+        }
+
+        match expected_hash {
+            Some(expected_hash) if !self.verify_model_hash(&download_path, expected_hash).await? => {
+                let _ = fs::remove_file(&download_path).await;
+                return Err(SearchError::ModelError(format!(
+                    "Downloaded model {} has incorrect SHA256 hash. Expected: {}, service will crash to prevent using corrupted model.",
+                    model_filename, expected_hash
+                )));
+            }
+            Some(_) => info!("Model {} downloaded and verified successfully", model_filename),
+            None => warn!("Model {} downloaded; no SHA256 configured, skipping verification", model_filename),
         }
 
-        info!("Model {} downloaded and verified successfully", model_filename);
-        Ok(model_path)
+        fs::rename(&download_path, model_path).await?;
+        Ok(model_path.to_path_buf())
     }
 
     /// Download model from GCS
@@ -168,14 +251,12 @@ impl ModelLoader {
     }
 
     /// Verify that a model file exists and has the correct hash
-    pub async fn verify_model(&self, model_filename: &str, expected_hash: &str) -> SearchResult<bool> {
-        let model_path = self.config.model_cache_dir.join(model_filename);
-        
+    pub async fn verify_model(&self, model_path: &Path, expected_hash: &str) -> SearchResult<bool> {
         if !model_path.exists() {
             return Ok(false);
         }
         
-        self.verify_model_hash(&model_path, expected_hash).await
+        self.verify_model_hash(model_path, expected_hash).await
     }
 }
 
@@ -211,11 +292,65 @@ mod tests {
         assert!(!result);
     }
 
+    #[tokio::test]
+    async fn test_existing_model_is_never_deleted() {
+        let temp_dir = TempDir::new().unwrap();
+        let model_path = temp_dir.path().join("operator_model.onnx");
+        std::fs::write(&model_path, b"operator provided model").unwrap();
+
+        let loader = ModelLoader::new(ModelConfig::default()).unwrap();
+
+        // No configured hash: the file is used as-is
+        let path = loader.ensure_model_available(&model_path, None).await.unwrap();
+        assert_eq!(path, model_path);
+
+        // Hash mismatch: an error, and the file stays in place
+        let result = loader.ensure_model_available(&model_path, Some("deadbeef")).await;
+        assert!(matches!(result, Err(SearchError::ModelError(_))));
+        assert!(model_path.exists());
+    }
+
     #[test]
     fn test_model_config_default() {
         let config = ModelConfig::default();
         assert_eq!(config.gcs_base_url, "https://storage.googleapis.com/prod-models/v1");
-        assert_eq!(config.model_cache_dir, PathBuf::from("./models"));
+        assert_eq!(config.bi_encoder_path, PathBuf::from("./models/all-MiniLM-L6-v2.onnx"));
+        assert!(config.bi_encoder_hash.is_none());
+    }
+
+    #[test]
+    fn test_model_config_from_ml_config() {
+        let ml = MLConfig {
+            embedding_model: "bert-base-nli-mean-tokens".to_string(),
+            embedding_model_path: "/opt/models/bge-base.onnx".to_string(),
+            embedding_tokenizer_path: "/opt/tokenizers/bge-base.tokenizer.json".to_string(),
+            rerank_model_path: "/srv/rerank/reranker.onnx".to_string(),
+            rerank_tokenizer_path: "/srv/rerank/reranker.tokenizer.json".to_string(),
+            max_sequence_length: 256,
+            embedding_dimension: 768,
+            embedding_model_sha256: Some("ab12".to_string()),
+            embedding_tokenizer_sha256: None,
+            rerank_model_sha256: None,
+            rerank_tokenizer_sha256: Some("cd34".to_string()),
+        };
+
+        // Every artifact keeps its own configured directory
+        let config = ModelConfig::from_ml_config(&ml).unwrap();
+        assert_eq!(config.embedding_model, EmbeddingModel::BertBaseNliMeanTokens);
+        assert_eq!(config.bi_encoder_path, PathBuf::from("/opt/models/bge-base.onnx"));
+        assert_eq!(config.bi_encoder_tokenizer_path, PathBuf::from("/opt/tokenizers/bge-base.tokenizer.json"));
+        assert_eq!(config.cross_encoder_path, PathBuf::from("/srv/rerank/reranker.onnx"));
+        assert_eq!(config.cross_encoder_tokenizer_path, PathBuf::from("/srv/rerank/reranker.tokenizer.json"));
+        assert_eq!(config.bi_encoder_hash.as_deref(), Some("ab12"));
+        assert!(config.bi_encoder_tokenizer_hash.is_none());
+        assert_eq!(config.cross_encoder_tokenizer_hash.as_deref(), Some("cd34"));
+        assert_eq!(config.max_sequence_length, 256);
+
+        let unknown = MLConfig {
+            embedding_model: "unknown-model".to_string(),
+            ..ml
+        };
+        assert!(ModelConfig::from_ml_config(&unknown).is_err());
     }
 
     #[test]
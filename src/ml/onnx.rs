@@ -0,0 +1,105 @@
+//! Shared ONNX Runtime session handling for the encoder models
+//!
+//! Wraps an `ort` session so that it can be shared between concurrent requests
+//! and runs inference on the blocking thread pool to keep the async runtime free.
+
+use crate::error::{SearchError, SearchResult};
+use crate::ml::tokenizer::TokenizedBatch;
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use ort::value::Tensor;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+
+/// Raw output tensor returned by a model: shape plus row-major data
+#[derive(Debug, Clone)]
+pub(crate) struct ModelOutput {
+    pub shape: Vec<usize>,
+    pub data: Vec<f32>,
+}
+
+/// Thread-safe handle to a loaded ONNX session
+///
+/// `Session::run` requires exclusive access, so the session lives behind a mutex.
+#[derive(Clone)]
+pub(crate) struct OnnxSession {
+    session: Arc<Mutex<Session>>,
+    uses_token_type_ids: bool,
+}
+
+impl OnnxSession {
+    /// Load and optimize an ONNX model from disk
+    pub fn load(model_path: &Path) -> SearchResult<Self> {
+        info!("Creating ONNX Runtime session for {}", model_path.display());
+
+        let session = Session::builder()
+            .and_then(|builder| builder.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|builder| builder.commit_from_file(model_path))
+            .map_err(|e| SearchError::ModelError(format!(
+                "Failed to create ONNX session for {}: {}",
+                model_path.display(),
+                e
+            )))?;
+
+        // Not every BERT export takes token_type_ids, so only feed it when declared
+        let uses_token_type_ids = session
+            .inputs
+            .iter()
+            .any(|input| input.name == "token_type_ids");
+
+        debug!(
+            "ONNX session ready with {} inputs (token_type_ids: {})",
+            session.inputs.len(),
+            uses_token_type_ids
+        );
+
+        Ok(OnnxSession {
+            session: Arc::new(Mutex::new(session)),
+            uses_token_type_ids,
+        })
+    }
+
+    /// Run the model on a padded batch and return its first output tensor
+    pub async fn run(&self, batch: TokenizedBatch) -> SearchResult<ModelOutput> {
+        let session = Arc::clone(&self.session);
+        let uses_token_type_ids = self.uses_token_type_ids;
+
+        tokio::task::spawn_blocking(move || {
+            let shape = batch.shape();
+            let input_ids = Tensor::from_array((shape, batch.input_ids))
+                .map_err(|e| SearchError::ModelError(format!("Failed to create input_ids tensor: {}", e)))?;
+            let attention_mask = Tensor::from_array((shape, batch.attention_mask))
+                .map_err(|e| SearchError::ModelError(format!("Failed to create attention_mask tensor: {}", e)))?;
+
+            let mut inputs = ort::inputs![
+                "input_ids" => input_ids,
+                "attention_mask" => attention_mask,
+            ];
+
+            if uses_token_type_ids {
+                let token_type_ids = Tensor::from_array((shape, batch.token_type_ids))
+                    .map_err(|e| SearchError::ModelError(format!("Failed to create token_type_ids tensor: {}", e)))?;
+                inputs.push(("token_type_ids".into(), token_type_ids.into()));
+            }
+
+            let mut session = session
+                .lock()
+                .map_err(|_| SearchError::Internal("ONNX session lock poisoned".to_string()))?;
+
+            let outputs = session
+                .run(inputs)
+                .map_err(|e| SearchError::ModelError(format!("ONNX inference failed: {}", e)))?;
+
+            let (output_shape, data) = outputs[0]
+                .try_extract_tensor::<f32>()
+                .map_err(|e| SearchError::ModelError(format!("Failed to extract model output: {}", e)))?;
+
+            Ok(ModelOutput {
+                shape: output_shape.iter().map(|&dim| dim as usize).collect(),
+                data: data.to_vec(),
+            })
+        })
+        .await
+        .map_err(|e| SearchError::Internal(format!("Inference task failed: {}", e)))?
+    }
+}
@@ -18,24 +18,26 @@ mod tests {
     async fn test_model_config_default() {
         let config = ModelConfig::default();
         assert_eq!(config.gcs_base_url, "https://storage.googleapis.com/prod-models/v1");
-        assert_eq!(config.model_cache_dir, PathBuf::from("./models"));
-        assert!(!config.bi_encoder_hash.is_empty());
-        assert!(!config.cross_encoder_hash.is_empty());
+        assert_eq!(config.cross_encoder_path, PathBuf::from("./models/ms-marco-MiniLM-L-6-v2.onnx"));
+        // Verification is opt-in: no hash is assumed for operator-provided files
+        assert!(config.bi_encoder_hash.is_none());
+        assert!(config.cross_encoder_hash.is_none());
     }
 
     #[tokio::test]
     async fn test_model_config_custom() {
         let config = ModelConfig {
             gcs_base_url: "https://custom-bucket.com/models".to_string(),
-            model_cache_dir: PathBuf::from("/tmp/models"),
-            bi_encoder_hash: "custom_bi_hash".to_string(),
-            cross_encoder_hash: "custom_cross_hash".to_string(),
+            bi_encoder_path: PathBuf::from("/tmp/models/bi.onnx"),
+            bi_encoder_hash: Some("custom_bi_hash".to_string()),
+            cross_encoder_hash: Some("custom_cross_hash".to_string()),
+            ..ModelConfig::default()
         };
 
         assert_eq!(config.gcs_base_url, "https://custom-bucket.com/models");
-        assert_eq!(config.model_cache_dir, PathBuf::from("/tmp/models"));
-        assert_eq!(config.bi_encoder_hash, "custom_bi_hash");
-        assert_eq!(config.cross_encoder_hash, "custom_cross_hash");
+        assert_eq!(config.bi_encoder_path, PathBuf::from("/tmp/models/bi.onnx"));
+        assert_eq!(config.bi_encoder_hash.as_deref(), Some("custom_bi_hash"));
+        assert_eq!(config.cross_encoder_hash.as_deref(), Some("custom_cross_hash"));
     }
 
     #[tokio::test]
@@ -1,7 +1,8 @@
 use crate::error::{SearchError, SearchResult};
 use farmhash;
 use std::collections::HashMap;
-use tokenizers::tokenizer::Tokenizer;
+use std::path::Path;
+use tokenizers::tokenizer::{Encoding, PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
 
 /// Tokenized text with all necessary components for ONNX model inference
 #[derive(Debug, Clone)]
@@ -14,6 +15,62 @@ pub struct TokenizedText {
     pub token_type_ids: Vec<u32>,
 }
 
+/// Batch of tokenized texts padded to a common sequence length
+///
+/// All buffers are laid out row-major as `[batch_size, seq_len]` so they can be
+/// handed directly to ONNX Runtime as int64 input tensors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenizedBatch {
+    /// Padded token IDs
+    pub input_ids: Vec<i64>,
+    /// Attention mask (0 for padding positions)
+    pub attention_mask: Vec<i64>,
+    /// Padded token type IDs
+    pub token_type_ids: Vec<i64>,
+    /// Number of sequences in the batch
+    pub batch_size: usize,
+    /// Length of the longest sequence in the batch
+    pub seq_len: usize,
+}
+
+impl TokenizedBatch {
+    /// Pad a set of tokenized texts to the longest sequence in the set
+    pub fn from_tokenized(items: &[TokenizedText]) -> Self {
+        let batch_size = items.len();
+        let seq_len = items.iter().map(|t| t.input_ids.len()).max().unwrap_or(0);
+
+        let mut input_ids = vec![0i64; batch_size * seq_len];
+        let mut attention_mask = vec![0i64; batch_size * seq_len];
+        let mut token_type_ids = vec![0i64; batch_size * seq_len];
+
+        for (row, item) in items.iter().enumerate() {
+            let offset = row * seq_len;
+            for (i, &id) in item.input_ids.iter().enumerate() {
+                input_ids[offset + i] = id as i64;
+            }
+            for (i, &mask) in item.attention_mask.iter().enumerate() {
+                attention_mask[offset + i] = mask as i64;
+            }
+            for (i, &type_id) in item.token_type_ids.iter().enumerate() {
+                token_type_ids[offset + i] = type_id as i64;
+            }
+        }
+
+        TokenizedBatch {
+            input_ids,
+            attention_mask,
+            token_type_ids,
+            batch_size,
+            seq_len,
+        }
+    }
+
+    /// Tensor shape for the batch inputs
+    pub fn shape(&self) -> [usize; 2] {
+        [self.batch_size, self.seq_len]
+    }
+}
+
 /// TokenizerService handles text preprocessing, normalization, and tokenization
 /// for semantic search queries. It provides query normalization, text cleaning,
 /// and cache key generation using farmhash64.
@@ -24,10 +81,9 @@ pub struct TokenizerService {
 
 impl TokenizerService {
     /// Create a new TokenizerService instance with async initialization
-    /// This will load the actual tokenizer for ONNX model integration
+    /// Only text normalization and cache keys are available; use `from_file`
+    /// to load a tokenizer for ONNX model inference
     pub async fn new() -> SearchResult<Self> {
-        // For now, create without tokenizer - in production this would load
-        // the actual tokenizer.json file for the BERT-based models
         Ok(TokenizerService {
             tokenizer: None,
         })
@@ -40,6 +96,31 @@ impl TokenizerService {
         })
     }
 
+    /// Load a HuggingFace tokenizer.json from disk
+    ///
+    /// Truncation is capped at `max_sequence_length` and batches are padded to
+    /// their longest sequence, keeping any pad token defined in the file.
+    pub fn from_file(path: &Path, max_sequence_length: usize) -> SearchResult<Self> {
+        let mut tokenizer = Tokenizer::from_file(path).map_err(|e| {
+            SearchError::ModelError(format!("Failed to load tokenizer from {}: {}", path.display(), e))
+        })?;
+
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: max_sequence_length,
+                ..TruncationParams::default()
+            }))
+            .map_err(|e| SearchError::ModelError(format!("Invalid tokenizer truncation: {}", e)))?;
+
+        let padding = PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..tokenizer.get_padding().cloned().unwrap_or_default()
+        };
+        tokenizer.with_padding(Some(padding));
+
+        Ok(Self::with_tokenizer(tokenizer))
+    }
+
     /// Create TokenizerService with a specific tokenizer
     /// This will be used when we integrate with actual ONNX models
     pub fn with_tokenizer(tokenizer: Tokenizer) -> Self {
@@ -159,32 +240,82 @@ impl TokenizerService {
     /// Returns TokenizedText with input_ids, attention_mask, and token_type_ids
     /// that can be used for ONNX model inference.
     pub fn tokenize(&self, text: &str) -> SearchResult<TokenizedText> {
-        match &self.tokenizer {
-            Some(tokenizer) => {
-                let cleaned_text = self.clean_text(text);
-                
-                let encoding = tokenizer
-                    .encode(cleaned_text, false)
-                    .map_err(|e| SearchError::ModelError(format!("Tokenization failed: {}", e)))?;
-                
-                let input_ids = encoding.get_ids().to_vec();
-                let attention_mask = encoding.get_attention_mask().to_vec();
-                let token_type_ids = encoding.get_type_ids().to_vec();
-                
-                Ok(TokenizedText {
-                    input_ids,
-                    attention_mask,
-                    token_type_ids,
-                })
-            }
-            None => {
-                // For now, return an error since we don't have a tokenizer loaded
-                // This will be implemented when we add ONNX model loading
-                Err(SearchError::ModelError(
-                    "Tokenizer not loaded - will be implemented with ONNX model integration".to_string()
-                ))
-            }
+        let tokenizer = self.loaded_tokenizer()?;
+        let cleaned_text = self.clean_text(text);
+
+        let encoding = tokenizer
+            .encode(cleaned_text, true)
+            .map_err(|e| SearchError::ModelError(format!("Tokenization failed: {}", e)))?;
+
+        Ok(Self::encoding_to_tokenized(&encoding))
+    }
+
+    /// Tokenize a query-document pair as a single sentence pair
+    ///
+    /// The query is the first segment (token_type_id 0) and the document the
+    /// second segment (token_type_id 1), as expected by BERT cross-encoders.
+    pub fn tokenize_pair(&self, query: &str, document: &str) -> SearchResult<TokenizedText> {
+        let tokenizer = self.loaded_tokenizer()?;
+        let cleaned_query = self.clean_text(query);
+        let cleaned_document = self.clean_text(document);
+
+        let encoding = tokenizer
+            .encode((cleaned_query, cleaned_document), true)
+            .map_err(|e| SearchError::ModelError(format!("Pair tokenization failed: {}", e)))?;
+
+        Ok(Self::encoding_to_tokenized(&encoding))
+    }
+
+    /// Tokenize multiple texts and pad them into a single batch
+    pub fn tokenize_batch(&self, texts: &[String]) -> SearchResult<TokenizedBatch> {
+        let tokenizer = self.loaded_tokenizer()?;
+        let cleaned: Vec<String> = texts.iter().map(|text| self.clean_text(text)).collect();
+
+        let encodings = tokenizer
+            .encode_batch(cleaned, true)
+            .map_err(|e| SearchError::ModelError(format!("Batch tokenization failed: {}", e)))?;
+
+        Ok(Self::encodings_to_batch(&encodings))
+    }
+
+    /// Tokenize multiple query-document pairs and pad them into a single batch
+    pub fn tokenize_pair_batch(&self, pairs: &[(&str, &str)]) -> SearchResult<TokenizedBatch> {
+        let tokenizer = self.loaded_tokenizer()?;
+        let cleaned: Vec<(String, String)> = pairs
+            .iter()
+            .map(|(query, document)| (self.clean_text(query), self.clean_text(document)))
+            .collect();
+
+        let encodings = tokenizer
+            .encode_batch(cleaned, true)
+            .map_err(|e| SearchError::ModelError(format!("Pair batch tokenization failed: {}", e)))?;
+
+        Ok(Self::encodings_to_batch(&encodings))
+    }
+
+    /// Byte offsets of every token of the raw text, without special tokens
+    ///
+    /// The text is not cleaned, so offsets index into it directly. Tokens past
+    /// the truncation length come back as overflowing encodings; with the
+    /// default stride of 0 they do not overlap and are appended in order.
+    pub fn token_offsets(&self, text: &str) -> SearchResult<Vec<(usize, usize)>> {
+        let tokenizer = self.loaded_tokenizer()?;
+
+        let encoding = tokenizer
+            .encode(text, false)
+            .map_err(|e| SearchError::ModelError(format!("Tokenization failed: {}", e)))?;
+
+        let mut offsets = encoding.get_offsets().to_vec();
+        for overflow in encoding.get_overflowing() {
+            offsets.extend_from_slice(overflow.get_offsets());
         }
+
+        Ok(offsets)
+    }
+
+    /// Number of tokens in the raw text, without special tokens or truncation
+    pub fn count_tokens(&self, text: &str) -> SearchResult<usize> {
+        Ok(self.token_offsets(text)?.len())
     }
 
     /// Tokenize text and return only token IDs (legacy method)
@@ -235,6 +366,30 @@ impl TokenizerService {
         Ok(())
     }
 
+    /// Get the loaded tokenizer or a model error if none is available
+    fn loaded_tokenizer(&self) -> SearchResult<&Tokenizer> {
+        self.tokenizer.as_ref().ok_or_else(|| {
+            SearchError::ModelError(
+                "Tokenizer not loaded - create the service with TokenizerService::from_file".to_string()
+            )
+        })
+    }
+
+    /// Convert a tokenizers encoding into model inputs
+    fn encoding_to_tokenized(encoding: &Encoding) -> TokenizedText {
+        TokenizedText {
+            input_ids: encoding.get_ids().to_vec(),
+            attention_mask: encoding.get_attention_mask().to_vec(),
+            token_type_ids: encoding.get_type_ids().to_vec(),
+        }
+    }
+
+    /// Convert a batch of encodings into a padded tensor batch
+    fn encodings_to_batch(encodings: &[Encoding]) -> TokenizedBatch {
+        let tokenized: Vec<TokenizedText> = encodings.iter().map(Self::encoding_to_tokenized).collect();
+        TokenizedBatch::from_tokenized(&tokenized)
+    }
+
     /// Helper function to normalize whitespace in text
     fn normalize_whitespace(&self, text: &str) -> String {
         let mut result = String::new();
@@ -420,6 +575,62 @@ mod tests {
         assert!(result.unwrap_err().is_model_error());
     }
 
+    #[test]
+    fn test_tokenized_batch_padding() {
+        let items = vec![
+            TokenizedText {
+                input_ids: vec![101, 7592, 102],
+                attention_mask: vec![1, 1, 1],
+                token_type_ids: vec![0, 0, 0],
+            },
+            TokenizedText {
+                input_ids: vec![101, 102],
+                attention_mask: vec![1, 1],
+                token_type_ids: vec![0, 0],
+            },
+        ];
+
+        let batch = TokenizedBatch::from_tokenized(&items);
+        assert_eq!(batch.shape(), [2, 3]);
+        assert_eq!(batch.input_ids, vec![101, 7592, 102, 101, 102, 0]);
+        assert_eq!(batch.attention_mask, vec![1, 1, 1, 1, 1, 0]);
+        assert_eq!(batch.token_type_ids, vec![0; 6]);
+    }
+
+    #[test]
+    fn test_tokenize_batch_without_tokenizer() {
+        let tokenizer = TokenizerService::new_sync().unwrap();
+
+        let result = tokenizer.tokenize_batch(&["hello world".to_string()]);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().is_model_error());
+    }
+
+    #[test]
+    fn test_from_file_missing_tokenizer() {
+        let result = TokenizerService::from_file(Path::new("models/does-not-exist.tokenizer.json"), 128);
+        assert!(matches!(result, Err(e) if e.is_model_error()));
+    }
+
+    #[test]
+    fn test_token_offsets_without_tokenizer() {
+        let tokenizer = TokenizerService::new_sync().unwrap();
+
+        let result = tokenizer.token_offsets("hello world");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().is_model_error());
+        assert!(tokenizer.count_tokens("hello world").unwrap_err().is_model_error());
+    }
+
+    #[test]
+    fn test_tokenize_pair_without_tokenizer() {
+        let tokenizer = TokenizerService::new_sync().unwrap();
+
+        let result = tokenizer.tokenize_pair("query", "document");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().is_model_error());
+    }
+
     #[test]
     fn test_vocab_size_without_tokenizer() {
         let tokenizer = TokenizerService::new_sync().unwrap();
@@ -0,0 +1,119 @@
+//! Retrieval-augmented answer generation
+//!
+//! Runs the normal semantic search, packs the top snippets into a numbered
+//! context within the token budget and asks the generator for an answer that
+//! cites the passages by number.
+
+use crate::config::GeneratorConfig;
+use crate::error::{SearchError, SearchResult};
+use crate::rag::context::{AssembledContext, ContextService, Packing};
+use crate::rag::generator::{GenerationPrompt, Generator, TokenStream};
+use crate::types::{AnswerRequest, AnswerResponse, Citation};
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Answer given when no search result fits the context, without calling the generator
+pub const NO_CONTEXT_ANSWER: &str = "No relevant posts were found to answer this question.";
+
+/// Share of the context budget filled with passages
+///
+/// The budget is counted with the embedding tokenizer, which usually splits
+/// text differently from the generator's, so the rest is left as a margin
+/// against the generator counting more tokens for the same context.
+const CONTEXT_BUDGET_SHARE: f32 = 0.8;
+
+/// Answer streamed as it is generated
+pub struct AnswerStream {
+    /// Results given to the generator, known before generation starts
+    pub citations: Vec<Citation>,
+    /// Generator model writing the answer
+    pub model: String,
+    /// Answer text pieces
+    pub tokens: TokenStream,
+}
+
+/// Service answering questions from search results
+pub struct AnswerService {
+    /// Context service retrieving and packing the search results
+    context_service: Arc<ContextService>,
+    /// Backend writing the answers
+    generator: Arc<dyn Generator>,
+    /// Context budget in tokens when the request sets none
+    max_context_tokens: u32,
+    /// Answer length in tokens when the request sets none
+    max_answer_tokens: u32,
+}
+
+impl AnswerService {
+    /// Create a new answer service
+    pub fn new(context_service: Arc<ContextService>, generator: Arc<dyn Generator>, config: &GeneratorConfig) -> Self {
+        Self {
+            context_service,
+            generator,
+            max_context_tokens: config.max_context_tokens,
+            max_answer_tokens: config.max_answer_tokens,
+        }
+    }
+
+    /// Generate a complete answer with its citations
+    #[instrument(skip(self, request), fields(k = request.k))]
+    pub async fn answer(&self, request: AnswerRequest) -> SearchResult<AnswerResponse> {
+        let (prompt, context) = self.prepare(&request).await?;
+
+        let answer = if context.is_empty() {
+            NO_CONTEXT_ANSWER.to_string()
+        } else {
+            self.generator.generate(&prompt).await?
+        };
+
+        info!("Generated answer from {} passages", context.passages.len());
+
+        Ok(AnswerResponse {
+            answer,
+            citations: context.citations(),
+            model: self.generator.model_id().to_string(),
+        })
+    }
+
+    /// Start generating an answer as a stream
+    ///
+    /// Search and context assembly finish before this returns, so their
+    /// errors are reported up front; generation errors arrive in the stream.
+    #[instrument(skip(self, request), fields(k = request.k))]
+    pub async fn answer_stream(&self, request: AnswerRequest) -> SearchResult<AnswerStream> {
+        let (prompt, context) = self.prepare(&request).await?;
+
+        let tokens: TokenStream = if context.is_empty() {
+            Box::pin(futures::stream::once(async { Ok::<_, SearchError>(NO_CONTEXT_ANSWER.to_string()) }))
+        } else {
+            self.generator.generate_stream(&prompt).await?
+        };
+
+        Ok(AnswerStream {
+            citations: context.citations(),
+            model: self.generator.model_id().to_string(),
+            tokens,
+        })
+    }
+
+    /// Search for the question and build the generator prompt
+    async fn prepare(&self, request: &AnswerRequest) -> SearchResult<(GenerationPrompt, AssembledContext)> {
+        request.validate().map_err(SearchError::InvalidRequest)?;
+
+        let budget = request.max_context_tokens.unwrap_or(self.max_context_tokens);
+        let budget = ((budget as f32 * CONTEXT_BUDGET_SHARE) as usize).max(1);
+        let context = self
+            .context_service
+            .assemble(request.to_search_request(), budget, Packing::RankOrder)
+            .await?;
+
+        let prompt = GenerationPrompt {
+            question: request.query.clone(),
+            context: context.text(),
+            source_count: context.passages.len(),
+            max_tokens: request.max_answer_tokens.unwrap_or(self.max_answer_tokens),
+        };
+
+        Ok((prompt, context))
+    }
+}
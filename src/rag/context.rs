@@ -0,0 +1,420 @@
+//! Token-budgeted context assembly
+//!
+//! Search results are rendered as numbered passages (`[1] Title (url)`
+//! followed by the snippet) and added in rank order while they fit the token
+//! budget, so a generated answer can cite them by number. Compact packing also
+//! drops passages repeating text already in the context and cuts a passage
+//! that does not fit at a word boundary when enough budget is left.
+
+use crate::error::{SearchError, SearchResult};
+use crate::ml::MLService;
+use crate::search::SearchService;
+use crate::types::{Citation, ContextRequest, ContextResponse, ContextSource, SearchRequest, SearchResponse};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Separator between rendered passages
+const PASSAGE_SEPARATOR: &str = "\n\n";
+
+/// Marker appended to a passage cut to fit the budget
+const TRUNCATION_MARKER: &str = "...";
+
+/// Smallest remaining budget worth filling with a cut passage
+const MIN_TRIMMED_TOKENS: usize = 16;
+
+/// Share of a passage's words found in an earlier passage above which it is a duplicate
+const DUPLICATE_WORD_OVERLAP: f32 = 0.8;
+
+/// How search results are packed into the token budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Packing {
+    /// Whole results in rank order, skipping those that do not fit
+    RankOrder,
+    /// Rank order without repeated passages, trimming the one that does not fit
+    Compact,
+}
+
+/// Search result included in an assembled context
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextPassage {
+    /// 1-based number the passage is cited by
+    pub index: u32,
+    /// External post identifier
+    pub post_id: String,
+    /// Post title
+    pub title: String,
+    /// Post URL
+    pub url: String,
+    /// Passage text
+    pub text: String,
+    /// Search score of the result
+    pub score: f32,
+    /// Tokens of the rendered passage
+    pub tokens: usize,
+    /// Whether the text was cut to fit the budget
+    pub truncated: bool,
+}
+
+impl ContextPassage {
+    /// Passage as it appears in the context block
+    pub fn render(&self) -> String {
+        format!("[{}] {} ({})\n{}", self.index, self.title, self.url, self.text)
+    }
+
+    /// Citation pointing back to the post
+    pub fn citation(&self) -> Citation {
+        Citation {
+            index: self.index,
+            post_id: self.post_id.clone(),
+            title: self.title.clone(),
+            url: self.url.clone(),
+            score: self.score,
+        }
+    }
+}
+
+/// Numbered passages that fit a token budget
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssembledContext {
+    /// Passages in rank order, numbered from 1
+    pub passages: Vec<ContextPassage>,
+    /// Total tokens of the rendered passages
+    pub tokens: usize,
+}
+
+impl AssembledContext {
+    /// Whether no result fit the budget
+    pub fn is_empty(&self) -> bool {
+        self.passages.is_empty()
+    }
+
+    /// Context block handed to the generator
+    pub fn text(&self) -> String {
+        self.passages
+            .iter()
+            .map(ContextPassage::render)
+            .collect::<Vec<_>>()
+            .join(PASSAGE_SEPARATOR)
+    }
+
+    /// Citations for every passage, in passage order
+    pub fn citations(&self) -> Vec<Citation> {
+        self.passages.iter().map(ContextPassage::citation).collect()
+    }
+
+    /// Passages with their character ranges in `text()`
+    ///
+    /// The ranges locate the rendered passage, header line included, within
+    /// the context block; they are not offsets into the source post.
+    pub fn sources(&self) -> Vec<ContextSource> {
+        let separator_chars = PASSAGE_SEPARATOR.chars().count();
+        let mut offset = 0;
+
+        self.passages
+            .iter()
+            .map(|passage| {
+                let start = offset;
+                let end = start + passage.render().chars().count();
+                offset = end + separator_chars;
+
+                ContextSource {
+                    index: passage.index,
+                    post_id: passage.post_id.clone(),
+                    title: passage.title.clone(),
+                    url: passage.url.clone(),
+                    score: passage.score,
+                    start: start as u32,
+                    end: end as u32,
+                    tokens: passage.tokens as u32,
+                    truncated: passage.truncated,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Pack search results into a context of at most `budget` tokens
+///
+/// Results are taken in rank order and results without a snippet are left
+/// out. A result that does not fit is skipped so a shorter result further
+/// down can still use the budget.
+///
+/// With `Packing::Compact`, further results of a post already included and
+/// passages whose words mostly repeat an included passage are left out too,
+/// and a passage that does not fit is cut to the remaining budget if at least
+/// `MIN_TRIMMED_TOKENS` are left.
+pub fn assemble_context<F>(
+    results: &[SearchResponse],
+    budget: usize,
+    packing: Packing,
+    count_tokens: F,
+) -> SearchResult<AssembledContext>
+where
+    F: Fn(&str) -> SearchResult<usize>,
+{
+    let mut context = AssembledContext::default();
+    let mut included_posts = HashSet::new();
+    let mut included_words: Vec<HashSet<String>> = Vec::new();
+
+    for result in results {
+        let text = result.snippet.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let words = word_set(text);
+        if packing == Packing::Compact
+            && (included_posts.contains(&result.post_id)
+                || included_words.iter().any(|included| is_duplicate(&words, included)))
+        {
+            continue;
+        }
+
+        let mut passage = ContextPassage {
+            index: context.passages.len() as u32 + 1,
+            post_id: result.post_id.clone(),
+            title: result.title.clone(),
+            url: result.meta.url.clone(),
+            text: text.to_string(),
+            score: result.score,
+            tokens: 0,
+            truncated: false,
+        };
+        passage.tokens = count_tokens(&passage.render())?;
+
+        let remaining = budget - context.tokens;
+        if passage.tokens > remaining {
+            if packing == Packing::RankOrder || remaining < MIN_TRIMMED_TOKENS {
+                continue;
+            }
+            passage = match trim_passage(passage, remaining, &count_tokens)? {
+                Some(trimmed) => trimmed,
+                None => continue,
+            };
+        }
+
+        context.tokens += passage.tokens;
+        included_posts.insert(passage.post_id.clone());
+        included_words.push(words);
+        context.passages.push(passage);
+    }
+
+    Ok(context)
+}
+
+/// Cut a passage to the longest run of leading words that fits `budget`
+fn trim_passage<F>(passage: ContextPassage, budget: usize, count_tokens: &F) -> SearchResult<Option<ContextPassage>>
+where
+    F: Fn(&str) -> SearchResult<usize>,
+{
+    let words: Vec<&str> = passage.text.split_whitespace().collect();
+
+    let cut = |count: usize| -> SearchResult<ContextPassage> {
+        let mut trimmed = ContextPassage {
+            text: format!("{}{}", words[..count].join(" "), TRUNCATION_MARKER),
+            truncated: true,
+            ..passage.clone()
+        };
+        trimmed.tokens = count_tokens(&trimmed.render())?;
+        Ok(trimmed)
+    };
+
+    // Binary search for the most words that fit
+    let (mut low, mut high) = (1, words.len().saturating_sub(1));
+    let mut best = None;
+    while low <= high {
+        let middle = (low + high) / 2;
+        let trimmed = cut(middle)?;
+        if trimmed.tokens <= budget {
+            best = Some(trimmed);
+            low = middle + 1;
+        } else {
+            high = middle - 1;
+        }
+    }
+
+    Ok(best)
+}
+
+/// Lowercased words of a passage
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether most of a passage's words already appear in another one
+fn is_duplicate(words: &HashSet<String>, included: &HashSet<String>) -> bool {
+    let smaller = words.len().min(included.len());
+    if smaller == 0 {
+        return false;
+    }
+
+    let shared = words.intersection(included).count();
+    shared as f32 / smaller as f32 >= DUPLICATE_WORD_OVERLAP
+}
+
+/// Service building context blocks from search results
+pub struct ContextService {
+    /// Search service retrieving the results
+    search_service: Arc<SearchService>,
+    /// ML service whose tokenizer measures the passages
+    ml_service: Arc<MLService>,
+}
+
+impl ContextService {
+    /// Create a new context service
+    pub fn new(search_service: Arc<SearchService>, ml_service: Arc<MLService>) -> Self {
+        Self {
+            search_service,
+            ml_service,
+        }
+    }
+
+    /// Search and pack the results into at most `budget` tokens
+    pub async fn assemble(&self, request: SearchRequest, budget: usize, packing: Packing) -> SearchResult<AssembledContext> {
+        let results = self.search_service.semantic_search(request).await?;
+        assemble_context(&results, budget, packing, |text| self.ml_service.count_tokens(text))
+    }
+
+    /// Build a context block for a downstream prompt
+    #[instrument(skip(self, request), fields(max_tokens = request.max_tokens))]
+    pub async fn build_context(&self, request: ContextRequest) -> SearchResult<ContextResponse> {
+        request.validate().map_err(SearchError::InvalidRequest)?;
+
+        let context = self
+            .assemble(request.to_search_request(), request.max_tokens as usize, Packing::Compact)
+            .await?;
+
+        info!(
+            "Built context of {} tokens from {} passages",
+            context.tokens,
+            context.passages.len()
+        );
+
+        Ok(ContextResponse {
+            context: context.text(),
+            tokens: context.tokens as u32,
+            passages: context.sources(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_fixtures::search_response;
+
+    fn result(post_id: &str, snippet: &str) -> SearchResponse {
+        SearchResponse {
+            snippet: snippet.to_string(),
+            ..search_response(post_id, 0.9)
+        }
+    }
+
+    /// Whitespace-separated words stand in for model tokens
+    fn count_words(text: &str) -> SearchResult<usize> {
+        Ok(text.split_whitespace().count())
+    }
+
+    #[test]
+    fn test_passages_are_numbered_and_rendered() {
+        let results = vec![result("a", "Ownership moves values."), result("b", "Borrowing lends them.")];
+        let context = assemble_context(&results, 100, Packing::RankOrder, count_words).unwrap();
+
+        assert_eq!(context.passages.len(), 2);
+        assert_eq!(context.tokens, 14);
+        assert_eq!(
+            context.text(),
+            "[1] Title a (https://example.com/a)\nOwnership moves values.\n\n\
+             [2] Title b (https://example.com/b)\nBorrowing lends them."
+        );
+
+        let citations = context.citations();
+        assert_eq!(citations[1].index, 2);
+        assert_eq!(citations[1].post_id, "b");
+        assert_eq!(citations[1].url, "https://example.com/b");
+    }
+
+    #[test]
+    fn test_budget_skips_results_that_do_not_fit() {
+        let results = vec![
+            result("a", "short one"),
+            result("b", &"long ".repeat(50)),
+            result("c", "another short one"),
+        ];
+        let context = assemble_context(&results, 20, Packing::RankOrder, count_words).unwrap();
+
+        let post_ids: Vec<&str> = context.passages.iter().map(|p| p.post_id.as_str()).collect();
+        assert_eq!(post_ids, vec!["a", "c"]);
+        assert_eq!(context.passages[1].index, 2);
+        assert!(context.tokens <= 20);
+    }
+
+    #[test]
+    fn test_empty_snippets_and_tiny_budgets() {
+        let results = vec![result("a", "   "), result("b", "text")];
+        let context = assemble_context(&results, 100, Packing::RankOrder, count_words).unwrap();
+        assert_eq!(context.passages.len(), 1);
+        assert_eq!(context.passages[0].index, 1);
+
+        assert!(assemble_context(&results, 1, Packing::RankOrder, count_words).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_passages_are_deduplicated() {
+        let results = vec![
+            result("a", "The borrow checker rejects aliasing mutable references."),
+            result("a", "Another passage of the same post."),
+            // Syndicated copy of the first post with one word changed
+            result("b", "The borrow checker rejects aliased mutable references."),
+            result("c", "Lifetimes describe how long references stay valid."),
+        ];
+        let context = assemble_context(&results, 100, Packing::Compact, count_words).unwrap();
+
+        let post_ids: Vec<&str> = context.passages.iter().map(|p| p.post_id.as_str()).collect();
+        assert_eq!(post_ids, vec!["a", "c"]);
+        assert_eq!(context.passages[1].index, 2);
+
+        // Rank-order packing keeps every result that fits
+        let context = assemble_context(&results, 100, Packing::RankOrder, count_words).unwrap();
+        assert_eq!(context.passages.len(), 4);
+    }
+
+    #[test]
+    fn test_last_passage_is_trimmed_to_fit() {
+        let long = (0..60).map(|i| format!("word{}", i)).collect::<Vec<_>>().join(" ");
+        let results = vec![result("a", "short one"), result("b", &long)];
+        let context = assemble_context(&results, 30, Packing::Compact, count_words).unwrap();
+
+        assert_eq!(context.passages.len(), 2);
+        let trimmed = &context.passages[1];
+        assert!(trimmed.truncated);
+        assert!(trimmed.text.starts_with("word0 word1"));
+        assert!(trimmed.text.ends_with(TRUNCATION_MARKER));
+        assert_eq!(context.tokens, 30);
+        assert!(!context.passages[0].truncated);
+
+        // Rank-order packing skips the passage instead
+        let context = assemble_context(&results, 30, Packing::RankOrder, count_words).unwrap();
+        assert_eq!(context.passages.len(), 1);
+    }
+
+    #[test]
+    fn test_sources_point_into_context_text() {
+        let results = vec![result("a", "Ownership moves values."), result("b", "Borrowing lends them.")];
+        let context = assemble_context(&results, 100, Packing::Compact, count_words).unwrap();
+        let text: Vec<char> = context.text().chars().collect();
+
+        let sources = context.sources();
+        assert_eq!(sources.len(), 2);
+        for (source, passage) in sources.iter().zip(&context.passages) {
+            let rendered: String = text[source.start as usize..source.end as usize].iter().collect();
+            assert_eq!(rendered, passage.render());
+        }
+        assert_eq!(sources[1].start, sources[0].end + 2);
+        assert_eq!(sources[1].url, "https://example.com/b");
+    }
+}
@@ -0,0 +1,452 @@
+//! Pluggable answer generation backends
+//!
+//! `AnswerService` only depends on the `Generator` trait. The OpenAI-compatible
+//! backend talks to any `/chat/completions` API (OpenAI, vLLM, Ollama, ...);
+//! the stub answers deterministically without a model, for tests and local
+//! development.
+
+use crate::config::GeneratorConfig;
+use crate::error::{SearchError, SearchResult};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use serde_json::{json, Value};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::warn;
+
+/// Answer text as it is generated, one piece at a time
+pub type TokenStream = Pin<Box<dyn Stream<Item = SearchResult<String>> + Send>>;
+
+/// Instructions given to the model with every prompt
+pub const SYSTEM_PROMPT: &str = "Answer the question using only the numbered context passages. \
+Cite the passages you use by their number in square brackets, such as [1]. \
+If the context does not contain the answer, say that you do not know.";
+
+/// Longest error body quoted from a failed generator response
+const MAX_ERROR_BODY_CHARS: usize = 200;
+
+/// Longest wait for the next piece of a streamed answer
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Question and context handed to a generator
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationPrompt {
+    /// User question
+    pub question: String,
+    /// Numbered context passages
+    pub context: String,
+    /// Number of passages in the context
+    pub source_count: usize,
+    /// Maximum answer length in tokens
+    pub max_tokens: u32,
+}
+
+impl GenerationPrompt {
+    /// User message combining the context and the question
+    pub fn user_message(&self) -> String {
+        format!("Context:\n{}\n\nQuestion: {}", self.context, self.question)
+    }
+}
+
+/// Text generation model answering questions from a context
+#[async_trait]
+pub trait Generator: Send + Sync {
+    /// Generate the complete answer
+    async fn generate(&self, prompt: &GenerationPrompt) -> SearchResult<String>;
+
+    /// Generate the answer as a stream of text pieces
+    async fn generate_stream(&self, prompt: &GenerationPrompt) -> SearchResult<TokenStream>;
+
+    /// Stable identifier of the underlying model
+    fn model_id(&self) -> &str;
+}
+
+/// Build the configured generator; `None` when answers are disabled
+pub fn build_generator(config: &GeneratorConfig) -> SearchResult<Option<Arc<dyn Generator>>> {
+    match config.backend.as_str() {
+        "none" => Ok(None),
+        "stub" => Ok(Some(Arc::new(StubGenerator))),
+        "openai" => Ok(Some(Arc::new(OpenAiGenerator::new(config)?))),
+        other => Err(SearchError::ConfigError(format!("Unsupported GENERATOR_BACKEND '{}'", other))),
+    }
+}
+
+/// Generator backed by an OpenAI-compatible chat completions API
+pub struct OpenAiGenerator {
+    client: reqwest::Client,
+    /// Full `/chat/completions` URL
+    endpoint: String,
+    /// Bearer token, if the API needs one
+    api_key: Option<String>,
+    /// Model name passed to the API
+    model: String,
+    /// Limit for a complete generation, streamed or not
+    timeout: Duration,
+}
+
+impl OpenAiGenerator {
+    /// Create a generator for the configured API
+    pub fn new(config: &GeneratorConfig) -> SearchResult<Self> {
+        let timeout = Duration::from_millis(config.timeout_ms);
+
+        // The client only bounds connecting: complete generations set a
+        // request timeout and streams enforce their own deadline
+        let client = reqwest::Client::builder()
+            .connect_timeout(timeout)
+            .build()
+            .map_err(|e| SearchError::ConfigError(format!("Failed to create generator HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            endpoint: format!("{}/chat/completions", config.base_url.trim_end_matches('/')),
+            api_key: config.api_key.clone(),
+            model: config.model.clone(),
+            timeout,
+        })
+    }
+
+    /// Chat completion request body
+    fn request_body(&self, prompt: &GenerationPrompt, stream: bool) -> Value {
+        json!({
+            "model": self.model,
+            "messages": [
+                { "role": "system", "content": SYSTEM_PROMPT },
+                { "role": "user", "content": prompt.user_message() },
+            ],
+            "max_tokens": prompt.max_tokens,
+            "temperature": 0.0,
+            "stream": stream,
+        })
+    }
+
+    /// Send a completion request and check the response status
+    async fn send(&self, prompt: &GenerationPrompt, stream: bool) -> SearchResult<reqwest::Response> {
+        let mut request = self.client.post(&self.endpoint).json(&self.request_body(prompt, stream));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        if !stream {
+            request = request.timeout(self.timeout);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SearchError::ModelError(format!("Generator request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let body: String = body.chars().take(MAX_ERROR_BODY_CHARS).collect();
+            return Err(SearchError::ModelError(format!("Generator returned {}: {}", status, body)));
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Generator for OpenAiGenerator {
+    async fn generate(&self, prompt: &GenerationPrompt) -> SearchResult<String> {
+        let body: Value = self
+            .send(prompt, false)
+            .await?
+            .json()
+            .await
+            .map_err(|e| SearchError::ModelError(format!("Invalid generator response: {}", e)))?;
+
+        parse_completion(&body)
+    }
+
+    async fn generate_stream(&self, prompt: &GenerationPrompt) -> SearchResult<TokenStream> {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        // Streaming requests carry no client timeout, so waiting for the
+        // response headers is bounded by the same deadline
+        let response = tokio::time::timeout_at(deadline, self.send(prompt, true))
+            .await
+            .map_err(|_| SearchError::ModelError("Generator did not respond in time".to_string()))??;
+        let mut bytes = response.bytes_stream();
+        let (tx, rx) = tokio::sync::mpsc::channel(64);
+
+        // Server-sent events arrive in arbitrary chunks; split them into lines
+        // on raw bytes so multibyte characters are never cut in half
+        tokio::spawn(async move {
+            let mut buffer = Vec::new();
+
+            loop {
+                // A stalled backend must not hold the connection open: bound
+                // both the gap between pieces and the whole generation
+                let idle_limit = STREAM_IDLE_TIMEOUT.min(deadline.saturating_duration_since(tokio::time::Instant::now()));
+                let chunk = match tokio::time::timeout(idle_limit, bytes.next()).await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => return,
+                    Err(_) => {
+                        let reason = if tokio::time::Instant::now() >= deadline {
+                            "did not finish in time"
+                        } else {
+                            "stalled"
+                        };
+                        warn!("Generator stream {}", reason);
+                        let _ = tx.send(Err(SearchError::ModelError(format!("Generator stream {}", reason)))).await;
+                        return;
+                    }
+                };
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        let _ = tx.send(Err(SearchError::ModelError(format!("Generator stream failed: {}", e)))).await;
+                        return;
+                    }
+                };
+                buffer.extend_from_slice(&chunk);
+
+                while let Some(newline) = buffer.iter().position(|&byte| byte == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=newline).collect();
+                    match parse_stream_line(&String::from_utf8_lossy(&line)) {
+                        Ok(Some(StreamLine::Token(token))) => {
+                            if tx.send(Ok(token)).await.is_err() {
+                                // Client went away; dropping the response closes the connection
+                                return;
+                            }
+                        }
+                        Ok(Some(StreamLine::Done)) => return,
+                        Ok(None) => {}
+                        Err(e) => {
+                            warn!("Malformed generator stream event: {}", e);
+                            let _ = tx.send(Err(e)).await;
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+}
+
+/// Answer text of a complete chat completion
+fn parse_completion(body: &Value) -> SearchResult<String> {
+    body["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| SearchError::ModelError("Generator response has no answer content".to_string()))
+}
+
+/// Meaningful line of a streamed chat completion
+#[derive(Debug, PartialEq)]
+enum StreamLine {
+    /// Next piece of the answer
+    Token(String),
+    /// End of the stream
+    Done,
+}
+
+/// Parse one server-sent event line of a streamed chat completion
+///
+/// Blank lines, comments and events without answer text yield `None`.
+fn parse_stream_line(line: &str) -> SearchResult<Option<StreamLine>> {
+    let data = match line.trim().strip_prefix("data:") {
+        Some(data) => data.trim(),
+        None => return Ok(None),
+    };
+
+    if data == "[DONE]" {
+        return Ok(Some(StreamLine::Done));
+    }
+
+    let event: Value = serde_json::from_str(data)
+        .map_err(|e| SearchError::ModelError(format!("Invalid generator stream event: {}", e)))?;
+
+    Ok(event["choices"][0]["delta"]["content"]
+        .as_str()
+        .filter(|token| !token.is_empty())
+        .map(|token| StreamLine::Token(token.to_string())))
+}
+
+/// Deterministic generator that needs no model
+///
+/// Answers with a fixed sentence citing every context passage, so tests and
+/// local setups can exercise the answer pipeline end to end.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StubGenerator;
+
+impl StubGenerator {
+    fn answer(prompt: &GenerationPrompt) -> String {
+        let citations: Vec<String> = (1..=prompt.source_count).map(|index| format!("[{}]", index)).collect();
+        format!("Stub answer to \"{}\" based on {}.", prompt.question, citations.join(" "))
+    }
+}
+
+#[async_trait]
+impl Generator for StubGenerator {
+    async fn generate(&self, prompt: &GenerationPrompt) -> SearchResult<String> {
+        Ok(Self::answer(prompt))
+    }
+
+    async fn generate_stream(&self, prompt: &GenerationPrompt) -> SearchResult<TokenStream> {
+        let tokens: Vec<SearchResult<String>> = Self::answer(prompt)
+            .split_inclusive(' ')
+            .map(|token| Ok(token.to_string()))
+            .collect();
+        Ok(Box::pin(futures::stream::iter(tokens)))
+    }
+
+    fn model_id(&self) -> &str {
+        "stub"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(source_count: usize) -> GenerationPrompt {
+        GenerationPrompt {
+            question: "What is ownership?".to_string(),
+            context: "[1] Ownership (https://example.com/1)\nEach value has one owner.".to_string(),
+            source_count,
+            max_tokens: 128,
+        }
+    }
+
+    fn openai_config() -> GeneratorConfig {
+        GeneratorConfig {
+            backend: "openai".to_string(),
+            base_url: "http://localhost:8000/v1/".to_string(),
+            api_key: None,
+            model: "local-model".to_string(),
+            timeout_ms: 1000,
+            max_context_tokens: 2000,
+            max_answer_tokens: 512,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stub_generator_is_deterministic() {
+        let generator = StubGenerator;
+        let answer = generator.generate(&prompt(2)).await.unwrap();
+        assert_eq!(answer, "Stub answer to \"What is ownership?\" based on [1] [2].");
+
+        let streamed: Vec<String> = generator
+            .generate_stream(&prompt(2))
+            .await
+            .unwrap()
+            .map(|token| token.unwrap())
+            .collect()
+            .await;
+        assert!(streamed.len() > 1);
+        assert_eq!(streamed.concat(), answer);
+    }
+
+    #[test]
+    fn test_openai_request_body() {
+        let generator = OpenAiGenerator::new(&openai_config()).unwrap();
+        assert_eq!(generator.endpoint, "http://localhost:8000/v1/chat/completions");
+        assert_eq!(generator.model_id(), "local-model");
+
+        let body = generator.request_body(&prompt(1), true);
+        assert_eq!(body["model"], "local-model");
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["max_tokens"], 128);
+        assert_eq!(body["messages"][0]["content"], SYSTEM_PROMPT);
+        assert!(body["messages"][1]["content"]
+            .as_str()
+            .unwrap()
+            .ends_with("Question: What is ownership?"));
+    }
+
+    #[tokio::test]
+    async fn test_stalled_stream_ends_with_error() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Backend that sends one token and then never finishes the response
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = socket.read(&mut request).await;
+
+            let event = "data: {\"choices\":[{\"delta\":{\"content\":\"Own\"}}]}\n\n";
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\n\r\n{:x}\r\n{}\r\n",
+                event.len(),
+                event
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+
+        let mut config = openai_config();
+        config.base_url = format!("http://{}/v1", address);
+        config.timeout_ms = 200;
+        let generator = OpenAiGenerator::new(&config).unwrap();
+
+        let pieces: Vec<SearchResult<String>> = generator.generate_stream(&prompt(1)).await.unwrap().collect().await;
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[0].as_ref().unwrap(), "Own");
+        assert!(pieces[1].as_ref().unwrap_err().is_model_error());
+    }
+
+    #[tokio::test]
+    async fn test_silent_backend_times_out() {
+        // Backend that accepts the connection but never sends headers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+
+        let mut config = openai_config();
+        config.base_url = format!("http://{}/v1", address);
+        config.timeout_ms = 200;
+        let generator = OpenAiGenerator::new(&config).unwrap();
+
+        let result = tokio::time::timeout(Duration::from_secs(5), generator.generate_stream(&prompt(1))).await;
+        assert!(matches!(result, Ok(Err(e)) if e.is_model_error()));
+    }
+
+    #[test]
+    fn test_parse_completion() {
+        let body = json!({ "choices": [{ "message": { "role": "assistant", "content": "Owners drop values [1]." } }] });
+        assert_eq!(parse_completion(&body).unwrap(), "Owners drop values [1].");
+        assert!(parse_completion(&json!({ "choices": [] })).unwrap_err().is_model_error());
+    }
+
+    #[test]
+    fn test_parse_stream_line() {
+        let token = r#"data: {"choices":[{"delta":{"content":"Own"}}]}"#;
+        assert_eq!(parse_stream_line(token).unwrap(), Some(StreamLine::Token("Own".to_string())));
+        assert_eq!(parse_stream_line("data: [DONE]\n").unwrap(), Some(StreamLine::Done));
+
+        // Role-only deltas, blank lines and comments carry no text
+        assert_eq!(parse_stream_line(r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#).unwrap(), None);
+        assert_eq!(parse_stream_line("").unwrap(), None);
+        assert_eq!(parse_stream_line(": keep-alive").unwrap(), None);
+
+        assert!(parse_stream_line("data: {not json").is_err());
+    }
+
+    #[test]
+    fn test_build_generator() {
+        let mut config = openai_config();
+        assert_eq!(build_generator(&config).unwrap().unwrap().model_id(), "local-model");
+
+        config.backend = "stub".to_string();
+        assert_eq!(build_generator(&config).unwrap().unwrap().model_id(), "stub");
+
+        config.backend = "none".to_string();
+        assert!(build_generator(&config).unwrap().is_none());
+
+        config.backend = "unknown".to_string();
+        assert!(build_generator(&config).is_err());
+    }
+}
@@ -0,0 +1,15 @@
+//! Retrieval-augmented generation on top of semantic search
+//!
+//! This module contains:
+//! - Context assembly of search results into a numbered, deduplicated, token-budgeted block
+//! - ContextService serving context blocks for callers bringing their own model
+//! - Generator trait with an OpenAI-compatible HTTP backend and a deterministic stub
+//! - AnswerService answering questions from search results with citations
+
+pub mod context;
+pub mod generator;
+pub mod answer;
+
+pub use context::{assemble_context, AssembledContext, ContextPassage, ContextService, Packing};
+pub use generator::{build_generator, GenerationPrompt, Generator, OpenAiGenerator, StubGenerator, TokenStream};
+pub use answer::{AnswerService, AnswerStream, NO_CONTEXT_ANSWER};
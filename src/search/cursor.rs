@@ -0,0 +1,171 @@
+//! Signed pagination cursors for search results
+//!
+//! A cursor records where the previous page of a ranked list ended: the query
+//! fingerprint, how many results were already returned, and the score and
+//! post_id of the last one. The next page is the same ranking continued after
+//! that position, so results shifting between requests never repeat on later
+//! pages. Reranked and diversified lists are not ordered by score, so their
+//! pages continue at the recorded position instead. Cursors are HMAC-SHA256
+//! signed so clients cannot forge positions or replay a cursor against a
+//! different query.
+
+use crate::error::{SearchError, SearchResult};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Deepest position in a ranked list that can be paged to
+pub const MAX_SEARCH_DEPTH: usize = 500;
+
+/// Maximum length of an encoded cursor
+pub const MAX_CURSOR_LENGTH: usize = 1024;
+
+/// Layout version of the cursor payload
+const CURSOR_VERSION: u8 = 1;
+
+/// Bytes of the HMAC tag kept in the cursor
+const TAG_LENGTH: usize = 16;
+
+/// Version, fingerprint, offset and score bytes preceding the post_id
+const HEADER_LENGTH: usize = 1 + 8 + 4 + 4;
+
+/// Position after the last result of a page
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchCursor {
+    /// Fingerprint of the query and search parameters
+    pub fingerprint: u64,
+    /// Number of results returned on previous pages
+    pub offset: u32,
+    /// Score of the last returned result
+    pub last_score: f32,
+    /// Post identifier of the last returned result
+    pub last_post_id: String,
+}
+
+impl SearchCursor {
+    /// Whether a result ranks after the cursor (score descending, then post_id)
+    pub fn ranks_after(&self, score: f32, post_id: &str) -> bool {
+        score < self.last_score || (score == self.last_score && post_id > self.last_post_id.as_str())
+    }
+}
+
+/// Signs and verifies cursors with a server-side secret
+pub struct CursorCodec {
+    /// HMAC keyed with the secret, cloned for every cursor
+    mac: HmacSha256,
+}
+
+impl CursorCodec {
+    /// Create a codec with the given secret
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            mac: HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length"),
+        }
+    }
+
+    /// Create a codec with a random secret; its cursors only work on this process
+    pub fn random() -> Self {
+        Self::new(&rand::random::<[u8; 32]>())
+    }
+
+    /// Encode and sign a cursor
+    pub fn encode(&self, cursor: &SearchCursor) -> String {
+        let mut payload = Vec::with_capacity(HEADER_LENGTH + cursor.last_post_id.len() + TAG_LENGTH);
+        payload.push(CURSOR_VERSION);
+        payload.extend_from_slice(&cursor.fingerprint.to_be_bytes());
+        payload.extend_from_slice(&cursor.offset.to_be_bytes());
+        payload.extend_from_slice(&cursor.last_score.to_be_bytes());
+        payload.extend_from_slice(cursor.last_post_id.as_bytes());
+
+        let mut mac = self.mac.clone();
+        mac.update(&payload);
+        let tag = mac.finalize().into_bytes();
+        payload.extend_from_slice(&tag[..TAG_LENGTH]);
+
+        hex::encode(payload)
+    }
+
+    /// Verify and decode a cursor produced by `encode`
+    pub fn decode(&self, token: &str) -> SearchResult<SearchCursor> {
+        let invalid = || SearchError::InvalidRequest("Invalid or tampered cursor".to_string());
+
+        if token.len() > MAX_CURSOR_LENGTH {
+            return Err(invalid());
+        }
+
+        let bytes = hex::decode(token).map_err(|_| invalid())?;
+        if bytes.len() < HEADER_LENGTH + TAG_LENGTH {
+            return Err(invalid());
+        }
+
+        // The tag is the leftmost TAG_LENGTH bytes of the HMAC, compared in constant time
+        let (payload, tag) = bytes.split_at(bytes.len() - TAG_LENGTH);
+        let mut mac = self.mac.clone();
+        mac.update(payload);
+        if mac.verify_truncated_left(tag).is_err() || payload[0] != CURSOR_VERSION {
+            return Err(invalid());
+        }
+
+        let fingerprint = u64::from_be_bytes(payload[1..9].try_into().map_err(|_| invalid())?);
+        let offset = u32::from_be_bytes(payload[9..13].try_into().map_err(|_| invalid())?);
+        let last_score = f32::from_be_bytes(payload[13..17].try_into().map_err(|_| invalid())?);
+        let last_post_id = String::from_utf8(payload[HEADER_LENGTH..].to_vec()).map_err(|_| invalid())?;
+
+        Ok(SearchCursor {
+            fingerprint,
+            offset,
+            last_score,
+            last_post_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor() -> SearchCursor {
+        SearchCursor {
+            fingerprint: 0xdead_beef,
+            offset: 20,
+            last_score: 0.75,
+            last_post_id: "post_42".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let codec = CursorCodec::new(b"test-secret");
+        let token = codec.encode(&cursor());
+        assert_eq!(codec.decode(&token).unwrap(), cursor());
+    }
+
+    #[test]
+    fn test_cursor_rejects_tampering() {
+        let codec = CursorCodec::new(b"test-secret");
+        let token = codec.encode(&cursor());
+
+        // Flip one payload nibble
+        let mut tampered: Vec<char> = token.chars().collect();
+        tampered[4] = if tampered[4] == '0' { '1' } else { '0' };
+        let tampered: String = tampered.into_iter().collect();
+        assert!(matches!(codec.decode(&tampered), Err(SearchError::InvalidRequest(_))));
+
+        // Signed with another secret
+        assert!(CursorCodec::new(b"other-secret").decode(&token).is_err());
+
+        assert!(codec.decode("not-hex").is_err());
+        assert!(codec.decode("00").is_err());
+    }
+
+    #[test]
+    fn test_ranks_after() {
+        let cursor = cursor();
+        assert!(cursor.ranks_after(0.5, "post_1"));
+        assert!(cursor.ranks_after(0.75, "post_5"));
+        assert!(!cursor.ranks_after(0.75, "post_42"));
+        assert!(!cursor.ranks_after(0.75, "post_1"));
+        assert!(!cursor.ranks_after(0.9, "post_99"));
+    }
+}
@@ -0,0 +1,135 @@
+//! Result diversification with maximal marginal relevance (MMR)
+//!
+//! Syndicated and near-duplicate posts embed almost identically, so a plain
+//! relevance ranking can fill a page with copies of one article. MMR picks
+//! results greedily, trading each candidate's relevance against its
+//! similarity to the results already picked. "More like this" searches
+//! instead drop the near-duplicates of their seed post outright.
+
+use crate::cache::cosine_similarity;
+use crate::types::{SearchCandidate, SearchResponse};
+use std::collections::HashMap;
+
+/// Reorder results by maximal marginal relevance and keep the first `limit`
+///
+/// Each step picks the result maximizing
+/// `lambda * score - (1 - lambda) * max_similarity_to_picked`, so `lambda = 1`
+/// keeps the relevance order and `lambda = 0` only maximizes diversity.
+/// Results should arrive ranked; ties go to the earlier one. A result without
+/// an embedding is treated as dissimilar to everything.
+pub fn maximal_marginal_relevance(
+    results: Vec<SearchResponse>,
+    embeddings: &HashMap<String, Vec<f32>>,
+    lambda: f32,
+    limit: usize,
+) -> Vec<SearchResponse> {
+    let lambda = lambda.clamp(0.0, 1.0);
+    let mut remaining: Vec<(SearchResponse, f32)> = results.into_iter().map(|result| (result, 0.0)).collect();
+    let mut selected = Vec::with_capacity(limit.min(remaining.len()));
+
+    while selected.len() < limit && !remaining.is_empty() {
+        let mut best = 0;
+        let mut best_score = f32::NEG_INFINITY;
+        for (i, (result, max_similarity)) in remaining.iter().enumerate() {
+            let mmr = lambda * result.score - (1.0 - lambda) * max_similarity;
+            if mmr > best_score {
+                best = i;
+                best_score = mmr;
+            }
+        }
+
+        let (picked, _) = remaining.remove(best);
+
+        // Only the newly picked result can raise a candidate's maximum similarity
+        if let Some(picked_embedding) = embeddings.get(&picked.post_id) {
+            for (result, max_similarity) in remaining.iter_mut() {
+                if let Some(embedding) = embeddings.get(&result.post_id) {
+                    *max_similarity = max_similarity.max(cosine_similarity(picked_embedding, embedding));
+                }
+            }
+        }
+
+        selected.push(picked);
+    }
+
+    selected
+}
+
+/// Cosine similarity to a seed post at or above which a result is a near-duplicate of it
+pub const NEAR_DUPLICATE_SIMILARITY: f32 = 0.95;
+
+/// Drop candidates at least `threshold` similar to the seed of their search
+///
+/// Used by "more like this" searches, where copies of the seed post would
+/// otherwise top the list. The candidates must come from a search with the
+/// seed's embedding, keeping their raw scores, which are then their cosine
+/// similarity to the seed.
+pub fn remove_near_duplicates(mut candidates: Vec<SearchCandidate>, threshold: f32) -> Vec<SearchCandidate> {
+    candidates.retain(|candidate| candidate.score < threshold);
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_fixtures::{search_candidate as candidate, search_response as result};
+    use crate::types::SearchSource;
+
+    /// "a" and its syndicated copy "a2" embed identically; "b" is unrelated
+    fn fixture() -> (Vec<SearchResponse>, HashMap<String, Vec<f32>>) {
+        let results = vec![result("a", 0.95), result("a2", 0.94), result("b", 0.80)];
+        let embeddings = HashMap::from([
+            ("a".to_string(), vec![1.0, 0.0]),
+            ("a2".to_string(), vec![1.0, 0.0]),
+            ("b".to_string(), vec![0.0, 1.0]),
+        ]);
+        (results, embeddings)
+    }
+
+    fn order(results: &[SearchResponse]) -> Vec<&str> {
+        results.iter().map(|r| r.post_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_mmr_demotes_near_duplicates() {
+        let (results, embeddings) = fixture();
+        let diversified = maximal_marginal_relevance(results, &embeddings, 0.5, 3);
+        assert_eq!(order(&diversified), vec!["a", "b", "a2"]);
+
+        // Scores are still the relevance scores
+        assert_eq!(diversified[2].score, 0.94);
+    }
+
+    #[test]
+    fn test_mmr_lambda_one_keeps_relevance_order() {
+        let (results, embeddings) = fixture();
+        let diversified = maximal_marginal_relevance(results, &embeddings, 1.0, 3);
+        assert_eq!(order(&diversified), vec!["a", "a2", "b"]);
+    }
+
+    #[test]
+    fn test_mmr_limit_and_missing_embeddings() {
+        let (results, mut embeddings) = fixture();
+        embeddings.remove("a2");
+
+        // Without an embedding "a2" is not penalized as a duplicate
+        let diversified = maximal_marginal_relevance(results, &embeddings, 0.5, 2);
+        assert_eq!(order(&diversified), vec!["a", "a2"]);
+
+        assert!(maximal_marginal_relevance(Vec::new(), &embeddings, 0.5, 5).is_empty());
+    }
+
+    #[test]
+    fn test_remove_near_duplicates_of_seed() {
+        let candidates = vec![
+            candidate("a", 0.99, SearchSource::Postgres),
+            candidate("a2", 0.95, SearchSource::Postgres),
+            candidate("b", 0.80, SearchSource::Postgres),
+        ];
+
+        // "a" and "a2" copy the seed; "b" is only similar to it
+        let kept = remove_near_duplicates(candidates, NEAR_DUPLICATE_SIMILARITY);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].post_id, "b");
+    }
+}
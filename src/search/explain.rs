@@ -0,0 +1,195 @@
+//! Score explanations for `explain: true` searches
+//!
+//! Keeps the rankings the candidates were retrieved from so each result can
+//! report its raw vector similarity, full-text score, the sources that
+//! returned it and its fusion terms. Cross-encoder scores are recorded once
+//! reranking has run.
+
+use crate::search::fusion::{fusion_ranks, rrf_contribution};
+use crate::types::{FusionExplanation, ScoreExplanation, SearchCandidate, SearchFilters, SearchMode, SearchResponse, SearchSource};
+use std::collections::{HashMap, HashSet};
+
+/// Position and score of a post in one ranking
+#[derive(Debug, Clone)]
+struct RankedHit {
+    /// 1-based rank
+    rank: usize,
+    /// Score in this ranking
+    score: f32,
+    /// Sources that returned the post for this ranking
+    sources: Vec<SearchSource>,
+}
+
+/// Builds explanations for the results of one search
+#[derive(Debug, Clone)]
+pub struct Explainer {
+    /// Degradation mode the search ran in
+    search_mode: SearchMode,
+    /// Vector ranking by post
+    vector: HashMap<String, RankedHit>,
+    /// Full-text ranking by post
+    lexical: HashMap<String, RankedHit>,
+    /// Vector weight when the two rankings were fused
+    fusion_weight: Option<f32>,
+    /// Top-level filter conditions of the request
+    filters_passed: Vec<String>,
+}
+
+impl Explainer {
+    /// Create an explainer from the rankings the candidates came from
+    ///
+    /// `fusion_weight` is set when the vector and full-text rankings were
+    /// fused; otherwise at most one of them is non-empty.
+    pub fn new(
+        vector: &[SearchCandidate],
+        lexical: &[SearchCandidate],
+        fusion_weight: Option<f32>,
+        search_mode: SearchMode,
+        filters: Option<&SearchFilters>,
+    ) -> Self {
+        Self {
+            search_mode,
+            vector: ranked_hits(vector),
+            lexical: ranked_hits(lexical),
+            fusion_weight: fusion_weight.map(|weight| weight.clamp(0.0, 1.0)),
+            filters_passed: filters
+                .map(|filters| filters.condition_names().into_iter().map(String::from).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Create an explainer for candidates that were not fused
+    ///
+    /// Full-text candidates form the full-text ranking, all others the vector ranking.
+    pub fn unfused(candidates: &[SearchCandidate], search_mode: SearchMode, filters: Option<&SearchFilters>) -> Self {
+        let (lexical, vector): (Vec<SearchCandidate>, Vec<SearchCandidate>) = candidates
+            .iter()
+            .cloned()
+            .partition(|candidate| candidate.source == SearchSource::Lexical);
+        Self::new(&vector, &lexical, None, search_mode, filters)
+    }
+
+    /// Explanation of a result scored `retrieval_score` before reranking
+    pub fn explain(&self, post_id: &str, retrieval_score: f32) -> ScoreExplanation {
+        let vector = self.vector.get(post_id);
+        let lexical = self.lexical.get(post_id);
+
+        let mut sources: Vec<SearchSource> = Vec::new();
+        for source in vector.into_iter().chain(lexical).flat_map(|hit| hit.sources.iter()) {
+            if !sources.contains(source) {
+                sources.push(source.clone());
+            }
+        }
+
+        let fusion = self.fusion_weight.map(|vector_weight| FusionExplanation {
+            vector_weight,
+            vector_rank: vector.map(|hit| hit.rank as u32),
+            lexical_rank: lexical.map(|hit| hit.rank as u32),
+            vector_contribution: vector.map_or(0.0, |hit| rrf_contribution(vector_weight, hit.rank)),
+            lexical_contribution: lexical.map_or(0.0, |hit| rrf_contribution(1.0 - vector_weight, hit.rank)),
+        });
+
+        ScoreExplanation {
+            search_mode: self.search_mode.clone(),
+            sources,
+            vector_score: vector.map(|hit| hit.score),
+            lexical_score: lexical.map(|hit| hit.score),
+            fusion,
+            retrieval_score,
+            rerank_score: None,
+            filters_passed: self.filters_passed.clone(),
+        }
+    }
+}
+
+/// Record cross-encoder scores on explained results
+///
+/// `reranked` are the results the cross-encoder scored; results outside it
+/// kept their retrieval score.
+pub fn record_rerank_scores(results: &mut [SearchResponse], reranked: &[SearchResponse]) {
+    let reranked: HashSet<&str> = reranked.iter().map(|result| result.post_id.as_str()).collect();
+
+    for result in results {
+        if !reranked.contains(result.post_id.as_str()) {
+            continue;
+        }
+        if let Some(explanation) = result.explanation.as_mut() {
+            explanation.rerank_score = Some(result.score);
+        }
+    }
+}
+
+/// Rank, score and sources of every post in a ranking, by best rank
+fn ranked_hits(candidates: &[SearchCandidate]) -> HashMap<String, RankedHit> {
+    let ranks = fusion_ranks(candidates);
+    let mut hits = HashMap::new();
+
+    for candidate in candidates {
+        hits.entry(candidate.post_id.clone()).or_insert_with(|| {
+            let mut sources = vec![candidate.source.clone()];
+            sources.extend(candidate.merged_from.iter().cloned());
+            RankedHit {
+                rank: ranks[candidate.post_id.as_str()],
+                score: candidate.score,
+                sources,
+            }
+        });
+    }
+
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::fusion::{reciprocal_rank_fusion, RRF_K};
+    use crate::types::test_fixtures::search_candidate as candidate;
+
+    #[test]
+    fn test_hybrid_explanation_matches_fused_score() {
+        let mut both = candidate("a", 0.82, SearchSource::Redis);
+        both.merged_from.push(SearchSource::Postgres);
+        let vector = vec![candidate("b", 0.91, SearchSource::Postgres), both];
+        let lexical = vec![candidate("a", 0.4, SearchSource::Lexical)];
+
+        let fused = reciprocal_rank_fusion(&vector, &lexical, 0.6);
+        let a = fused.iter().find(|c| c.post_id == "a").unwrap();
+
+        let explainer = Explainer::new(&vector, &lexical, Some(0.6), SearchMode::Full, None);
+        let explanation = explainer.explain("a", a.score);
+
+        assert_eq!(
+            explanation.sources,
+            vec![SearchSource::Redis, SearchSource::Postgres, SearchSource::Lexical]
+        );
+        assert_eq!(explanation.vector_score, Some(0.82));
+        assert_eq!(explanation.lexical_score, Some(0.4));
+
+        let fusion = explanation.fusion.unwrap();
+        assert_eq!(fusion.vector_rank, Some(2));
+        assert_eq!(fusion.lexical_rank, Some(1));
+        assert!((fusion.vector_contribution + fusion.lexical_contribution - a.score).abs() < 1e-6);
+        assert!((fusion.lexical_contribution - 0.4).abs() < 1e-6);
+        assert!((fusion.vector_contribution - 0.6 * (RRF_K + 1.0) / (RRF_K + 2.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_unfused_explanation() {
+        let candidates = vec![candidate("a", 0.7, SearchSource::Postgres)];
+        let filters = SearchFilters {
+            language: Some("en".to_string()),
+            frozen: Some(false),
+            ..Default::default()
+        };
+
+        let explainer = Explainer::unfused(&candidates, SearchMode::PostgresOnly, Some(&filters));
+        let explanation = explainer.explain("a", 0.7);
+
+        assert_eq!(explanation.search_mode, SearchMode::PostgresOnly);
+        assert_eq!(explanation.sources, vec![SearchSource::Postgres]);
+        assert_eq!(explanation.vector_score, Some(0.7));
+        assert_eq!(explanation.lexical_score, None);
+        assert!(explanation.fusion.is_none());
+        assert_eq!(explanation.filters_passed, vec!["language", "frozen"]);
+    }
+}
@@ -3,12 +3,14 @@
 /// This module implements the main search service with circuit breaker integration,
 /// automatic fallback to Postgres-only search, and graceful degradation modes.
 
-use crate::cache::CacheManager;
+use crate::cache::{prefilter_is_exact, CacheManager};
 use crate::database::DatabaseManager;
 use crate::error::{SearchError, SearchResult};
-use crate::types::{SearchCandidate, SearchMode, SearchSource};
+use crate::types::{ChunkCandidate, SearchCandidate, SearchFilters, SearchMode, SearchSource};
 use crate::search::circuit_breaker::{CircuitBreaker, CircuitBreakerStats};
 use crate::search::retry::{RetryExecutor, RetryConfig, RetryStrategy};
+use crate::search::normalization::{fusion_scores, ScoreNormalization};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, error, info, warn};
@@ -25,6 +27,8 @@ pub struct FallbackSearchService {
     retry_executor: RetryExecutor,
     /// Maximum number of candidates after merging
     max_candidates: usize,
+    /// Normalization comparing each source's scores when merging
+    score_normalization: ScoreNormalization,
 }
 
 impl FallbackSearchService {
@@ -48,6 +52,7 @@ impl FallbackSearchService {
             circuit_breaker,
             retry_executor,
             max_candidates: 130,
+            score_normalization: ScoreNormalization::None,
         }
     }
 
@@ -66,14 +71,35 @@ impl FallbackSearchService {
             circuit_breaker,
             retry_executor,
             max_candidates: 130,
+            score_normalization: ScoreNormalization::None,
         }
     }
 
+    /// Compare each source's scores with the given normalization when merging
+    ///
+    /// Passage hits are aggregated per post from their raw scores, so only
+    /// post-level merging is affected.
+    pub fn with_score_normalization(mut self, score_normalization: ScoreNormalization) -> Self {
+        self.score_normalization = score_normalization;
+        self
+    }
+
+    /// Normalization comparing each source's scores when merging
+    pub fn score_normalization(&self) -> ScoreNormalization {
+        self.score_normalization
+    }
+
     /// Perform search with automatic fallback and circuit breaker logic
+    ///
+    /// Filters are pushed down into both the Redis KNN pre-filter and the
+    /// Postgres query. Filters the pre-filter can only approximate are served
+    /// by Postgres alone, with Redis as the fallback when Postgres fails;
+    /// relaxed Redis candidates then need the caller's post-filter.
     pub async fn search_with_fallback(
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
     ) -> SearchResult<(Vec<SearchCandidate>, SearchMode)> {
         debug!("Starting search with fallback logic, limit: {}", limit);
 
@@ -82,23 +108,142 @@ impl FallbackSearchService {
         debug!("Determined search mode: {:?}", search_mode);
 
         match search_mode {
+            // Relaxed Redis hits would crowd out Postgres' exact matches when merged
+            SearchMode::Full | SearchMode::Degraded if !prefilter_is_exact(filters) => {
+                match self.postgres_only_search_with_retry(query_vector, limit, filters).await {
+                    Ok(result) => Ok(result),
+                    Err(e) => {
+                        warn!("Postgres search failed, falling back to the relaxed Redis pre-filter: {}", e);
+                        self.cache_only_search_with_retry(query_vector, limit, filters).await
+                    }
+                }
+            }
             SearchMode::Full => {
-                self.full_search_with_retry(query_vector, limit).await
+                self.full_search_with_retry(query_vector, limit, filters).await
             }
             SearchMode::PostgresOnly => {
-                self.postgres_only_search_with_retry(query_vector, limit).await
+                self.postgres_only_search_with_retry(query_vector, limit, filters).await
             }
             SearchMode::CacheOnly => {
-                self.cache_only_search_with_retry(query_vector, limit).await
+                self.cache_only_search_with_retry(query_vector, limit, filters).await
             }
             SearchMode::Degraded => {
                 // For now, degraded mode is same as full but without reranking
                 // Reranking logic will be implemented in a later task
-                self.full_search_with_retry(query_vector, limit).await
+                self.full_search_with_retry(query_vector, limit, filters).await
             }
         }
     }
 
+    /// Perform passage-level search with the same source selection as `search_with_fallback`
+    ///
+    /// Returns every passage hit, several per post; aggregating them back to
+    /// posts is left to the caller. A source that fails is skipped as long as
+    /// another one answered.
+    pub async fn passage_search_with_fallback(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<(Vec<ChunkCandidate>, SearchMode)> {
+        debug!("Starting passage search with fallback logic, limit: {}", limit);
+
+        let search_mode = self.determine_search_mode().await;
+        let (use_redis, use_postgres) = match search_mode {
+            SearchMode::PostgresOnly => (false, true),
+            SearchMode::CacheOnly => (true, false),
+            // Relaxed Redis hits would crowd out Postgres' exact matches
+            SearchMode::Full | SearchMode::Degraded => (prefilter_is_exact(filters), true),
+        };
+
+        let query_vector = query_vector.to_vec();
+        let filters = filters.cloned();
+
+        let hits = self.retry_executor.execute(|| {
+            let query_vector = query_vector.clone();
+            let filters = filters.clone();
+
+            async move {
+                self.execute_passage_search(&query_vector, limit, filters.as_ref(), use_redis, use_postgres).await
+            }
+        }).await.map_err(|e| {
+            error!("Passage search failed after retries: {}", e);
+            e
+        })?;
+
+        Ok((hits, search_mode))
+    }
+
+    /// Execute passage search on the selected sources in parallel
+    async fn execute_passage_search(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        filters: Option<&SearchFilters>,
+        use_redis: bool,
+        use_postgres: bool,
+    ) -> SearchResult<Vec<ChunkCandidate>> {
+        let redis_search = async {
+            if !use_redis {
+                return None;
+            }
+            let result = timeout(Duration::from_millis(400), self.cache_manager.chunk_search(query_vector, limit, filters))
+                .await
+                .unwrap_or_else(|_| Err(SearchError::RedisError("Redis passage search timeout".to_string())));
+            Some(result)
+        };
+        let postgres_search = async {
+            if !use_postgres {
+                return None;
+            }
+            let result = timeout(Duration::from_millis(500), self.database_manager.chunk_search(query_vector, limit, filters))
+                .await
+                .unwrap_or_else(|_| Err(SearchError::DatabaseError("Postgres passage search timeout".to_string())));
+            Some(result)
+        };
+
+        let (redis_result, postgres_result) = tokio::join!(redis_search, postgres_search);
+
+        let mut hits = Vec::new();
+        let mut answered = false;
+        let mut last_error = None;
+
+        match redis_result {
+            Some(Ok(candidates)) => {
+                debug!("Redis passage search succeeded: {} hits", candidates.len());
+                self.circuit_breaker.record_redis_success().await;
+                hits.extend(candidates);
+                answered = true;
+            }
+            Some(Err(e)) => {
+                warn!("Redis passage search failed: {}", e);
+                self.circuit_breaker.record_redis_failure().await;
+                last_error = Some(e);
+            }
+            None => {}
+        }
+
+        match postgres_result {
+            Some(Ok(candidates)) => {
+                debug!("Postgres passage search succeeded: {} hits", candidates.len());
+                self.circuit_breaker.record_postgres_success().await;
+                hits.extend(candidates);
+                answered = true;
+            }
+            Some(Err(e)) => {
+                warn!("Postgres passage search failed: {}", e);
+                self.circuit_breaker.record_postgres_failure().await;
+                last_error = Some(e);
+            }
+            None => {}
+        }
+
+        match last_error {
+            Some(e) if !answered => Err(e),
+            _ => Ok(hits),
+        }
+    }
+
     /// Determine the appropriate search mode based on system state
     async fn determine_search_mode(&self) -> SearchMode {
         // Check if Redis circuit is open
@@ -125,20 +270,23 @@ impl FallbackSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
     ) -> SearchResult<(Vec<SearchCandidate>, SearchMode)> {
         let circuit_breaker = self.circuit_breaker.clone();
         let cache_manager = self.cache_manager.clone();
         let database_manager = self.database_manager.clone();
         let query_vector = query_vector.to_vec();
+        let filters = filters.cloned();
 
         let result = self.retry_executor.execute(|| {
             let circuit_breaker = circuit_breaker.clone();
             let cache_manager = cache_manager.clone();
             let database_manager = database_manager.clone();
             let query_vector = query_vector.clone();
+            let filters = filters.clone();
 
             async move {
-                self.execute_full_search(&query_vector, limit, &cache_manager, &database_manager, &circuit_breaker).await
+                self.execute_full_search(&query_vector, limit, filters.as_ref(), &cache_manager, &database_manager, &circuit_breaker).await
             }
         }).await;
 
@@ -148,7 +296,7 @@ impl FallbackSearchService {
                 error!("Full search failed after retries: {}", e);
                 // Try fallback to Postgres-only
                 warn!("Attempting fallback to Postgres-only search");
-                self.postgres_only_search_with_retry(&query_vector, limit).await
+                self.postgres_only_search_with_retry(&query_vector, limit, filters.as_ref()).await
             }
         }
     }
@@ -158,6 +306,7 @@ impl FallbackSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
         cache_manager: &CacheManager,
         database_manager: &DatabaseManager,
         circuit_breaker: &CircuitBreaker,
@@ -166,8 +315,8 @@ impl FallbackSearchService {
 
         // Launch both searches in parallel
         let (redis_result, postgres_result) = tokio::join!(
-            self.redis_search_with_timeout(query_vector, 100, cache_manager),
-            self.postgres_search_with_timeout(query_vector, 100, database_manager)
+            self.redis_search_with_timeout(query_vector, 100, filters, cache_manager),
+            self.postgres_search_with_timeout(query_vector, 100, filters, database_manager)
         );
 
         // Process Redis result
@@ -231,18 +380,21 @@ impl FallbackSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
     ) -> SearchResult<(Vec<SearchCandidate>, SearchMode)> {
         let database_manager = self.database_manager.clone();
         let circuit_breaker = self.circuit_breaker.clone();
         let query_vector = query_vector.to_vec();
+        let filters = filters.cloned();
 
         let result = self.retry_executor.execute(|| {
             let database_manager = database_manager.clone();
             let circuit_breaker = circuit_breaker.clone();
             let query_vector = query_vector.clone();
+            let filters = filters.clone();
 
             async move {
-                self.execute_postgres_only_search(&query_vector, limit, &database_manager, &circuit_breaker).await
+                self.execute_postgres_only_search(&query_vector, limit, filters.as_ref(), &database_manager, &circuit_breaker).await
             }
         }).await;
 
@@ -260,12 +412,13 @@ impl FallbackSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
         database_manager: &DatabaseManager,
         circuit_breaker: &CircuitBreaker,
     ) -> SearchResult<Vec<SearchCandidate>> {
         debug!("Executing Postgres-only search");
 
-        match self.postgres_search_with_timeout(query_vector, limit, database_manager).await {
+        match self.postgres_search_with_timeout(query_vector, limit, filters, database_manager).await {
             Ok(candidates) => {
                 debug!("Postgres-only search succeeded: {} candidates", candidates.len());
                 circuit_breaker.record_postgres_success().await;
@@ -284,18 +437,21 @@ impl FallbackSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
     ) -> SearchResult<(Vec<SearchCandidate>, SearchMode)> {
         let cache_manager = self.cache_manager.clone();
         let circuit_breaker = self.circuit_breaker.clone();
         let query_vector = query_vector.to_vec();
+        let filters = filters.cloned();
 
         let result = self.retry_executor.execute(|| {
             let cache_manager = cache_manager.clone();
             let circuit_breaker = circuit_breaker.clone();
             let query_vector = query_vector.clone();
+            let filters = filters.clone();
 
             async move {
-                self.execute_cache_only_search(&query_vector, limit, &cache_manager, &circuit_breaker).await
+                self.execute_cache_only_search(&query_vector, limit, filters.as_ref(), &cache_manager, &circuit_breaker).await
             }
         }).await;
 
@@ -313,12 +469,13 @@ impl FallbackSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
         cache_manager: &CacheManager,
         circuit_breaker: &CircuitBreaker,
     ) -> SearchResult<Vec<SearchCandidate>> {
         debug!("Executing cache-only search");
 
-        match self.redis_search_with_timeout(query_vector, limit, cache_manager).await {
+        match self.redis_search_with_timeout(query_vector, limit, filters, cache_manager).await {
             Ok(candidates) => {
                 debug!("Cache-only search succeeded: {} candidates", candidates.len());
                 circuit_breaker.record_redis_success().await;
@@ -337,11 +494,12 @@ impl FallbackSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
         cache_manager: &CacheManager,
     ) -> SearchResult<Vec<SearchCandidate>> {
         let search_timeout = Duration::from_millis(400);
         
-        timeout(search_timeout, cache_manager.vector_search(query_vector, limit))
+        timeout(search_timeout, cache_manager.vector_search(query_vector, limit, filters))
             .await
             .map_err(|_| SearchError::RedisError("Redis search timeout".to_string()))?
     }
@@ -351,46 +509,21 @@ impl FallbackSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
         database_manager: &DatabaseManager,
     ) -> SearchResult<Vec<SearchCandidate>> {
         let search_timeout = Duration::from_millis(500);
         
-        timeout(search_timeout, database_manager.vector_search(query_vector, limit))
+        timeout(search_timeout, database_manager.vector_search(query_vector, limit, filters))
             .await
             .map_err(|_| SearchError::DatabaseError("Postgres search timeout".to_string()))?
     }
 
     /// Merge and deduplicate search candidates
     fn merge_and_dedup(&self, candidates: Vec<SearchCandidate>) -> Vec<SearchCandidate> {
-        use std::collections::HashMap;
-
         debug!("Merging and deduplicating {} candidates", candidates.len());
 
-        let mut best_candidates: HashMap<String, SearchCandidate> = HashMap::new();
-
-        for candidate in candidates {
-            match best_candidates.get(&candidate.post_id) {
-                Some(existing) => {
-                    if candidate.score > existing.score {
-                        debug!(
-                            "Replacing candidate {} (score: {:.4} -> {:.4})",
-                            candidate.post_id, existing.score, candidate.score
-                        );
-                        best_candidates.insert(candidate.post_id.clone(), candidate);
-                    }
-                }
-                None => {
-                    best_candidates.insert(candidate.post_id.clone(), candidate);
-                }
-            }
-        }
-
-        let mut merged_candidates: Vec<SearchCandidate> = best_candidates.into_values().collect();
-        merged_candidates.sort_by(|a, b| {
-            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        merged_candidates.truncate(self.max_candidates);
+        let merged_candidates = merge_candidates(candidates, self.max_candidates, self.score_normalization);
         debug!("Merge complete: {} unique candidates", merged_candidates.len());
 
         merged_candidates
@@ -443,6 +576,56 @@ pub struct FallbackHealthStatus {
     pub postgres_error: Option<String>,
 }
 
+/// Keep the best-scoring candidate per post, ranked by score
+///
+/// Candidates are compared by their score normalized per source with
+/// `strategy`, and keep their raw score. Sources of the dropped duplicates
+/// are recorded in `merged_from` of the kept candidate.
+pub(crate) fn merge_candidates(
+    candidates: Vec<SearchCandidate>,
+    max_candidates: usize,
+    strategy: ScoreNormalization,
+) -> Vec<SearchCandidate> {
+    let fused = fusion_scores(&candidates, strategy);
+    let mut best_candidates: HashMap<String, (f32, SearchCandidate)> = HashMap::new();
+
+    for (fused_score, mut candidate) in fused.into_iter().zip(candidates) {
+        match best_candidates.get_mut(&candidate.post_id) {
+            Some((existing_score, existing)) => {
+                let dropped_sources = if fused_score > *existing_score {
+                    debug!(
+                        "Replacing candidate {} (score: {:.4} -> {:.4})",
+                        candidate.post_id, existing_score, fused_score
+                    );
+                    *existing_score = fused_score;
+                    std::mem::swap(existing, &mut candidate);
+                    candidate.merged_from.push(candidate.source.clone());
+                    candidate.merged_from
+                } else {
+                    vec![candidate.source]
+                };
+
+                for source in dropped_sources {
+                    if source != existing.source && !existing.merged_from.contains(&source) {
+                        existing.merged_from.push(source);
+                    }
+                }
+            }
+            None => {
+                best_candidates.insert(candidate.post_id.clone(), (fused_score, candidate));
+            }
+        }
+    }
+
+    let mut merged: Vec<(f32, SearchCandidate)> = best_candidates.into_values().collect();
+    merged.sort_by(|a, b| {
+        b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    merged.truncate(max_candidates);
+    merged.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
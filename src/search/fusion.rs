@@ -0,0 +1,173 @@
+//! Rank fusion for hybrid lexical + vector retrieval
+//!
+//! Cosine similarities and full-text ranks are not on comparable scales, so
+//! the two candidate lists are merged by rank with weighted reciprocal rank
+//! fusion (RRF) instead of by raw score.
+
+use crate::types::{PassageSpan, SearchCandidate, SearchSource};
+use std::collections::HashMap;
+
+/// RRF smoothing constant; 60 is the value from the original RRF paper
+pub const RRF_K: f32 = 60.0;
+
+/// Default weight of the vector ranking in hybrid fusion
+pub const DEFAULT_VECTOR_WEIGHT: f32 = 0.5;
+
+/// Merge vector and lexical candidates with weighted reciprocal rank fusion
+///
+/// Each candidate scores `w / (RRF_K + rank)` per list it appears in, where
+/// the vector list is weighted by `vector_weight` and the lexical list by
+/// `1 - vector_weight`. Scores are scaled so that a post ranked first in both
+/// lists scores 1.0, keeping `min_score` meaningful for hybrid results.
+pub fn reciprocal_rank_fusion(
+    vector: &[SearchCandidate],
+    lexical: &[SearchCandidate],
+    vector_weight: f32,
+) -> Vec<SearchCandidate> {
+    let vector_weight = vector_weight.clamp(0.0, 1.0);
+    let lexical_weight = 1.0 - vector_weight;
+
+    let mut fused: HashMap<&str, f32> = HashMap::new();
+    for (candidates, weight) in [(vector, vector_weight), (lexical, lexical_weight)] {
+        for (post_id, rank) in fusion_ranks(candidates) {
+            *fused.entry(post_id).or_insert(0.0) += rrf_contribution(weight, rank);
+        }
+    }
+
+    // Keep the matching passage of passage-level vector hits
+    let passages: HashMap<&str, PassageSpan> = vector
+        .iter()
+        .rev()
+        .filter_map(|candidate| candidate.passage.map(|span| (candidate.post_id.as_str(), span)))
+        .collect();
+
+    let mut results: Vec<SearchCandidate> = fused
+        .into_iter()
+        .map(|(post_id, score)| SearchCandidate {
+            post_id: post_id.to_string(),
+            score: score.min(1.0),
+            source: SearchSource::Hybrid,
+            passage: passages.get(post_id).copied(),
+            merged_from: Vec::new(),
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.post_id.cmp(&b.post_id))
+    });
+
+    results
+}
+
+/// 1-based rank of every post in one ranking
+///
+/// Only the best rank of a post within one list counts, and later entries of
+/// the same post do not take up a rank.
+pub fn fusion_ranks(candidates: &[SearchCandidate]) -> HashMap<&str, usize> {
+    let mut ranks = HashMap::new();
+    for candidate in candidates {
+        let next_rank = ranks.len() + 1;
+        ranks.entry(candidate.post_id.as_str()).or_insert(next_rank);
+    }
+    ranks
+}
+
+/// Fused score a rank contributes, scaled so first place in both lists sums to 1.0
+pub fn rrf_contribution(weight: f32, rank: usize) -> f32 {
+    weight * (RRF_K + 1.0) / (RRF_K + rank as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(post_ids: &[&str], source: SearchSource) -> Vec<SearchCandidate> {
+        post_ids
+            .iter()
+            .enumerate()
+            .map(|(i, post_id)| SearchCandidate {
+                post_id: post_id.to_string(),
+                score: 1.0 - i as f32 * 0.1,
+                source: source.clone(),
+                passage: None,
+                merged_from: Vec::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rrf_rewards_agreement() {
+        let vector = candidates(&["a", "b", "c"], SearchSource::Postgres);
+        let lexical = candidates(&["c", "a", "d"], SearchSource::Lexical);
+
+        let fused = reciprocal_rank_fusion(&vector, &lexical, DEFAULT_VECTOR_WEIGHT);
+        let order: Vec<&str> = fused.iter().map(|c| c.post_id.as_str()).collect();
+
+        assert_eq!(order, vec!["a", "c", "b", "d"]);
+        assert!(fused.iter().all(|c| c.source == SearchSource::Hybrid));
+        assert!(fused.iter().all(|c| c.score > 0.0 && c.score <= 1.0));
+    }
+
+    #[test]
+    fn test_rrf_top_of_both_lists_scores_one() {
+        let vector = candidates(&["a", "b"], SearchSource::Redis);
+        let lexical = candidates(&["a", "c"], SearchSource::Lexical);
+
+        let fused = reciprocal_rank_fusion(&vector, &lexical, 0.3);
+        assert_eq!(fused[0].post_id, "a");
+        assert!((fused[0].score - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rrf_weight_extremes() {
+        let vector = candidates(&["a", "b"], SearchSource::Postgres);
+        let lexical = candidates(&["b", "c"], SearchSource::Lexical);
+
+        let vector_only = reciprocal_rank_fusion(&vector, &lexical, 1.0);
+        assert_eq!(vector_only[0].post_id, "a");
+
+        let lexical_only = reciprocal_rank_fusion(&vector, &lexical, 0.0);
+        assert_eq!(lexical_only[0].post_id, "b");
+    }
+
+    #[test]
+    fn test_rrf_ignores_duplicates_within_a_list() {
+        let mut vector = candidates(&["a", "b"], SearchSource::Redis);
+        vector.extend(candidates(&["a"], SearchSource::Postgres));
+
+        let fused = reciprocal_rank_fusion(&vector, &[], 1.0);
+        assert_eq!(fused.len(), 2);
+        assert!((fused[0].score - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rrf_keeps_vector_passages() {
+        let mut vector = candidates(&["a", "b"], SearchSource::Postgres);
+        vector[0].passage = Some(PassageSpan { start: 10, end: 20 });
+        let lexical = candidates(&["b", "a"], SearchSource::Lexical);
+
+        let fused = reciprocal_rank_fusion(&vector, &lexical, 0.5);
+        let a = fused.iter().find(|c| c.post_id == "a").unwrap();
+        let b = fused.iter().find(|c| c.post_id == "b").unwrap();
+        assert_eq!(a.passage, Some(PassageSpan { start: 10, end: 20 }));
+        assert_eq!(b.passage, None);
+    }
+
+    #[test]
+    fn test_contributions_sum_to_fused_score() {
+        let vector = candidates(&["a", "b", "a", "c"], SearchSource::Redis);
+        let lexical = candidates(&["c", "a"], SearchSource::Lexical);
+
+        let ranks = fusion_ranks(&vector);
+        assert_eq!(ranks["a"], 1);
+        assert_eq!(ranks["c"], 3);
+
+        let fused = reciprocal_rank_fusion(&vector, &lexical, 0.7);
+        let c = fused.iter().find(|c| c.post_id == "c").unwrap();
+        let expected = rrf_contribution(0.7, 3) + rrf_contribution(0.3, 1);
+        assert!((c.score - expected).abs() < 1e-6);
+    }
+}
@@ -7,6 +7,13 @@ pub mod circuit_breaker;
 pub mod retry;
 pub mod fallback;
 pub mod reranking;
+pub mod fusion;
+pub mod normalization;
+pub mod diversity;
+pub mod passages;
+pub mod snippet;
+pub mod cursor;
+pub mod explain;
 pub mod service;
 
 #[cfg(test)]
@@ -17,12 +24,19 @@ pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerSt
 pub use retry::{RetryExecutor, RetryConfig, RetryStrategy};
 pub use fallback::{FallbackSearchService, FallbackHealthStatus};
 pub use reranking::{RerankingService, RerankingConfig};
-pub use service::{SearchService, SearchServiceHealth, SearchServiceStats};
+pub use fusion::{fusion_ranks, reciprocal_rank_fusion, rrf_contribution, DEFAULT_VECTOR_WEIGHT};
+pub use normalization::{fusion_scores, ScoreNormalization};
+pub use diversity::{maximal_marginal_relevance, remove_near_duplicates, NEAR_DUPLICATE_SIMILARITY};
+pub use passages::aggregate_passages;
+pub use snippet::{extract_snippet, query_terms, Snippet};
+pub use explain::Explainer;
+pub use cursor::{CursorCodec, SearchCursor, MAX_CURSOR_LENGTH, MAX_SEARCH_DEPTH};
+pub use service::{SearchService, SearchServiceHealth, SearchServiceStats, SearchStage};
 
 use crate::cache::CacheManager;
 use crate::database::DatabaseManager;
 use crate::error::{SearchError, SearchResult};
-use crate::types::{SearchCandidate, SearchSource};
+use crate::types::{SearchCandidate, SearchFilters, SearchSource};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::time::{timeout, Duration};
@@ -36,6 +50,8 @@ pub struct VectorSearchService {
     database_manager: Arc<DatabaseManager>,
     /// Maximum number of candidates to return after merging
     max_candidates: usize,
+    /// Normalization applied to each source's scores before merging
+    score_normalization: ScoreNormalization,
 }
 
 impl VectorSearchService {
@@ -48,9 +64,16 @@ impl VectorSearchService {
             cache_manager,
             database_manager,
             max_candidates: 130, // As per requirements
+            score_normalization: ScoreNormalization::None,
         }
     }
 
+    /// Compare each source's scores with the given normalization when merging
+    pub fn with_score_normalization(mut self, score_normalization: ScoreNormalization) -> Self {
+        self.score_normalization = score_normalization;
+        self
+    }
+
     /// Perform parallel vector search across Redis and Postgres
     /// 
     /// This method queries both Redis and Postgres simultaneously, then merges
@@ -59,13 +82,14 @@ impl VectorSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
     ) -> SearchResult<Vec<SearchCandidate>> {
         debug!("Starting parallel vector search with limit: {}", limit);
 
         // Launch both searches in parallel
         let (redis_result, postgres_result) = tokio::join!(
-            self.redis_vector_search_with_timeout(query_vector, 100),
-            self.postgres_vector_search_with_timeout(query_vector, 100)
+            self.redis_vector_search_with_timeout(query_vector, 100, filters),
+            self.postgres_vector_search_with_timeout(query_vector, 100, filters)
         );
 
         // Collect successful results
@@ -133,10 +157,11 @@ impl VectorSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
     ) -> SearchResult<Vec<SearchCandidate>> {
         let search_timeout = Duration::from_millis(400); // Reasonable timeout for Redis
         
-        timeout(search_timeout, self.redis_vector_search(query_vector, limit))
+        timeout(search_timeout, self.redis_vector_search(query_vector, limit, filters))
             .await
             .map_err(|_| SearchError::RedisError("Redis search timeout".to_string()))?
     }
@@ -146,10 +171,11 @@ impl VectorSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
     ) -> SearchResult<Vec<SearchCandidate>> {
         let search_timeout = Duration::from_millis(500); // 500ms as per requirements
         
-        timeout(search_timeout, self.postgres_vector_search(query_vector, limit))
+        timeout(search_timeout, self.postgres_vector_search(query_vector, limit, filters))
             .await
             .map_err(|_| SearchError::DatabaseError("Postgres search timeout".to_string()))?
     }
@@ -159,9 +185,10 @@ impl VectorSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
     ) -> SearchResult<Vec<SearchCandidate>> {
         debug!("Performing Redis vector search");
-        self.cache_manager.vector_search(query_vector, limit).await
+        self.cache_manager.vector_search(query_vector, limit, filters).await
     }
 
     /// Search Postgres with pgvector
@@ -169,53 +196,58 @@ impl VectorSearchService {
         &self,
         query_vector: &[f32],
         limit: usize,
+        filters: Option<&SearchFilters>,
     ) -> SearchResult<Vec<SearchCandidate>> {
         debug!("Performing Postgres vector search");
-        self.database_manager.vector_search(query_vector, limit).await
+        self.database_manager.vector_search(query_vector, limit, filters).await
     }
 
     /// Merge and deduplicate search candidates
     /// 
     /// This method combines results from Redis and Postgres, removes duplicates
     /// by post_id, and keeps the result with the higher score for each post.
-    /// Results are sorted by cosine similarity score in descending order.
+    /// Scores are compared after per-source normalization and results are
+    /// sorted by it in descending order, but every candidate keeps its raw score.
     fn merge_and_dedup(&self, candidates: Vec<SearchCandidate>) -> Vec<SearchCandidate> {
         debug!("Merging and deduplicating {} candidates", candidates.len());
 
-        // Use HashMap to deduplicate by post_id, keeping the highest score
-        let mut best_candidates: HashMap<String, SearchCandidate> = HashMap::new();
+        let fused = fusion_scores(&candidates, self.score_normalization);
+
+        // Use HashMap to deduplicate by post_id, keeping the highest normalized score
+        let mut best_candidates: HashMap<String, (f32, SearchCandidate)> = HashMap::new();
 
-        for candidate in candidates {
+        for (fused_score, candidate) in fused.into_iter().zip(candidates) {
             match best_candidates.get(&candidate.post_id) {
-                Some(existing) => {
+                Some((existing_score, existing)) => {
                     // Keep the candidate with higher score
-                    if candidate.score > existing.score {
+                    if fused_score > *existing_score {
                         debug!(
                             "Replacing candidate {} (score: {:.4} -> {:.4}, source: {:?} -> {:?})",
-                            candidate.post_id, existing.score, candidate.score, existing.source, candidate.source
+                            candidate.post_id, existing_score, fused_score, existing.source, candidate.source
                         );
-                        best_candidates.insert(candidate.post_id.clone(), candidate);
+                        best_candidates.insert(candidate.post_id.clone(), (fused_score, candidate));
                     } else {
                         debug!(
                             "Keeping existing candidate {} (score: {:.4} vs {:.4})",
-                            candidate.post_id, existing.score, candidate.score
+                            candidate.post_id, existing_score, fused_score
                         );
                     }
                 }
                 None => {
-                    best_candidates.insert(candidate.post_id.clone(), candidate);
+                    best_candidates.insert(candidate.post_id.clone(), (fused_score, candidate));
                 }
             }
         }
 
-        // Convert to vector and sort by score (descending)
-        let mut merged_candidates: Vec<SearchCandidate> = best_candidates.into_values().collect();
-        merged_candidates.sort_by(|a, b| {
-            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+        // Convert to vector and sort by normalized score (descending)
+        let mut merged: Vec<(f32, SearchCandidate)> = best_candidates.into_values().collect();
+        merged.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal)
         });
 
         // Limit to max candidates as per requirements
-        merged_candidates.truncate(self.max_candidates);
+        merged.truncate(self.max_candidates);
+        let merged_candidates: Vec<SearchCandidate> = merged.into_iter().map(|(_, candidate)| candidate).collect();
 
         debug!(
             "Merge complete: {} unique candidates (limited to {})",
@@ -237,6 +269,7 @@ impl VectorSearchService {
             redis_memory_usage: redis_stats.used_memory_bytes,
             postgres_active_connections: postgres_stats.active_connections,
             postgres_total_posts: postgres_stats.total_posts,
+            score_normalization: self.score_normalization,
         })
     }
 
@@ -276,4 +309,5 @@ pub struct SearchStats {
     pub redis_memory_usage: u64,
     pub postgres_active_connections: u32,
     pub postgres_total_posts: u64,
+    pub score_normalization: ScoreNormalization,
 }
\ No newline at end of file
@@ -0,0 +1,213 @@
+//! Per-source score normalization used when candidates are merged
+//!
+//! Redis KNN and pgvector each report their own distance, and indexes added
+//! later (quantized or approximate) will not agree with either, so raw scores
+//! from different sources are not on one scale. Each source's candidates are
+//! rescaled on their own to decide which candidate per post is kept and which
+//! make the cut. The rescaled values depend on the rest of the pool, so they
+//! never replace the raw scores that `min_score`, cursors and explanations use.
+
+use crate::error::{SearchError, SearchResult};
+use crate::types::{SearchCandidate, SearchSource};
+use serde::{Deserialize, Serialize};
+
+/// Strategy for putting the scores of one source on a 0-1 scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreNormalization {
+    /// Keep raw similarity scores
+    #[default]
+    None,
+    /// Rescale linearly so the best candidate of a source scores 1.0 and the worst 0.0
+    MinMax,
+    /// Standardize per source and map through the logistic function, 0.5 at the source mean
+    ZScore,
+    /// Score by rank within a source, from 1.0 for the best down to 1/n for the last
+    Rank,
+}
+
+impl ScoreNormalization {
+    /// Every supported strategy
+    pub const ALL: [ScoreNormalization; 4] = [
+        ScoreNormalization::None,
+        ScoreNormalization::MinMax,
+        ScoreNormalization::ZScore,
+        ScoreNormalization::Rank,
+    ];
+
+    /// Look up a strategy by its name
+    pub fn from_name(name: &str) -> SearchResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.as_str() == name)
+            .ok_or_else(|| {
+                let supported: Vec<&str> = Self::ALL.iter().map(|strategy| strategy.as_str()).collect();
+                SearchError::ConfigError(format!(
+                    "Unsupported SCORE_NORMALIZATION '{}', expected one of: {}",
+                    name,
+                    supported.join(", ")
+                ))
+            })
+    }
+
+    /// Name used in configuration and stats
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScoreNormalization::None => "none",
+            ScoreNormalization::MinMax => "min_max",
+            ScoreNormalization::ZScore => "z_score",
+            ScoreNormalization::Rank => "rank",
+        }
+    }
+}
+
+/// Spread below which all scores of a source count as equal
+const MIN_SPREAD: f32 = 1e-6;
+
+/// Scores of the candidates normalized separately for each source, in candidate order
+///
+/// The candidates keep their raw scores; these values only rank them against
+/// candidates of other sources.
+pub fn fusion_scores(candidates: &[SearchCandidate], strategy: ScoreNormalization) -> Vec<f32> {
+    let mut fused: Vec<f32> = candidates.iter().map(|candidate| candidate.score).collect();
+    if strategy == ScoreNormalization::None {
+        return fused;
+    }
+
+    let mut sources: Vec<&SearchSource> = Vec::new();
+    for candidate in candidates {
+        if !sources.contains(&&candidate.source) {
+            sources.push(&candidate.source);
+        }
+    }
+
+    for source in sources {
+        let members: Vec<usize> = (0..candidates.len())
+            .filter(|&index| candidates[index].source == *source)
+            .collect();
+        let mut scores: Vec<f32> = members.iter().map(|&index| candidates[index].score).collect();
+        normalize_scores(&mut scores, strategy);
+        for (index, normalized) in members.into_iter().zip(scores) {
+            fused[index] = normalized;
+        }
+    }
+
+    fused
+}
+
+/// Normalize the scores of one source in place
+pub fn normalize_scores(scores: &mut [f32], strategy: ScoreNormalization) {
+    if scores.is_empty() {
+        return;
+    }
+
+    match strategy {
+        ScoreNormalization::None => {}
+        ScoreNormalization::MinMax => {
+            let min = scores.iter().copied().fold(f32::INFINITY, f32::min);
+            let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let spread = max - min;
+            for score in scores.iter_mut() {
+                *score = if spread < MIN_SPREAD { 1.0 } else { (*score - min) / spread };
+            }
+        }
+        ScoreNormalization::ZScore => {
+            let n = scores.len() as f32;
+            let mean = scores.iter().sum::<f32>() / n;
+            let std_dev = (scores.iter().map(|score| (score - mean).powi(2)).sum::<f32>() / n).sqrt();
+            for score in scores.iter_mut() {
+                let z = if std_dev < MIN_SPREAD { 0.0 } else { (*score - mean) / std_dev };
+                *score = 1.0 / (1.0 + (-z).exp());
+            }
+        }
+        ScoreNormalization::Rank => {
+            // Equal scores share the better rank
+            let mut order: Vec<usize> = (0..scores.len()).collect();
+            order.sort_by(|&a, &b| scores[b].partial_cmp(&scores[a]).unwrap_or(std::cmp::Ordering::Equal));
+
+            let n = scores.len() as f32;
+            let mut normalized = vec![0.0; scores.len()];
+            let mut rank = 0;
+            for (position, &index) in order.iter().enumerate() {
+                if position > 0 && scores[index] < scores[order[position - 1]] {
+                    rank = position;
+                }
+                normalized[index] = (n - rank as f32) / n;
+            }
+            scores.copy_from_slice(&normalized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_fixtures::search_candidate as candidate;
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_strategy_names_round_trip() {
+        for strategy in ScoreNormalization::ALL {
+            assert_eq!(ScoreNormalization::from_name(strategy.as_str()).unwrap(), strategy);
+        }
+        assert_eq!(ScoreNormalization::default(), ScoreNormalization::None);
+        assert!(ScoreNormalization::from_name("softmax").is_err());
+    }
+
+    #[test]
+    fn test_normalize_scores() {
+        let raw = [0.9, 0.7, 0.5];
+
+        let mut scores = raw;
+        normalize_scores(&mut scores, ScoreNormalization::None);
+        assert_close(&scores, &raw);
+
+        let mut scores = raw;
+        normalize_scores(&mut scores, ScoreNormalization::MinMax);
+        assert_close(&scores, &[1.0, 0.5, 0.0]);
+
+        let mut scores = raw;
+        normalize_scores(&mut scores, ScoreNormalization::ZScore);
+        assert!((scores[1] - 0.5).abs() < 1e-5);
+        assert!(scores[0] > scores[1] && scores[1] > scores[2]);
+        assert!((scores[0] + scores[2] - 1.0).abs() < 1e-5);
+
+        let mut scores = [0.5, 0.9, 0.7, 0.9];
+        normalize_scores(&mut scores, ScoreNormalization::Rank);
+        assert_close(&scores, &[0.25, 1.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_normalize_equal_scores() {
+        let mut scores = [0.4, 0.4];
+        normalize_scores(&mut scores, ScoreNormalization::MinMax);
+        assert_close(&scores, &[1.0, 1.0]);
+
+        let mut scores = [0.4];
+        normalize_scores(&mut scores, ScoreNormalization::ZScore);
+        assert_close(&scores, &[0.5]);
+    }
+
+    #[test]
+    fn test_fusion_scores_per_source() {
+        // Postgres scores sit on a compressed scale; each source is rescaled on its own
+        let candidates = vec![
+            candidate("a", 0.95, SearchSource::Redis),
+            candidate("b", 0.55, SearchSource::Postgres),
+            candidate("c", 0.75, SearchSource::Redis),
+            candidate("d", 0.60, SearchSource::Postgres),
+        ];
+
+        assert_close(&fusion_scores(&candidates, ScoreNormalization::MinMax), &[1.0, 0.0, 0.0, 1.0]);
+        assert_close(&fusion_scores(&candidates, ScoreNormalization::None), &[0.95, 0.55, 0.75, 0.60]);
+
+        // Raw scores are left untouched
+        assert!((candidates[1].score - 0.55).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,150 @@
+//! Aggregation of passage hits into post candidates
+//!
+//! Passage search ranks passages, but results are posts. Each post is scored
+//! from its best passages and keeps the best one so the response can show it
+//! as the snippet.
+
+use crate::types::{ChunkCandidate, PassageAggregation, SearchCandidate, SearchSource};
+use std::collections::HashMap;
+
+/// Collapse passage hits into one candidate per post, ranked by score
+///
+/// Hits for the same passage from several sources count once, with the
+/// higher score, and the other sources are kept in `merged_from`.
+///
+/// `Max` scores a post by its best passage. `MeanTopN(n)` averages its `n`
+/// best passages, missing ones counting as 0, favouring posts that match in
+/// several places.
+pub fn aggregate_passages(hits: Vec<ChunkCandidate>, aggregation: PassageAggregation) -> Vec<SearchCandidate> {
+    let mut sources: HashMap<String, Vec<SearchSource>> = HashMap::new();
+    let mut passages: HashMap<(String, u32), ChunkCandidate> = HashMap::new();
+    for hit in hits {
+        let post_sources = sources.entry(hit.post_id.clone()).or_default();
+        if !post_sources.contains(&hit.source) {
+            post_sources.push(hit.source.clone());
+        }
+
+        let key = (hit.post_id.clone(), hit.chunk_index);
+        match passages.get(&key) {
+            Some(existing) if existing.score >= hit.score => {}
+            _ => {
+                passages.insert(key, hit);
+            }
+        }
+    }
+
+    let mut by_post: HashMap<String, Vec<ChunkCandidate>> = HashMap::new();
+    for hit in passages.into_values() {
+        by_post.entry(hit.post_id.clone()).or_default().push(hit);
+    }
+
+    let mut candidates: Vec<SearchCandidate> = by_post
+        .into_iter()
+        .map(|(post_id, mut hits)| {
+            hits.sort_by(|a, b| {
+                b.score
+                    .partial_cmp(&a.score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.chunk_index.cmp(&b.chunk_index))
+            });
+
+            let score = match aggregation {
+                PassageAggregation::Max => hits[0].score,
+                PassageAggregation::MeanTopN(n) => {
+                    let n = n.max(1) as usize;
+                    hits.iter().take(n).map(|hit| hit.score).sum::<f32>() / n as f32
+                }
+            };
+
+            let source = hits[0].source.clone();
+            let merged_from = sources
+                .remove(&post_id)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|other| *other != source)
+                .collect();
+
+            SearchCandidate {
+                post_id,
+                score,
+                source,
+                passage: Some(hits[0].span),
+                merged_from,
+            }
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.post_id.cmp(&b.post_id))
+    });
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PassageSpan;
+
+    fn hit(post_id: &str, chunk_index: u32, score: f32) -> ChunkCandidate {
+        ChunkCandidate {
+            post_id: post_id.to_string(),
+            chunk_index,
+            span: PassageSpan {
+                start: chunk_index * 100,
+                end: chunk_index * 100 + 120,
+            },
+            score,
+            source: SearchSource::Postgres,
+        }
+    }
+
+    /// "a" has one strong passage, "b" several good ones
+    fn hits() -> Vec<ChunkCandidate> {
+        vec![
+            hit("a", 4, 0.90),
+            hit("b", 0, 0.80),
+            hit("b", 1, 0.78),
+            hit("b", 2, 0.75),
+            hit("a", 5, 0.30),
+        ]
+    }
+
+    #[test]
+    fn test_max_aggregation() {
+        let candidates = aggregate_passages(hits(), PassageAggregation::Max);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].post_id, "a");
+        assert_eq!(candidates[0].score, 0.90);
+        assert_eq!(candidates[0].passage, Some(PassageSpan { start: 400, end: 520 }));
+        assert_eq!(candidates[1].passage, Some(PassageSpan { start: 0, end: 120 }));
+    }
+
+    #[test]
+    fn test_mean_top_n_aggregation() {
+        let candidates = aggregate_passages(hits(), PassageAggregation::MeanTopN(3));
+        assert_eq!(candidates[0].post_id, "b");
+        assert!((candidates[0].score - 0.776_666_7).abs() < 1e-5);
+        assert!((candidates[1].score - 0.4).abs() < 1e-5);
+
+        // The snippet passage is still the best one
+        assert_eq!(candidates[1].passage, Some(PassageSpan { start: 400, end: 520 }));
+    }
+
+    #[test]
+    fn test_duplicate_passages_count_once() {
+        let mut duplicated = vec![hit("a", 0, 0.5), hit("a", 0, 0.7)];
+        duplicated[1].source = SearchSource::Redis;
+
+        let candidates = aggregate_passages(duplicated, PassageAggregation::MeanTopN(2));
+        assert_eq!(candidates.len(), 1);
+        assert!((candidates[0].score - 0.35).abs() < 1e-6);
+        assert_eq!(candidates[0].source, SearchSource::Redis);
+        assert_eq!(candidates[0].merged_from, vec![SearchSource::Postgres]);
+
+        assert!(aggregate_passages(Vec::new(), PassageAggregation::Max).is_empty());
+    }
+}
@@ -60,6 +60,10 @@ impl RerankingService {
     /// This method takes the original query and search results, applies cross-encoder
     /// scoring, and returns reranked results. It includes performance optimizations
     /// and graceful degradation on failures.
+    ///
+    /// The flag returned with the results tells whether the cross-encoder
+    /// actually scored them; it is false when reranking is disabled, there is
+    /// nothing to rerank, or a failure degraded to the original results.
     #[instrument(skip(self, search_results), fields(
         query_len = query.len(),
         num_results = search_results.len(),
@@ -70,16 +74,16 @@ impl RerankingService {
         query: &str,
         search_results: &[SearchResponse],
         rerank_enabled: bool,
-    ) -> SearchResult<Vec<SearchResponse>> {
+    ) -> SearchResult<(Vec<SearchResponse>, bool)> {
         // If reranking is not enabled, return original results
         if !rerank_enabled {
             debug!("Reranking disabled, returning original results");
-            return Ok(search_results.to_vec());
+            return Ok((search_results.to_vec(), false));
         }
 
         if search_results.is_empty() {
             debug!("No results to rerank");
-            return Ok(search_results.to_vec());
+            return Ok((search_results.to_vec(), false));
         }
 
         debug!("Starting reranking for {} results", search_results.len());
@@ -117,7 +121,7 @@ impl RerankingService {
                 final_results.extend(remaining_results);
                 
                 info!("Reranking completed successfully: {} total results", final_results.len());
-                Ok(final_results)
+                Ok((final_results, true))
             }
             Err(e) => {
                 if self.config.enable_graceful_degradation {
@@ -128,7 +132,7 @@ impl RerankingService {
                     final_results.extend(remaining_results);
                     
                     info!("Graceful degradation: returning {} results with original scores", final_results.len());
-                    Ok(final_results)
+                    Ok((final_results, false))
                 } else {
                     error!("Reranking failed and graceful degradation disabled: {}", e);
                     Err(e)
@@ -393,6 +397,7 @@ mod tests {
                 post_id: "post1".to_string(),
                 title: "First Post".to_string(),
                 snippet: "This is the first post about machine learning".to_string(),
+                highlights: Vec::new(),
                 score: 0.8,
                 meta: PostMetadata {
                     author_name: "Author 1".to_string(),
@@ -400,12 +405,16 @@ mod tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: false,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
             SearchResponse {
                 post_id: "post2".to_string(),
                 title: "Second Post".to_string(),
                 snippet: "This is the second post about artificial intelligence".to_string(),
+                highlights: Vec::new(),
                 score: 0.7,
                 meta: PostMetadata {
                     author_name: "Author 2".to_string(),
@@ -413,12 +422,16 @@ mod tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: false,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
             SearchResponse {
                 post_id: "post3".to_string(),
                 title: "Third Post".to_string(),
                 snippet: "This is the third post about deep learning".to_string(),
+                highlights: Vec::new(),
                 score: 0.6,
                 meta: PostMetadata {
                     author_name: "Author 3".to_string(),
@@ -426,7 +439,10 @@ mod tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: false,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
         ]
     }
@@ -438,16 +454,22 @@ mod tests {
                 post_id: "post1".to_string(),
                 score: 0.8,
                 source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
             },
             SearchCandidate {
                 post_id: "post2".to_string(),
                 score: 0.7,
                 source: SearchSource::Postgres,
+                passage: None,
+                merged_from: Vec::new(),
             },
             SearchCandidate {
                 post_id: "post3".to_string(),
                 score: 0.6,
                 source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
             },
         ]
     }
@@ -491,8 +513,9 @@ mod tests {
         let results = create_test_search_results();
         let original_len = results.len();
         
-        let reranked = service.rerank_results("test query", &results, false).await.unwrap();
+        let (reranked, scored) = service.rerank_results("test query", &results, false).await.unwrap();
         
+        assert!(!scored);
         assert_eq!(reranked.len(), original_len);
         // Should return original results unchanged when reranking is disabled
         assert_eq!(reranked[0].post_id, "post1");
@@ -505,8 +528,9 @@ mod tests {
         let service = RerankingService::new(cross_encoder);
         let results = vec![];
         
-        let reranked = service.rerank_results("test query", &results, true).await.unwrap();
+        let (reranked, scored) = service.rerank_results("test query", &results, true).await.unwrap();
         
+        assert!(!scored);
         assert!(reranked.is_empty());
     }
 
@@ -516,7 +540,7 @@ mod tests {
         let service = RerankingService::new(cross_encoder);
         let results = create_test_search_results();
         
-        let reranked = service.rerank_results("machine learning", &results, true).await.unwrap();
+        let (reranked, _) = service.rerank_results("machine learning", &results, true).await.unwrap();
         
         assert_eq!(reranked.len(), 3);
         // Results should be reordered based on cross-encoder scores
@@ -528,6 +552,20 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_rerank_results_degraded_reports_unscored() {
+        // The mock cross-encoder has no tokenizer, so scoring fails
+        let cross_encoder = Arc::new(create_mock_cross_encoder());
+        let service = RerankingService::new(cross_encoder);
+        let results = create_test_search_results();
+
+        let (degraded, scored) = service.rerank_results("test query", &results, true).await.unwrap();
+
+        assert!(!scored);
+        assert_eq!(degraded[0].post_id, results[0].post_id);
+        assert_eq!(degraded[0].score, results[0].score);
+    }
+
     #[tokio::test]
     async fn test_rerank_candidates_disabled() {
         let cross_encoder = Arc::new(create_mock_cross_encoder());
@@ -553,7 +591,7 @@ mod tests {
         let service = RerankingService::with_config(cross_encoder, config);
         let results = create_test_search_results(); // 3 results
         
-        let reranked = service.rerank_results("test query", &results, true).await.unwrap();
+        let (reranked, _) = service.rerank_results("test query", &results, true).await.unwrap();
         
         // Should still return all 3 results, but only first 2 should be reranked
         assert_eq!(reranked.len(), 3);
@@ -1,30 +1,67 @@
 /// Main search service that coordinates vector search, reranking, and result processing
 /// 
 /// This module implements the complete search pipeline including:
-/// - Vector search coordination across Redis and Postgres
+/// - Vector search coordination across Redis and Postgres, with per-source score normalization
+/// - Postgres full-text and hybrid retrieval with reciprocal rank fusion
 /// - Optional cross-encoder reranking when rerank=true
+/// - Optional maximal marginal relevance (MMR) diversification
+/// - Optional passage-level retrieval over chunked posts, aggregated back to posts
+/// - Query-aware snippets with highlighted query terms
+/// - Optional per-result score explanations
 /// - Graceful degradation and circuit breaker integration
 /// - Result filtering and metadata enrichment
+/// - Top-k result caching keyed on the normalized query and search parameters
+/// - Signed cursor pagination through the same ranked list
+/// - Staged results for streaming clients, sent before reranking finishes
+/// - Batch searches sharing one embedding call, run with bounded concurrency
+/// - "More like this" searches seeded from a stored post's embedding
 
-use crate::cache::CacheManager;
+use crate::cache::{CacheManager, CacheStats};
 use crate::database::DatabaseManager;
 use crate::error::{SearchError, SearchResult};
-use crate::ml::MLService;
-use crate::types::{SearchRequest, SearchResponse, SearchCandidate, SearchMode, Post, SearchFilters, PostMetadata};
-use crate::search::{FallbackSearchService, RerankingService, RerankingConfig};
+use crate::ml::{MLService, TokenizerService};
+use crate::observability::Metrics;
+use crate::types::{CachedResult, SearchRequest, SearchResponse, SearchCandidate, SearchMode, SearchSummary, Post, SearchFilters, PostMetadata, RetrievalMode, PassageAggregation, SimilarPostsRequest, DEFAULT_COLLECTION};
+use crate::search::{FallbackSearchService, RerankingService, RerankingConfig, reciprocal_rank_fusion, maximal_marginal_relevance, remove_near_duplicates, aggregate_passages, ScoreNormalization, DEFAULT_VECTOR_WEIGHT, NEAR_DUPLICATE_SIMILARITY};
+use crate::search::cursor::{CursorCodec, SearchCursor, MAX_SEARCH_DEPTH};
+use crate::search::snippet::{extract_snippet, query_terms};
+use crate::search::explain::{record_rerank_scores, Explainer};
+use futures::StreamExt;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn, instrument};
 
+/// Stage of a search streamed with `SearchService::semantic_search_staged`
+#[derive(Debug, Clone)]
+pub enum SearchStage {
+    /// Page of results that were not reranked
+    Results(Vec<SearchResponse>),
+    /// Page of results reordered by the cross-encoder
+    Reranked(Vec<SearchResponse>),
+    /// Summary sent after the final page
+    Done(SearchSummary),
+}
+
 /// Complete search service with ML integration
 pub struct SearchService {
     /// ML service for embeddings and reranking
     ml_service: Arc<MLService>,
     /// Fallback search service for vector search coordination
-    fallback_search: Arc<FallbackSearchService>,
+    fallback_search: FallbackSearchService,
     /// Database manager for post metadata retrieval
     database_manager: Arc<DatabaseManager>,
     /// Reranking service for cross-encoder scoring
     reranking_service: Arc<RerankingService>,
+    /// Query normalizer used to build top-k cache keys
+    query_normalizer: TokenizerService,
+    /// Prometheus metrics updated with top-k cache activity
+    metrics: Option<Arc<Metrics>>,
+    /// Signs and verifies pagination cursors
+    cursor_codec: CursorCodec,
+    /// Collection searched, so cursors of one collection fail on another
+    collection: String,
 }
 
 impl SearchService {
@@ -35,10 +72,10 @@ impl SearchService {
         ml_service: Arc<MLService>,
     ) -> SearchResult<Self> {
         // Create fallback search service
-        let fallback_search = Arc::new(FallbackSearchService::new(
+        let fallback_search = FallbackSearchService::new(
             cache_manager,
             database_manager.clone(),
-        ));
+        );
 
         // Create reranking service with default configuration
         let reranking_service = Arc::new(RerankingService::new(
@@ -50,6 +87,10 @@ impl SearchService {
             fallback_search,
             database_manager,
             reranking_service,
+            query_normalizer: TokenizerService::new_sync()?,
+            metrics: None,
+            cursor_codec: CursorCodec::random(),
+            collection: DEFAULT_COLLECTION.to_string(),
         })
     }
 
@@ -60,10 +101,10 @@ impl SearchService {
         ml_service: Arc<MLService>,
         reranking_config: RerankingConfig,
     ) -> SearchResult<Self> {
-        let fallback_search = Arc::new(FallbackSearchService::new(
+        let fallback_search = FallbackSearchService::new(
             cache_manager,
             database_manager.clone(),
-        ));
+        );
 
         let reranking_service = Arc::new(RerankingService::with_config(
             Arc::new(ml_service.cross_encoder().clone()),
@@ -75,60 +116,352 @@ impl SearchService {
             fallback_search,
             database_manager,
             reranking_service,
+            query_normalizer: TokenizerService::new_sync()?,
+            metrics: None,
+            cursor_codec: CursorCodec::random(),
+            collection: DEFAULT_COLLECTION.to_string(),
         })
     }
 
+    /// Report top-k cache activity to the given metrics
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Name the collection this service searches
+    pub fn with_collection(mut self, collection: &str) -> Self {
+        self.collection = collection.to_string();
+        self
+    }
+
+    /// Sign pagination cursors with a shared secret so any instance accepts them
+    pub fn with_cursor_secret(mut self, secret: &[u8]) -> Self {
+        self.cursor_codec = CursorCodec::new(secret);
+        self
+    }
+
+    /// Compare each retrieval source's scores with the given normalization when merging
+    pub fn with_score_normalization(mut self, score_normalization: ScoreNormalization) -> Self {
+        self.fallback_search = self.fallback_search.with_score_normalization(score_normalization);
+        self
+    }
+
     /// Perform complete semantic search with optional reranking
-    #[instrument(skip(self), fields(
+    pub async fn semantic_search(&self, request: SearchRequest) -> SearchResult<Vec<SearchResponse>> {
+        self.run_search(request, None, None).await.map(|(results, _)| results)
+    }
+
+    /// Perform semantic search, reporting results before reranking finishes
+    ///
+    /// When reranking runs, the page in retrieval order is sent as
+    /// `SearchStage::Results` first, without a `next_cursor`. The final page
+    /// follows as `SearchStage::Reranked` when the cross-encoder scored it, or
+    /// as `SearchStage::Results` when it was not reranked, including when the
+    /// reranker fell back to the retrieval order. A `SearchStage::Done`
+    /// summary ends the stages.
+    /// Each page replaces the previous one. A failure is sent as an error and
+    /// ends the stages.
+    pub async fn semantic_search_staged(&self, request: SearchRequest, stages: mpsc::Sender<SearchResult<SearchStage>>) {
+        let (results, summary) = match self.run_search(request, Some(&stages), None).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                error!("Staged search failed: {}", e);
+                let _ = stages.send(Err(e)).await;
+                return;
+            }
+        };
+
+        let page = if summary.reranked {
+            SearchStage::Reranked(results)
+        } else {
+            SearchStage::Results(results)
+        };
+
+        // A closed channel means the client went away; nothing left to report
+        if stages.send(Ok(page)).await.is_ok() {
+            let _ = stages.send(Ok(SearchStage::Done(summary))).await;
+        }
+    }
+
+    /// Run several searches, embedding their queries in one model call
+    ///
+    /// Requests that failed validation are passed in as their error and
+    /// returned as is without being searched. At most `concurrency` searches
+    /// run at once, each limited to `query_timeout`. Outcomes are returned in
+    /// request order, and a failed search does not affect the others.
+    pub async fn semantic_search_batch(
+        &self,
+        requests: Vec<SearchResult<SearchRequest>>,
+        concurrency: usize,
+        query_timeout: Duration,
+    ) -> Vec<SearchResult<Vec<SearchResponse>>> {
+        info!("Starting batch search for {} queries", requests.len());
+
+        let embeddings = self.batch_query_embeddings(&requests).await;
+
+        futures::stream::iter(requests.into_iter().zip(embeddings))
+            .map(|(request, query_embedding)| async move {
+                let request = match request {
+                    Ok(request) => request,
+                    Err(rejection) => return Err(rejection),
+                };
+                match tokio::time::timeout(query_timeout, self.run_search(request, None, query_embedding)).await {
+                    Ok(outcome) => outcome.map(|(results, _)| results),
+                    Err(_) => Err(SearchError::Timeout),
+                }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Embed the queries of every request that needs a vector ranking in one batch
+    ///
+    /// Returns one entry per request. Rejected and lexical requests get
+    /// `None`, and so do first-page requests already in the top-k cache,
+    /// which their search serves without an embedding; a hit is read again
+    /// by the search, which is cheaper than embedding its query. Every
+    /// request gets `None` when the batch fails, leaving each search to embed
+    /// its own query so one bad query cannot fail the others.
+    async fn batch_query_embeddings(&self, requests: &[SearchResult<SearchRequest>]) -> Vec<Option<Vec<f32>>> {
+        let mut embeddings = vec![None; requests.len()];
+        let lookups = requests.iter().enumerate().filter_map(|(position, request)| {
+            let request = request.as_ref().ok().filter(|request| request.mode != RetrievalMode::Lexical)?;
+            Some(async move { (!self.is_top_k_cached(request).await).then(|| (position, request.query.clone())) })
+        });
+        let (positions, queries): (Vec<usize>, Vec<String>) =
+            futures::future::join_all(lookups).await.into_iter().flatten().unzip();
+
+        if queries.is_empty() {
+            return embeddings;
+        }
+
+        debug!("Generating {} query embeddings in one batch", queries.len());
+        match self.ml_service.generate_embeddings_batch(&queries).await {
+            Ok(batch) if batch.len() == queries.len() => {
+                for (position, embedding) in positions.into_iter().zip(batch) {
+                    embeddings[position] = Some(embedding);
+                }
+            }
+            Ok(batch) => warn!(
+                "Batch embedding returned {} vectors for {} queries, embedding queries one at a time",
+                batch.len(),
+                queries.len()
+            ),
+            Err(e) => warn!("Batch embedding failed, embedding queries one at a time: {}", e),
+        }
+
+        embeddings
+    }
+
+    /// Whether a request would be served from the top-k cache
+    ///
+    /// Does not count towards the cache metrics; the search itself does.
+    async fn is_top_k_cached(&self, request: &SearchRequest) -> bool {
+        if request.cursor.is_some() || request.explain {
+            return false;
+        }
+        let cache_key = top_k_cache_key(
+            &self.query_normalizer,
+            request,
+            self.ml_service.embedding_model_id(),
+            self.fallback_search.score_normalization(),
+        );
+        matches!(
+            self.fallback_search.cache_manager().get_top_k_cache(cache_key).await,
+            Ok(Some(_))
+        )
+    }
+
+    /// Find posts similar to a stored post, seeded from its embedding
+    ///
+    /// The embedding is read from the Redis vector cache, or from Postgres
+    /// when it is not cached. Results pass the usual filters and score
+    /// threshold but are not reranked, cached or paginated, and the seed post
+    /// is never returned. Near-duplicates of the seed are dropped before the
+    /// filters when requested, searching deeper until enough results remain.
+    /// Returns `None` when the post does not exist or has no embedding.
+    #[instrument(skip(self, request), fields(post_id = %request.post_id, k = request.k))]
+    pub async fn similar_posts(&self, request: &SimilarPostsRequest) -> SearchResult<Option<Vec<SearchResponse>>> {
+        info!("Starting similar posts search for post: {}", request.post_id);
+
+        let Some(seed_embedding) = self.seed_embedding(&request.post_id).await? else {
+            info!("No embedding found for post: {}", request.post_id);
+            return Ok(None);
+        };
+
+        // One extra candidate for the seed post itself, doubled like any
+        // search to leave room for filtered-out results
+        let k = request.k as usize;
+        let mut limit = (k + 1) * 2;
+        let (candidates, search_mode) = loop {
+            let (mut candidates, search_mode) = self
+                .fallback_search
+                .search_with_fallback(&seed_embedding, limit, request.filters.as_ref())
+                .await
+                .map_err(|e| {
+                    error!("Vector search failed: {}", e);
+                    e
+                })?;
+            let exhausted = candidates.len() < limit;
+            candidates.retain(|candidate| candidate.post_id != request.post_id);
+            if !request.exclude_near_duplicates {
+                break (candidates, search_mode);
+            }
+
+            // Raw scores are the similarity to the seed, so copies of it can
+            // be dropped before anything else looks at the candidates
+            candidates = remove_near_duplicates(candidates, NEAR_DUPLICATE_SIMILARITY);
+            if candidates.len() >= k * 2 || exhausted || limit >= MAX_SEARCH_DEPTH {
+                break (candidates, search_mode);
+            }
+            debug!("{} candidates left after removing near-duplicates, searching deeper", candidates.len());
+            limit = (limit * 2).min(MAX_SEARCH_DEPTH);
+        };
+
+        debug!("Similar posts retrieval: {} candidates (mode: {:?})", candidates.len(), search_mode);
+        if candidates.is_empty() {
+            return Ok(Some(vec![]));
+        }
+
+        let posts = self.fetch_posts_for_candidates(&candidates).await?;
+        let mut results = self.create_search_responses(&candidates, &posts, "")?;
+
+        if let Some(filters) = &request.filters {
+            results = self.apply_filters(results, filters);
+        }
+        if let Some(min_score) = request.min_score {
+            results.retain(|result| result.score >= min_score);
+        }
+
+        sort_ranked(&mut results);
+        results.truncate(k);
+
+        info!("Similar posts search completed: {} results returned", results.len());
+        Ok(Some(results))
+    }
+
+    /// Stored embedding of a post, from the vector cache or else from Postgres
+    async fn seed_embedding(&self, post_id: &str) -> SearchResult<Option<Vec<f32>>> {
+        match self.fallback_search.cache_manager().get_vector_cache(post_id).await {
+            Ok(Some(embedding)) => return Ok(Some(embedding)),
+            Ok(None) => debug!("No cached embedding for post {}, reading it from the database", post_id),
+            Err(e) => warn!("Failed to fetch cached embedding for post {}, reading it from the database: {}", post_id, e),
+        }
+
+        let post = self.database_manager.get_post_by_id(post_id).await?;
+        Ok(post.map(|post| post.embedding).filter(|embedding| !embedding.is_empty()))
+    }
+
+    /// Run the search pipeline, sending the page before reranking to `early_results`
+    ///
+    /// `query_embedding` is used instead of embedding the query when the
+    /// caller already embedded it.
+    #[instrument(skip(self, early_results, query_embedding), fields(
         query_len = request.query.len(),
         k = request.k,
         rerank = request.rerank,
-        min_score = request.min_score
+        min_score = request.min_score,
+        mode = request.mode.as_str()
     ))]
-    pub async fn semantic_search(&self, request: SearchRequest) -> SearchResult<Vec<SearchResponse>> {
+    async fn run_search(
+        &self,
+        request: SearchRequest,
+        early_results: Option<&mpsc::Sender<SearchResult<SearchStage>>>,
+        query_embedding: Option<Vec<f32>>,
+    ) -> SearchResult<(Vec<SearchResponse>, SearchSummary)> {
         info!("Starting semantic search for query: '{}'", request.query);
 
-        // Step 1: Generate query embedding
-        debug!("Generating query embedding");
-        let query_embedding = self.ml_service.generate_embedding(&request.query).await
-            .map_err(|e| {
-                error!("Failed to generate query embedding: {}", e);
-                e
-            })?;
+        let started = Instant::now();
+        let k = request.k as usize;
+        let fingerprint = query_fingerprint(
+            &self.query_normalizer,
+            &request,
+            self.ml_service.embedding_model_id(),
+            self.fallback_search.score_normalization(),
+            &self.collection,
+        );
+        let cursor = self.resolve_cursor(&request, fingerprint)?;
+        let offset = cursor.as_ref().map_or(0, |cursor| cursor.offset as usize);
 
-        // Step 2: Perform vector search with fallback logic
-        debug!("Performing vector search");
-        let (search_candidates, search_mode) = self.fallback_search
-            .search_with_fallback(&query_embedding, request.k as usize * 2) // Get more candidates for reranking
-            .await
-            .map_err(|e| {
-                error!("Vector search failed: {}", e);
-                e
-            })?;
+        // Step 0: Serve identical first-page queries from the top-k cache
+        let cache_key = top_k_cache_key(
+            &self.query_normalizer,
+            &request,
+            self.ml_service.embedding_model_id(),
+            self.fallback_search.score_normalization(),
+        );
+        // Explained searches run the pipeline so there is something to explain
+        if cursor.is_none() && !request.explain {
+            if let Some(cached) = self.get_cached_results(cache_key).await {
+                info!("Semantic search served from top-k cache: {} results", cached.len());
+                let elapsed = elapsed_ms(started);
+                // Reranked searches are only cached when reranking succeeded
+                let summary = SearchSummary {
+                    search_mode: self.get_current_search_mode().await,
+                    reranked: request.rerank,
+                    cached: true,
+                    result_count: cached.len(),
+                    retrieval_ms: elapsed,
+                    rerank_ms: None,
+                    total_ms: elapsed,
+                };
+                return Ok((self.attach_next_cursor(cached, fingerprint, offset, k), summary));
+            }
+        }
+
+        // Steps 1-2: Retrieve candidates for the requested mode, deep enough
+        // to cover every previous page
+        let Retrieval {
+            candidates: search_candidates,
+            search_mode,
+            complete: retrieval_complete,
+            fused,
+        } = self
+            .retrieve_candidates(&request, (offset + k) * 2, query_embedding.as_deref()) // Get more candidates for reranking
+            .await?;
 
-        info!("Vector search completed: {} candidates found (mode: {:?})", 
-              search_candidates.len(), search_mode);
+        info!("Retrieval completed: {} candidates found (mode: {:?}, retrieval: {})", 
+              search_candidates.len(), search_mode, request.mode.as_str());
+
+        let mut summary = SearchSummary {
+            search_mode: search_mode.clone(),
+            reranked: false,
+            cached: false,
+            result_count: 0,
+            retrieval_ms: 0,
+            rerank_ms: None,
+            total_ms: 0,
+        };
 
         if search_candidates.is_empty() {
             info!("No search candidates found");
-            return Ok(vec![]);
+            if cursor.is_none() {
+                self.cache_results(cache_key, &[]).await;
+            }
+            summary.retrieval_ms = elapsed_ms(started);
+            summary.total_ms = summary.retrieval_ms;
+            return Ok((vec![], summary));
         }
 
         // Step 3: Fetch post metadata and create initial results
         debug!("Fetching post metadata for {} candidates", search_candidates.len());
         let posts = self.fetch_posts_for_candidates(&search_candidates).await?;
         
-        let mut search_results = self.create_search_responses(&search_candidates, &posts)?;
+        let mut search_results = self.create_search_responses(&search_candidates, &posts, &request.query)?;
 
-        // Step 4: Apply filters if specified
+        // Step 4: Re-check filters; retrieval already applied them, but posts
+        // rebuilt from cached metadata during a database outage are not guaranteed to match
         if let Some(filters) = &request.filters {
             debug!("Applying search filters");
             search_results = self.apply_filters(search_results, filters);
             info!("After filtering: {} results remain", search_results.len());
         }
 
-        // Step 5: Apply minimum score threshold if specified
-        if let Some(min_score) = request.min_score {
+        // Step 5: Apply minimum score threshold if specified; fused retrieval
+        // already applied it to each ranking before fusion
+        if let Some(min_score) = request.min_score.filter(|_| fused.is_none()) {
             debug!("Applying minimum score threshold: {}", min_score);
             let original_count = search_results.len();
             search_results.retain(|result| result.score >= min_score);
@@ -136,33 +469,340 @@ impl SearchService {
                   search_results.len(), original_count);
         }
 
+        if request.explain {
+            let explainer = match &fused {
+                Some(rankings) => Explainer::new(
+                    &rankings.vector,
+                    &rankings.lexical,
+                    Some(rankings.weight),
+                    search_mode.clone(),
+                    request.filters.as_ref(),
+                ),
+                None => Explainer::unfused(&search_candidates, search_mode.clone(), request.filters.as_ref()),
+            };
+            for result in &mut search_results {
+                result.explanation = Some(explainer.explain(&result.post_id, result.score));
+            }
+        }
+
+        summary.retrieval_ms = elapsed_ms(started);
+
         // Step 6: Perform reranking if enabled and degraded mode is not active
         let should_rerank = request.rerank && search_mode != SearchMode::Degraded;
+        // Results that skipped a requested rerank are not cached, so they expire with the outage
+        // and neither are hybrid results that lost one of their two rankings
+        let mut cacheable = retrieval_complete && (!request.rerank || should_rerank);
         if should_rerank {
+            if let Some(stages) = early_results {
+                let page = self
+                    .page(search_results.clone(), &posts, &request, cursor.as_ref(), offset, false)
+                    .await;
+                let _ = stages.send(Ok(SearchStage::Results(page))).await;
+            }
+
             debug!("Performing cross-encoder reranking");
+            let rerank_started = Instant::now();
             let original_results = search_results.clone(); // Clone for fallback
             match self.reranking_service
                 .rerank_results(&request.query, &search_results, true)
                 .await
             {
-                Ok(reranked) => {
+                Ok((reranked, scored)) => {
                     search_results = reranked;
-                    info!("Reranking completed successfully");
+                    // A degraded rerank left the retrieval scores in place
+                    if request.explain && scored {
+                        let covered = original_results.len().min(self.get_reranking_config().max_candidates_to_rerank);
+                        record_rerank_scores(&mut search_results, &original_results[..covered]);
+                    }
+                    // A reranker that degraded to the retrieval order counts as
+                    // a failure, so its results are not cached either
+                    summary.reranked = scored;
+                    cacheable &= scored;
+                    if scored {
+                        info!("Reranking completed successfully");
+                    }
                 }
                 Err(e) => {
                     warn!("Reranking failed, continuing with original scores: {}", e);
                     search_results = original_results; // Use cloned original results
+                    cacheable = false;
                 }
             }
+            summary.rerank_ms = Some(elapsed_ms(rerank_started));
         } else if request.rerank && search_mode == SearchMode::Degraded {
             warn!("Reranking requested but system is in degraded mode, skipping reranking");
         }
 
-        // Step 7: Limit results to requested number
-        search_results.truncate(request.k as usize);
+        // Step 7: Continue after the cursor and limit results to the page size
+        let search_results = self
+            .page(search_results, &posts, &request, cursor.as_ref(), offset, summary.reranked)
+            .await;
+
+        if cacheable && cursor.is_none() {
+            self.cache_results(cache_key, &search_results).await;
+        }
+
+        summary.result_count = search_results.len();
+        summary.total_ms = elapsed_ms(started);
 
         info!("Semantic search completed: {} final results returned", search_results.len());
-        Ok(search_results)
+        Ok((self.attach_next_cursor(search_results, fingerprint, offset, k), summary))
+    }
+
+    /// Order results and cut out the requested page
+    ///
+    /// Reranked results keep the reranker's order: only its first
+    /// `max_candidates_to_rerank` carry cross-encoder scores and the rest keep
+    /// their retrieval scores, so sorting by score would mix the two scales.
+    /// Like diversified ones, reranked pages are positions in that list
+    /// rather than keyset ranges.
+    async fn page(
+        &self,
+        mut search_results: Vec<SearchResponse>,
+        posts: &[Post],
+        request: &SearchRequest,
+        cursor: Option<&SearchCursor>,
+        offset: usize,
+        reranked: bool,
+    ) -> Vec<SearchResponse> {
+        let k = request.k as usize;
+
+        if !reranked {
+            sort_ranked(&mut search_results);
+        }
+        if let Some(lambda) = request.mmr_lambda {
+            // The MMR order does not follow the score, so diversified pages
+            // are positions in the diversified list rather than keyset ranges
+            debug!("Diversifying {} results with MMR (lambda: {})", search_results.len(), lambda);
+            let embeddings = self.result_embeddings(&search_results, posts).await;
+            search_results = maximal_marginal_relevance(search_results, &embeddings, lambda, offset + k);
+            search_results.drain(..offset.min(search_results.len()));
+        } else if reranked {
+            search_results.drain(..offset.min(search_results.len()));
+        } else if let Some(cursor) = cursor {
+            search_results.retain(|result| cursor.ranks_after(result.score, &result.post_id));
+        }
+        search_results.truncate(k);
+
+        search_results
+    }
+
+    /// Verify the request cursor and check that it belongs to this query
+    fn resolve_cursor(&self, request: &SearchRequest, fingerprint: u64) -> SearchResult<Option<SearchCursor>> {
+        let cursor = match request.cursor.as_deref() {
+            Some(token) => self.cursor_codec.decode(token)?,
+            None => return Ok(None),
+        };
+
+        if cursor.fingerprint != fingerprint {
+            return Err(SearchError::InvalidRequest(
+                "Cursor was issued for a different query or search parameters".to_string(),
+            ));
+        }
+
+        if cursor.offset as usize + request.k as usize > MAX_SEARCH_DEPTH {
+            return Err(SearchError::InvalidRequest(format!(
+                "Cannot page beyond the first {} results",
+                MAX_SEARCH_DEPTH
+            )));
+        }
+
+        Ok(Some(cursor))
+    }
+
+    /// Set `next_cursor` on the last result of a full page
+    ///
+    /// A short page means the ranking is exhausted, and no cursor is issued
+    /// past `MAX_SEARCH_DEPTH`.
+    fn attach_next_cursor(
+        &self,
+        mut results: Vec<SearchResponse>,
+        fingerprint: u64,
+        offset: usize,
+        k: usize,
+    ) -> Vec<SearchResponse> {
+        let next_offset = offset + results.len();
+        if results.len() < k || next_offset >= MAX_SEARCH_DEPTH {
+            return results;
+        }
+
+        if let Some(last) = results.last_mut() {
+            last.next_cursor = Some(self.cursor_codec.encode(&SearchCursor {
+                fingerprint,
+                offset: next_offset as u32,
+                last_score: last.score,
+                last_post_id: last.post_id.clone(),
+            }));
+        }
+
+        results
+    }
+
+    /// Retrieve candidates using the request's retrieval mode
+    ///
+    /// Hybrid search degrades to whichever ranking is still available when
+    /// the other one fails. When both are fused, `min_score` is applied to
+    /// each ranking's own scores before fusion.
+    async fn retrieve_candidates(
+        &self,
+        request: &SearchRequest,
+        limit: usize,
+        query_embedding: Option<&[f32]>,
+    ) -> SearchResult<Retrieval> {
+        match request.mode {
+            RetrievalMode::Vector => {
+                let (candidates, search_mode) = self
+                    .vector_candidates(&request.query, query_embedding, limit, request.filters.as_ref(), request.passage_aggregation)
+                    .await?;
+                Ok(Retrieval::unfused(candidates, search_mode, true))
+            }
+            RetrievalMode::Lexical => {
+                let candidates = self.lexical_candidates(&request.query, limit, request.filters.as_ref()).await?;
+                Ok(Retrieval::unfused(candidates, self.get_current_search_mode().await, true))
+            }
+            RetrievalMode::Hybrid => {
+                let (vector_result, lexical_result) = tokio::join!(
+                    self.vector_candidates(&request.query, query_embedding, limit, request.filters.as_ref(), request.passage_aggregation),
+                    self.lexical_candidates(&request.query, limit, request.filters.as_ref())
+                );
+
+                match (vector_result, lexical_result) {
+                    (Ok((mut vector, search_mode)), Ok(mut lexical)) => {
+                        // Fused scores are rank-based, so the threshold applies to each ranking's own scores
+                        if let Some(min_score) = request.min_score {
+                            vector.retain(|candidate| candidate.score >= min_score);
+                            lexical.retain(|candidate| candidate.score >= min_score);
+                        }
+                        let weight = request.fusion_weight.unwrap_or(DEFAULT_VECTOR_WEIGHT);
+                        let mut fused = reciprocal_rank_fusion(&vector, &lexical, weight);
+                        fused.truncate(limit);
+                        debug!(
+                            "Fused {} vector and {} lexical candidates into {} (vector weight: {})",
+                            vector.len(), lexical.len(), fused.len(), weight
+                        );
+                        Ok(Retrieval {
+                            candidates: fused,
+                            search_mode,
+                            complete: true,
+                            fused: Some(FusedRankings { vector, lexical, weight }),
+                        })
+                    }
+                    (Ok((vector, search_mode)), Err(e)) => {
+                        warn!("Full-text search failed, continuing with vector candidates only: {}", e);
+                        Ok(Retrieval::unfused(vector, search_mode, false))
+                    }
+                    (Err(e), Ok(lexical)) => {
+                        warn!("Vector search failed, continuing with full-text candidates only: {}", e);
+                        Ok(Retrieval::unfused(lexical, self.get_current_search_mode().await, false))
+                    }
+                    (Err(e), Err(_)) => Err(e),
+                }
+            }
+        }
+    }
+
+    /// Embed the query, unless it is already embedded, and run vector search with fallback logic
+    ///
+    /// With a passage aggregation the query is matched against post passages
+    /// instead of whole posts, and the hits are aggregated into one candidate
+    /// per post carrying its best passage.
+    async fn vector_candidates(
+        &self,
+        query: &str,
+        query_embedding: Option<&[f32]>,
+        limit: usize,
+        filters: Option<&SearchFilters>,
+        passage_aggregation: Option<PassageAggregation>,
+    ) -> SearchResult<(Vec<SearchCandidate>, SearchMode)> {
+        let generated;
+        let query_embedding = match query_embedding {
+            Some(embedding) => embedding,
+            None => {
+                debug!("Generating query embedding");
+                generated = self.ml_service.generate_embedding(query).await
+                    .map_err(|e| {
+                        error!("Failed to generate query embedding: {}", e);
+                        e
+                    })?;
+                &generated
+            }
+        };
+
+        if let Some(aggregation) = passage_aggregation {
+            // Several passages of one post can match, so fetch extra hits to
+            // still end up with `limit` distinct posts
+            debug!("Performing passage search (aggregation: {})", aggregation.as_str());
+            let (hits, search_mode) = self.fallback_search
+                .passage_search_with_fallback(query_embedding, limit * PASSAGE_HITS_PER_POST, filters)
+                .await
+                .map_err(|e| {
+                    error!("Passage search failed: {}", e);
+                    e
+                })?;
+
+            let mut candidates = aggregate_passages(hits, aggregation);
+            candidates.truncate(limit);
+            return Ok((candidates, search_mode));
+        }
+
+        debug!("Performing vector search");
+        self.fallback_search
+            .search_with_fallback(query_embedding, limit, filters)
+            .await
+            .map_err(|e| {
+                error!("Vector search failed: {}", e);
+                e
+            })
+    }
+
+    /// Run Postgres full-text search
+    async fn lexical_candidates(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: Option<&SearchFilters>,
+    ) -> SearchResult<Vec<SearchCandidate>> {
+        debug!("Performing full-text search");
+        self.database_manager
+            .lexical_search(query, limit, filters)
+            .await
+            .map_err(|e| {
+                error!("Full-text search failed: {}", e);
+                e
+            })
+    }
+
+    /// Look up cached results, treating cache errors as misses
+    async fn get_cached_results(&self, cache_key: u64) -> Option<Vec<SearchResponse>> {
+        let cache_manager = self.fallback_search.cache_manager();
+
+        let cached = match cache_manager.get_top_k_cache(cache_key).await {
+            Ok(cached) => cached,
+            Err(e) => {
+                warn!("Top-k cache lookup failed, searching without cache: {}", e);
+                None
+            }
+        };
+
+        if let Some(metrics) = &self.metrics {
+            if cached.is_some() {
+                metrics.cache_hits_total.inc();
+            } else {
+                metrics.cache_misses_total.inc();
+            }
+            metrics.redis_hit_topk_ratio.set(cache_manager.get_cache_stats().topk_hit_ratio());
+        }
+
+        cached.map(|results| results.into_iter().map(SearchResponse::from).collect())
+    }
+
+    /// Store final results in the top-k cache; failures only cost a future cache miss
+    async fn cache_results(&self, cache_key: u64, results: &[SearchResponse]) {
+        let cached: Vec<CachedResult> = results.iter().map(CachedResult::from_response).collect();
+
+        if let Err(e) = self.fallback_search.cache_manager().set_top_k_cache(cache_key, &cached).await {
+            warn!("Failed to cache search results: {}", e);
+        }
     }
 
     /// Fetch posts for the given search candidates with metadata backfill from cache
@@ -209,6 +849,7 @@ impl SearchService {
                         frozen: metadata.frozen,
                         date_gmt: metadata.date,
                         url: metadata.url.clone(),
+                        metadata: metadata.metadata.clone(),
                         embedding: Vec::new(), // Empty embedding for cache-only posts
                     };
                     posts.push(post);
@@ -241,6 +882,7 @@ impl SearchService {
                             date: posts.last().unwrap().date_gmt,
                             language: posts.last().unwrap().language.clone(),
                             frozen: posts.last().unwrap().frozen,
+                            metadata: posts.last().unwrap().metadata.clone(),
                         };
                         
                         if let Err(e) = self.fallback_search.cache_manager().set_metadata_cache(post_id, &metadata).await {
@@ -261,19 +903,70 @@ impl SearchService {
         Ok(posts)
     }
 
+    /// Collect embeddings of the given results for MMR diversification
+    ///
+    /// Posts rebuilt from cached metadata have no embedding; those are read
+    /// from the Redis vector cache in one pipelined lookup, and results missing
+    /// from both are left out.
+    async fn result_embeddings(&self, results: &[SearchResponse], posts: &[Post]) -> HashMap<String, Vec<f32>> {
+        let mut embeddings: HashMap<String, Vec<f32>> = posts
+            .iter()
+            .filter(|post| !post.embedding.is_empty())
+            .map(|post| (post.post_id.clone(), post.embedding.clone()))
+            .collect();
+
+        let missing: Vec<String> = results
+            .iter()
+            .filter(|result| !embeddings.contains_key(&result.post_id))
+            .map(|result| result.post_id.clone())
+            .collect();
+        if missing.is_empty() {
+            return embeddings;
+        }
+
+        match self.fallback_search.cache_manager().get_vector_caches(&missing).await {
+            Ok(cached) => {
+                if cached.len() < missing.len() {
+                    debug!("No embedding available for {} posts", missing.len() - cached.len());
+                }
+                embeddings.extend(cached);
+            }
+            Err(e) => warn!("Failed to fetch cached embeddings for {} posts: {}", missing.len(), e),
+        }
+
+        embeddings
+    }
+
     /// Create search responses from candidates and posts
+    ///
+    /// Snippets are centred on the part of the post most relevant to the query.
     fn create_search_responses(
         &self,
         candidates: &[SearchCandidate],
         posts: &[Post],
+        query: &str,
     ) -> SearchResult<Vec<SearchResponse>> {
         debug!("Creating search responses for {} candidates", candidates.len());
 
+        let terms = query_terms(query);
         let mut results = Vec::new();
         
         for candidate in candidates {
             if let Some(post) = posts.iter().find(|p| p.post_id == candidate.post_id) {
-                let search_response = post.to_search_response(candidate.score);
+                let mut search_response = post.to_search_response(candidate.score);
+
+                // Prefer the matching passage; posts rebuilt from cached metadata
+                // (no embedding) only have placeholder content to slice
+                let text = candidate
+                    .passage
+                    .filter(|_| !post.embedding.is_empty())
+                    .and_then(|span| post.passage_text(&span))
+                    .unwrap_or(&post.content);
+
+                let snippet = extract_snippet(text, &terms);
+                search_response.snippet = snippet.text;
+                search_response.highlights = snippet.highlights;
+
                 results.push(search_response);
             } else {
                 warn!("Post not found for candidate: {}", candidate.post_id);
@@ -289,30 +982,9 @@ impl SearchService {
         let original_count = results.len();
         debug!("Applying filters to {} results", original_count);
 
-        // Apply language filter
-        if let Some(language) = &filters.language {
-            let before_count = results.len();
-            results.retain(|result| {
-                // Case-insensitive language matching for better compatibility
-                result.meta.language.to_lowercase() == language.to_lowercase()
-            });
-            debug!("Language filter '{}' applied: {} -> {} results", 
-                   language, before_count, results.len());
-        }
-
-        // Apply frozen filter
-        if let Some(frozen) = filters.frozen {
-            let before_count = results.len();
-            results.retain(|result| result.meta.frozen == frozen);
-            debug!("Frozen filter '{}' applied: {} -> {} results", 
-                   frozen, before_count, results.len());
-            
-            // Log specific filtering behavior for GDPR compliance
-            if !frozen {
-                debug!("Excluded {} frozen posts for GDPR compliance", 
-                       before_count - results.len());
-            }
-        }
+        // Evaluate the full filter expression (language, frozen, dates,
+        // authors, URLs and nested combinators) against each post
+        results.retain(|result| filters.matches(&result.meta));
 
         let final_count = results.len();
         if final_count != original_count {
@@ -363,10 +1035,46 @@ impl SearchService {
             current_search_mode: current_mode,
             reranking_config,
             reranking_available: self.is_reranking_available(),
+            cache_stats: self.fallback_search.cache_manager().get_cache_stats(),
+            score_normalization: self.fallback_search.score_normalization(),
         })
     }
 }
 
+/// Candidates retrieved for one search
+struct Retrieval {
+    /// Candidates in retrieval order
+    candidates: Vec<SearchCandidate>,
+    /// Fallback search mode the candidates were retrieved in
+    search_mode: SearchMode,
+    /// Whether every retrieval path succeeded
+    complete: bool,
+    /// Rankings the candidates were fused from in hybrid search
+    fused: Option<FusedRankings>,
+}
+
+impl Retrieval {
+    /// Candidates taken from a single ranking
+    fn unfused(candidates: Vec<SearchCandidate>, search_mode: SearchMode, complete: bool) -> Self {
+        Self {
+            candidates,
+            search_mode,
+            complete,
+            fused: None,
+        }
+    }
+}
+
+/// Vector and full-text rankings merged by hybrid search
+struct FusedRankings {
+    /// Vector ranking
+    vector: Vec<SearchCandidate>,
+    /// Full-text ranking
+    lexical: Vec<SearchCandidate>,
+    /// Weight of the vector ranking
+    weight: f32,
+}
+
 /// Health status for the complete search service
 #[derive(Debug, Clone)]
 pub struct SearchServiceHealth {
@@ -383,6 +1091,106 @@ pub struct SearchServiceStats {
     pub current_search_mode: SearchMode,
     pub reranking_config: RerankingConfig,
     pub reranking_available: bool,
+    pub cache_stats: CacheStats,
+    pub score_normalization: ScoreNormalization,
+}
+
+/// Build the top-k cache key for a request
+///
+/// Everything that changes the returned results is part of the key: the
+/// normalized query, k, min_score, filters, rerank flag, MMR weight,
+/// embedding model and the score normalization that decides merges.
+fn top_k_cache_key(
+    normalizer: &TokenizerService,
+    request: &SearchRequest,
+    model_id: &str,
+    score_normalization: ScoreNormalization,
+) -> u64 {
+    let params = cache_key_params(request, model_id, score_normalization);
+    normalizer.generate_cache_key_with_params(&request.query, request.k, request.min_score, &params)
+}
+
+/// Parameters besides the query, k and min_score that change a request's results
+fn cache_key_params(
+    request: &SearchRequest,
+    model_id: &str,
+    score_normalization: ScoreNormalization,
+) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+
+    if let Some(filters) = &request.filters {
+        if let Some(language) = &filters.language {
+            params.insert("language".to_string(), language.to_lowercase());
+        }
+        if let Some(frozen) = filters.frozen {
+            params.insert("frozen".to_string(), frozen.to_string());
+        }
+
+        // Dates, authors, URLs, metadata and nested combinators
+        let rest = SearchFilters {
+            language: None,
+            frozen: None,
+            ..filters.clone()
+        };
+        if !rest.is_empty() {
+            if let Ok(expression) = serde_json::to_string(&rest) {
+                params.insert("filters".to_string(), expression);
+            }
+        }
+    }
+    params.insert("rerank".to_string(), request.rerank.to_string());
+    params.insert("model".to_string(), model_id.to_string());
+    params.insert("normalization".to_string(), score_normalization.as_str().to_string());
+    params.insert("mode".to_string(), request.mode.as_str().to_string());
+    if request.mode == RetrievalMode::Hybrid {
+        let weight = request.fusion_weight.unwrap_or(DEFAULT_VECTOR_WEIGHT);
+        params.insert("fusion_weight".to_string(), format!("{:.3}", weight));
+    }
+    if let Some(lambda) = request.mmr_lambda {
+        params.insert("mmr_lambda".to_string(), format!("{:.3}", lambda));
+    }
+    if let Some(aggregation) = request.passage_aggregation {
+        if request.mode != RetrievalMode::Lexical {
+            params.insert("passages".to_string(), aggregation.as_str());
+        }
+    }
+
+    params
+}
+
+/// Passage hits requested per wanted post in passage-level search
+const PASSAGE_HITS_PER_POST: usize = 4;
+
+/// Fingerprint shared by every page of one query
+///
+/// Covers the same normalized query and parameters as the top-k cache key,
+/// except the page size and cursor, plus the collection: collections share
+/// the cursor secret, and top-k entries are already kept apart by key prefix.
+fn query_fingerprint(
+    normalizer: &TokenizerService,
+    request: &SearchRequest,
+    model_id: &str,
+    score_normalization: ScoreNormalization,
+    collection: &str,
+) -> u64 {
+    let mut params = cache_key_params(request, model_id, score_normalization);
+    params.insert("collection".to_string(), collection.to_string());
+    normalizer.generate_cache_key_with_params(&request.query, 0, request.min_score, &params)
+}
+
+/// Milliseconds since `started`
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+/// Order results by score, breaking ties by post_id so pages are stable
+fn sort_ranked(results: &mut [SearchResponse]) {
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.post_id.cmp(&b.post_id))
+    });
 }
 
 #[cfg(test)]
@@ -398,6 +1206,7 @@ mod tests {
                 post_id: "post1".to_string(),
                 title: "English Post".to_string(),
                 snippet: "This is an English post".to_string(),
+                highlights: Vec::new(),
                 score: 0.9,
                 meta: PostMetadata {
                     author_name: "Author 1".to_string(),
@@ -405,12 +1214,16 @@ mod tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: false,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
             SearchResponse {
                 post_id: "post2".to_string(),
                 title: "Spanish Post".to_string(),
                 snippet: "Este es un post en español".to_string(),
+                highlights: Vec::new(),
                 score: 0.8,
                 meta: PostMetadata {
                     author_name: "Author 2".to_string(),
@@ -418,12 +1231,16 @@ mod tests {
                     date: Utc::now(),
                     language: "es".to_string(),
                     frozen: true,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
             SearchResponse {
                 post_id: "post3".to_string(),
                 title: "Another English Post".to_string(),
                 snippet: "Another English post that is frozen".to_string(),
+                highlights: Vec::new(),
                 score: 0.7,
                 meta: PostMetadata {
                     author_name: "Author 3".to_string(),
@@ -431,7 +1248,10 @@ mod tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: true,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
         ]
     }
@@ -446,6 +1266,7 @@ mod tests {
         let filters = SearchFilters {
             language: Some("en".to_string()),
             frozen: None,
+            ..Default::default()
         };
         
         let filtered: Vec<SearchResponse> = results
@@ -471,6 +1292,7 @@ mod tests {
         let filters = SearchFilters {
             language: None,
             frozen: Some(false),
+            ..Default::default()
         };
         
         let filtered: Vec<SearchResponse> = results
@@ -496,6 +1318,7 @@ mod tests {
         let filters = SearchFilters {
             language: Some("en".to_string()),
             frozen: Some(false),
+            ..Default::default()
         };
         
         let filtered: Vec<SearchResponse> = results
@@ -576,9 +1399,12 @@ mod tests {
             current_search_mode: SearchMode::Full,
             reranking_config: RerankingConfig::default(),
             reranking_available: true,
+            cache_stats: CacheStats::default(),
+            score_normalization: ScoreNormalization::MinMax,
         };
         
         assert_eq!(stats.current_search_mode, SearchMode::Full);
+        assert_eq!(stats.score_normalization.as_str(), "min_max");
         assert!(stats.reranking_available);
         assert_eq!(stats.reranking_config.max_candidates_to_rerank, 50);
     }
@@ -591,6 +1417,7 @@ mod tests {
         let filters = SearchFilters {
             language: Some("EN".to_string()), // Uppercase
             frozen: None,
+            ..Default::default()
         };
         
         // Simulate the filtering logic
@@ -617,6 +1444,7 @@ mod tests {
         let filters = SearchFilters {
             language: None,
             frozen: Some(false),
+            ..Default::default()
         };
         
         let filtered: Vec<SearchResponse> = results
@@ -643,6 +1471,7 @@ mod tests {
         let filters = SearchFilters {
             language: Some("es".to_string()),
             frozen: Some(true),
+            ..Default::default()
         };
         
         let filtered: Vec<SearchResponse> = results
@@ -678,6 +1507,7 @@ mod tests {
         let filters = SearchFilters {
             language: Some("fr".to_string()), // No French posts
             frozen: None,
+            ..Default::default()
         };
         
         let filtered: Vec<SearchResponse> = results
@@ -701,6 +1531,7 @@ mod tests {
         let filters = SearchFilters {
             language: Some("en".to_string()),
             frozen: Some(false),
+            ..Default::default()
         };
         
         let filtered: Vec<SearchResponse> = results
@@ -724,4 +1555,130 @@ mod tests {
         
         assert_eq!(filtered.len(), 0); // Should remain empty
     }
-}
\ No newline at end of file
+
+    fn cache_key_request(query: &str) -> SearchRequest {
+        SearchRequest {
+            query: query.to_string(),
+            k: 10,
+            min_score: Some(0.5),
+            rerank: false,
+            filters: None,
+            mode: RetrievalMode::Vector,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: None,
+            cursor: None,
+            explain: false,
+            collection: None,
+        }
+    }
+
+    #[test]
+    fn test_top_k_cache_key_normalizes_query() {
+        let normalizer = TokenizerService::new_sync().unwrap();
+
+        let key1 = top_k_cache_key(&normalizer, &cache_key_request("Rust  Programming"), "all-MiniLM-L6-v2", ScoreNormalization::None);
+        let key2 = top_k_cache_key(&normalizer, &cache_key_request("  rust programming "), "all-MiniLM-L6-v2", ScoreNormalization::None);
+        assert_eq!(key1, key2);
+    }
+
+    #[test]
+    fn test_query_fingerprint_ignores_page_size() {
+        let normalizer = TokenizerService::new_sync().unwrap();
+        let first = cache_key_request("rust programming");
+
+        let mut next = first.clone();
+        next.k = 25;
+        next.cursor = Some("00".to_string());
+        assert_eq!(
+            query_fingerprint(&normalizer, &first, "all-MiniLM-L6-v2", ScoreNormalization::None, DEFAULT_COLLECTION),
+            query_fingerprint(&normalizer, &next, "all-MiniLM-L6-v2", ScoreNormalization::None, DEFAULT_COLLECTION)
+        );
+
+        next.rerank = !first.rerank;
+        assert_ne!(
+            query_fingerprint(&normalizer, &first, "all-MiniLM-L6-v2", ScoreNormalization::None, DEFAULT_COLLECTION),
+            query_fingerprint(&normalizer, &next, "all-MiniLM-L6-v2", ScoreNormalization::None, DEFAULT_COLLECTION)
+        );
+
+        // A cursor issued by one collection is rejected by another on the same model
+        assert_ne!(
+            query_fingerprint(&normalizer, &first, "all-MiniLM-L6-v2", ScoreNormalization::None, DEFAULT_COLLECTION),
+            query_fingerprint(&normalizer, &first, "all-MiniLM-L6-v2", ScoreNormalization::None, "docs")
+        );
+    }
+
+    #[test]
+    fn test_sort_ranked_breaks_ties_by_post_id() {
+        let mut results = create_test_results_for_filtering();
+        for result in &mut results {
+            result.score = 0.5;
+        }
+        results.reverse();
+
+        sort_ranked(&mut results);
+        let order: Vec<&str> = results.iter().map(|r| r.post_id.as_str()).collect();
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(order, sorted);
+    }
+
+    #[test]
+    fn test_top_k_cache_key_covers_parameters() {
+        let normalizer = TokenizerService::new_sync().unwrap();
+        let base = cache_key_request("rust programming");
+        let base_key = top_k_cache_key(&normalizer, &base, "all-MiniLM-L6-v2", ScoreNormalization::None);
+
+        let mut reranked = base.clone();
+        reranked.rerank = true;
+        assert_ne!(base_key, top_k_cache_key(&normalizer, &reranked, "all-MiniLM-L6-v2", ScoreNormalization::None));
+
+        let mut filtered = base.clone();
+        filtered.filters = Some(SearchFilters {
+            language: Some("en".to_string()),
+            frozen: Some(false),
+            ..Default::default()
+        });
+        assert_ne!(base_key, top_k_cache_key(&normalizer, &filtered, "all-MiniLM-L6-v2", ScoreNormalization::None));
+
+        let mut by_author = filtered.clone();
+        by_author.filters.as_mut().unwrap().authors = Some(vec!["Ada".to_string()]);
+        let author_key = top_k_cache_key(&normalizer, &by_author, "all-MiniLM-L6-v2", ScoreNormalization::None);
+        assert_ne!(top_k_cache_key(&normalizer, &filtered, "all-MiniLM-L6-v2", ScoreNormalization::None), author_key);
+
+        by_author.filters.as_mut().unwrap().authors = Some(vec!["Bob".to_string()]);
+        assert_ne!(author_key, top_k_cache_key(&normalizer, &by_author, "all-MiniLM-L6-v2", ScoreNormalization::None));
+
+        let mut larger_k = base.clone();
+        larger_k.k = 20;
+        assert_ne!(base_key, top_k_cache_key(&normalizer, &larger_k, "all-MiniLM-L6-v2", ScoreNormalization::None));
+
+        assert_ne!(base_key, top_k_cache_key(&normalizer, &base, "bert-base-nli-mean-tokens", ScoreNormalization::None));
+        assert_ne!(base_key, top_k_cache_key(&normalizer, &base, "all-MiniLM-L6-v2", ScoreNormalization::MinMax));
+
+        let mut hybrid = base.clone();
+        hybrid.mode = RetrievalMode::Hybrid;
+        let hybrid_key = top_k_cache_key(&normalizer, &hybrid, "all-MiniLM-L6-v2", ScoreNormalization::None);
+        assert_ne!(base_key, hybrid_key);
+
+        // The default fusion weight and an explicit 0.5 share a cache entry
+        hybrid.fusion_weight = Some(0.5);
+        assert_eq!(hybrid_key, top_k_cache_key(&normalizer, &hybrid, "all-MiniLM-L6-v2", ScoreNormalization::None));
+        hybrid.fusion_weight = Some(0.8);
+        assert_ne!(hybrid_key, top_k_cache_key(&normalizer, &hybrid, "all-MiniLM-L6-v2", ScoreNormalization::None));
+
+        let mut diversified = base.clone();
+        diversified.mmr_lambda = Some(0.7);
+        let diversified_key = top_k_cache_key(&normalizer, &diversified, "all-MiniLM-L6-v2", ScoreNormalization::None);
+        assert_ne!(base_key, diversified_key);
+        diversified.mmr_lambda = Some(0.3);
+        assert_ne!(diversified_key, top_k_cache_key(&normalizer, &diversified, "all-MiniLM-L6-v2", ScoreNormalization::None));
+
+        let mut passages = base.clone();
+        passages.passage_aggregation = Some(PassageAggregation::Max);
+        let passages_key = top_k_cache_key(&normalizer, &passages, "all-MiniLM-L6-v2", ScoreNormalization::None);
+        assert_ne!(base_key, passages_key);
+        passages.passage_aggregation = Some(PassageAggregation::MeanTopN(3));
+        assert_ne!(passages_key, top_k_cache_key(&normalizer, &passages, "all-MiniLM-L6-v2", ScoreNormalization::None));
+    }
+}
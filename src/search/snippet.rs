@@ -0,0 +1,311 @@
+//! Query-aware snippet extraction
+//!
+//! Instead of always showing the start of a post, the snippet is a window of
+//! at most `MAX_SNIPPET_CHARS` characters centred on the sentence sharing the
+//! most terms with the query. Matched terms are returned as highlight spans,
+//! in UTF-16 code units, so clients can emphasize them.
+
+use crate::types::{HighlightSpan, MAX_SNIPPET_CHARS};
+use std::collections::HashSet;
+
+/// Marker for text cut from either side of a snippet
+const ELLIPSIS: &str = "...";
+
+/// Words too common to be worth matching or highlighting
+const STOPWORDS: &[&str] = &[
+    "an", "and", "are", "as", "at", "be", "by", "for", "from", "how", "in", "is", "it", "of", "on", "or",
+    "that", "the", "this", "to", "was", "what", "when", "where", "which", "who", "why", "with",
+];
+
+/// Snippet text with the character ranges of matched query terms
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    /// Snippet text, at most `MAX_SNIPPET_CHARS` characters
+    pub text: String,
+    /// Matched terms as UTF-16 offsets into `text`
+    pub highlights: Vec<HighlightSpan>,
+}
+
+/// Lowercased distinct query terms, without stopwords and single characters
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    words(query)
+        .into_iter()
+        .map(|(_, _, word)| word)
+        .filter(|word| word.chars().count() > 1 && !STOPWORDS.contains(&word.as_str()))
+        .filter(|word| seen.insert(word.clone()))
+        .collect()
+}
+
+/// Extract the snippet of `content` most relevant to the query terms
+///
+/// Content that fits is returned whole. Otherwise the window covers the
+/// best-matching sentence (or, for a sentence longer than the window, its
+/// first match) with surrounding context, cut at word boundaries and marked
+/// with ellipses. Without any match the snippet is the start of the content.
+pub fn extract_snippet(content: &str, terms: &[String]) -> Snippet {
+    let content = content.trim();
+    let chars: Vec<char> = content.chars().collect();
+
+    if chars.len() <= MAX_SNIPPET_CHARS {
+        return highlighted(content.to_string(), terms);
+    }
+
+    let budget = MAX_SNIPPET_CHARS - 2 * ELLIPSIS.len();
+    let (focus_start, focus_end) = focus_range(&chars, terms, budget);
+
+    // Centre the focus in the window, shifting it back inside the content
+    let padding = budget.saturating_sub(focus_end - focus_start) / 2;
+    let end = (focus_start.saturating_sub(padding) + budget).min(chars.len());
+    let start = end.saturating_sub(budget);
+
+    let (start, end) = snap_to_words(&chars, start, end);
+    let body: String = chars[start..end].iter().collect();
+
+    let mut text = String::with_capacity(body.len() + 2 * ELLIPSIS.len());
+    if start > 0 {
+        text.push_str(ELLIPSIS);
+    }
+    text.push_str(body.trim());
+    if end < chars.len() {
+        text.push_str(ELLIPSIS);
+    }
+
+    highlighted(text, terms)
+}
+
+/// Character range the window should be centred on
+fn focus_range(chars: &[char], terms: &[String], budget: usize) -> (usize, usize) {
+    let text: String = chars.iter().collect();
+    let words = words(&text);
+
+    let mut best: Option<(usize, (usize, usize))> = None;
+    for (sentence_start, sentence_end) in sentences(chars) {
+        let matched: HashSet<&str> = words
+            .iter()
+            .filter(|(start, end, _)| *start >= sentence_start && *end <= sentence_end)
+            .filter_map(|(_, _, word)| matching_term(word, terms))
+            .collect();
+
+        if matched.len() > best.map_or(0, |(count, _)| count) {
+            best = Some((matched.len(), (sentence_start, sentence_end)));
+        }
+    }
+
+    let (sentence_start, sentence_end) = match best {
+        Some((_, sentence)) => sentence,
+        None => return (0, 0),
+    };
+
+    if sentence_end - sentence_start <= budget {
+        return (sentence_start, sentence_end);
+    }
+
+    // Too long to show whole; centre on its first match instead
+    words
+        .iter()
+        .find(|(start, end, word)| {
+            *start >= sentence_start && *end <= sentence_end && matching_term(word, terms).is_some()
+        })
+        .map_or((sentence_start, sentence_start), |(start, end, _)| (*start, *end))
+}
+
+/// Character ranges of the sentences in `chars`
+///
+/// Sentences end after `.`, `!` or `?` followed by whitespace, and at line breaks.
+fn sentences(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+
+    for (i, &ch) in chars.iter().enumerate() {
+        let terminal =
+            matches!(ch, '.' | '!' | '?') && (i + 1 == chars.len() || chars[i + 1].is_whitespace());
+        if ch == '\n' || terminal {
+            let end = if ch == '\n' { i } else { i + 1 };
+            if end > start {
+                sentences.push((start, end));
+            }
+            start = i + 1;
+        }
+    }
+
+    if start < chars.len() {
+        sentences.push((start, chars.len()));
+    }
+
+    sentences
+}
+
+/// Move the window edges inward so no word is cut in half
+///
+/// A window without any whitespace to cut at is kept as is.
+fn snap_to_words(chars: &[char], start: usize, end: usize) -> (usize, usize) {
+    let mut snapped_start = start;
+    if start > 0 && !chars[start - 1].is_whitespace() {
+        while snapped_start < end && !chars[snapped_start].is_whitespace() {
+            snapped_start += 1;
+        }
+    }
+
+    let mut snapped_end = end;
+    if end < chars.len() && !chars[end].is_whitespace() {
+        while snapped_end > snapped_start && !chars[snapped_end - 1].is_whitespace() {
+            snapped_end -= 1;
+        }
+    }
+
+    if snapped_start >= snapped_end {
+        (start, end)
+    } else {
+        (snapped_start, snapped_end)
+    }
+}
+
+/// Attach highlight spans for every word of `text` matching a term
+///
+/// Words are found by character; the spans are converted to UTF-16 offsets.
+fn highlighted(text: String, terms: &[String]) -> Snippet {
+    // UTF-16 offset of every character boundary, including the end of `text`
+    let utf16_offsets: Vec<u32> = std::iter::once(0)
+        .chain(text.chars().scan(0, |offset, ch| {
+            *offset += ch.len_utf16() as u32;
+            Some(*offset)
+        }))
+        .collect();
+
+    let highlights = words(&text)
+        .into_iter()
+        .filter(|(_, _, word)| matching_term(word, terms).is_some())
+        .map(|(start, end, _)| HighlightSpan {
+            start: utf16_offsets[start],
+            end: utf16_offsets[end],
+        })
+        .collect();
+
+    Snippet { text, highlights }
+}
+
+/// Term matched by a lowercased word
+///
+/// Terms of four or more characters also match as a prefix, so `index`
+/// highlights `indexes` and `indexing`.
+fn matching_term<'a>(word: &str, terms: &'a [String]) -> Option<&'a str> {
+    terms
+        .iter()
+        .find(|term| word == term.as_str() || (term.chars().count() >= 4 && word.starts_with(term.as_str())))
+        .map(String::as_str)
+}
+
+/// Alphanumeric words of `text` as (start, end, lowercased word), in characters
+fn words(text: &str) -> Vec<(usize, usize, String)> {
+    let mut words = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (i, ch) in text.chars().enumerate() {
+        if ch.is_alphanumeric() {
+            current.get_or_insert_with(|| (i, String::new())).1.extend(ch.to_lowercase());
+        } else if let Some((start, word)) = current.take() {
+            words.push((start, i, word));
+        }
+    }
+
+    if let Some((start, word)) = current {
+        words.push((start, text.chars().count(), word));
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(query: &str) -> Vec<String> {
+        query_terms(query)
+    }
+
+    fn highlighted_words(snippet: &Snippet) -> Vec<String> {
+        let units: Vec<u16> = snippet.text.encode_utf16().collect();
+        snippet
+            .highlights
+            .iter()
+            .map(|span| String::from_utf16(&units[span.start as usize..span.end as usize]).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_query_terms() {
+        assert_eq!(terms("What is the Borrow checker? borrow"), vec!["borrow", "checker"]);
+        assert!(terms("a to the").is_empty());
+    }
+
+    #[test]
+    fn test_short_content_is_highlighted_in_place() {
+        let snippet = extract_snippet("  Rust's borrow checker prevents data races.  ", &terms("borrow checker"));
+        assert_eq!(snippet.text, "Rust's borrow checker prevents data races.");
+        assert_eq!(
+            snippet.highlights,
+            vec![HighlightSpan { start: 7, end: 13 }, HighlightSpan { start: 14, end: 21 }]
+        );
+    }
+
+    #[test]
+    fn test_highlights_use_utf16_offsets() {
+        // The emoji is one character but two UTF-16 code units
+        let snippet = extract_snippet("\u{1F980} café borrow", &terms("borrow"));
+        assert_eq!(snippet.highlights, vec![HighlightSpan { start: 8, end: 14 }]);
+        assert_eq!(highlighted_words(&snippet), vec!["borrow"]);
+    }
+
+    #[test]
+    fn test_snippet_centres_on_matching_sentence() {
+        let filler = "Unrelated introduction text goes here. ".repeat(20);
+        let content = format!(
+            "{}The borrow checker enforces ownership rules at compile time. {}",
+            filler, filler
+        );
+
+        let snippet = extract_snippet(&content, &terms("borrow checker ownership"));
+        assert!(snippet.text.chars().count() <= MAX_SNIPPET_CHARS);
+        assert!(snippet.text.starts_with(ELLIPSIS));
+        assert!(snippet.text.ends_with(ELLIPSIS));
+        assert!(snippet.text.contains("The borrow checker enforces ownership rules at compile time."));
+        assert_eq!(highlighted_words(&snippet), vec!["borrow", "checker", "ownership"]);
+    }
+
+    #[test]
+    fn test_snippet_without_match_starts_at_beginning() {
+        let content = "First sentence here. ".repeat(30);
+        let snippet = extract_snippet(&content, &terms("kubernetes"));
+        assert!(snippet.text.starts_with("First sentence here."));
+        assert!(snippet.text.ends_with(ELLIPSIS));
+        assert!(snippet.highlights.is_empty());
+    }
+
+    #[test]
+    fn test_snippet_counts_characters_not_bytes() {
+        // Multibyte characters around the match must neither panic nor be split
+        let content = format!("{} índice {}", "ñandú ".repeat(80), "pingüino ".repeat(80));
+        let snippet = extract_snippet(&content, &terms("índice"));
+
+        assert!(snippet.text.chars().count() <= MAX_SNIPPET_CHARS);
+        assert!(snippet.text.len() > MAX_SNIPPET_CHARS);
+        assert_eq!(highlighted_words(&snippet), vec!["índice"]);
+    }
+
+    #[test]
+    fn test_long_sentence_centres_on_first_match() {
+        let content = format!("{} needle {}", "hay ".repeat(200), "hay ".repeat(200));
+        let snippet = extract_snippet(&content, &terms("needle"));
+
+        assert!(snippet.text.contains("needle"));
+        assert!(snippet.text.starts_with(ELLIPSIS));
+        assert_eq!(snippet.highlights.len(), 1);
+    }
+
+    #[test]
+    fn test_prefix_matching() {
+        let snippet = extract_snippet("Indexes and indexing, but not indie.", &terms("index"));
+        assert_eq!(highlighted_words(&snippet), vec!["Indexes", "indexing"]);
+    }
+}
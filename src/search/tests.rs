@@ -93,16 +93,22 @@ fn create_test_candidates() -> (Vec<SearchCandidate>, Vec<SearchCandidate>) {
             post_id: "post1".to_string(),
             score: 0.95,
             source: SearchSource::Redis,
+            passage: None,
+            merged_from: Vec::new(),
         },
         SearchCandidate {
             post_id: "post2".to_string(),
             score: 0.85,
             source: SearchSource::Redis,
+            passage: None,
+            merged_from: Vec::new(),
         },
         SearchCandidate {
             post_id: "post3".to_string(),
             score: 0.75,
             source: SearchSource::Redis,
+            passage: None,
+            merged_from: Vec::new(),
         },
     ];
 
@@ -111,16 +117,22 @@ fn create_test_candidates() -> (Vec<SearchCandidate>, Vec<SearchCandidate>) {
             post_id: "post1".to_string(), // Duplicate with different score
             score: 0.90,
             source: SearchSource::Postgres,
+            passage: None,
+            merged_from: Vec::new(),
         },
         SearchCandidate {
             post_id: "post4".to_string(),
             score: 0.80,
             source: SearchSource::Postgres,
+            passage: None,
+            merged_from: Vec::new(),
         },
         SearchCandidate {
             post_id: "post5".to_string(),
             score: 0.70,
             source: SearchSource::Postgres,
+            passage: None,
+            merged_from: Vec::new(),
         },
     ];
 
@@ -139,6 +151,7 @@ mod tests {
             max_connections: 10,
             connection_timeout_secs: 5,
             default_ttl_secs: 3600,
+            vector_dimension: 384,
         };
         
         let database_config = DatabaseConfig {
@@ -146,6 +159,7 @@ mod tests {
             supabase_service_key: "test_key".to_string(),
             max_connections: 10,
             connection_timeout_secs: 30,
+            vector_dimension: 384,
         };
 
         // We can't easily create real managers in tests, so we'll test the merge logic directly
@@ -154,21 +168,29 @@ mod tests {
                 post_id: "post1".to_string(),
                 score: 0.95,
                 source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
             },
             SearchCandidate {
                 post_id: "post2".to_string(),
                 score: 0.85,
                 source: SearchSource::Postgres,
+                passage: None,
+                merged_from: Vec::new(),
             },
             SearchCandidate {
                 post_id: "post1".to_string(), // Duplicate with lower score
                 score: 0.90,
                 source: SearchSource::Postgres,
+                passage: None,
+                merged_from: Vec::new(),
             },
             SearchCandidate {
                 post_id: "post3".to_string(),
                 score: 0.75,
                 source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
             },
         ];
 
@@ -201,6 +223,8 @@ mod tests {
                 post_id: format!("post{}", i),
                 score: 1.0 - (i as f32 * 0.001), // Decreasing scores
                 source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
             });
         }
 
@@ -221,16 +245,22 @@ mod tests {
                 post_id: "post1".to_string(),
                 score: 0.80,
                 source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
             },
             SearchCandidate {
                 post_id: "post1".to_string(),
                 score: 0.90, // Higher score
                 source: SearchSource::Postgres,
+                passage: None,
+                merged_from: Vec::new(),
             },
             SearchCandidate {
                 post_id: "post1".to_string(),
                 score: 0.85, // Middle score
                 source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
             },
         ];
 
@@ -242,6 +272,8 @@ mod tests {
         // Should keep the highest score
         assert_eq!(merged[0].score, 0.90);
         assert_eq!(merged[0].source, SearchSource::Postgres);
+        // The other source that returned the post is recorded once
+        assert_eq!(merged[0].merged_from, vec![SearchSource::Redis]);
     }
 
     #[test]
@@ -258,16 +290,22 @@ mod tests {
                 post_id: "post1".to_string(),
                 score: 0.60,
                 source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
             },
             SearchCandidate {
                 post_id: "post2".to_string(),
                 score: 0.90,
                 source: SearchSource::Postgres,
+                passage: None,
+                merged_from: Vec::new(),
             },
             SearchCandidate {
                 post_id: "post3".to_string(),
                 score: 0.75,
                 source: SearchSource::Redis,
+                passage: None,
+                merged_from: Vec::new(),
             },
         ];
 
@@ -284,36 +322,39 @@ mod tests {
         assert_eq!(merged[2].score, 0.60);
     }
 
-    // Helper function to test merge logic without needing real managers
-    fn merge_and_dedup_helper(candidates: Vec<SearchCandidate>, max_candidates: usize) -> Vec<SearchCandidate> {
-        use std::collections::HashMap;
-
-        // Use HashMap to deduplicate by post_id, keeping the highest score
-        let mut best_candidates: HashMap<String, SearchCandidate> = HashMap::new();
-
-        for candidate in candidates {
-            match best_candidates.get(&candidate.post_id) {
-                Some(existing) => {
-                    // Keep the candidate with higher score
-                    if candidate.score > existing.score {
-                        best_candidates.insert(candidate.post_id.clone(), candidate);
-                    }
-                }
-                None => {
-                    best_candidates.insert(candidate.post_id.clone(), candidate);
-                }
-            }
-        }
+    #[test]
+    fn test_merge_normalizes_only_for_ranking() {
+        let candidate = |post_id: &str, score: f32, source: SearchSource| SearchCandidate {
+            post_id: post_id.to_string(),
+            score,
+            source,
+            passage: None,
+            merged_from: Vec::new(),
+        };
+        // Postgres scores sit on a compressed scale
+        let candidates = vec![
+            candidate("post1", 0.95, SearchSource::Redis),
+            candidate("post2", 0.70, SearchSource::Redis),
+            candidate("post3", 0.62, SearchSource::Postgres),
+            candidate("post4", 0.55, SearchSource::Postgres),
+        ];
 
-        // Convert to vector and sort by score (descending)
-        let mut merged_candidates: Vec<SearchCandidate> = best_candidates.into_values().collect();
-        merged_candidates.sort_by(|a, b| {
-            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
-        });
+        let merged = crate::search::fallback::merge_candidates(
+            candidates,
+            130,
+            crate::search::ScoreNormalization::MinMax,
+        );
 
-        // Limit to max candidates
-        merged_candidates.truncate(max_candidates);
-        merged_candidates
+        // Each source's best candidate ranks first, yet raw scores are kept
+        let order: Vec<&str> = merged.iter().map(|c| c.post_id.as_str()).collect();
+        assert!(order[..2].contains(&"post1") && order[..2].contains(&"post3"));
+        let post3 = merged.iter().find(|c| c.post_id == "post3").unwrap();
+        assert_eq!(post3.score, 0.62);
+    }
+
+    // Helper function to test merge logic without needing real managers
+    fn merge_and_dedup_helper(candidates: Vec<SearchCandidate>, max_candidates: usize) -> Vec<SearchCandidate> {
+        crate::search::fallback::merge_candidates(candidates, max_candidates, crate::search::ScoreNormalization::None)
     }
 }
 
@@ -651,6 +692,7 @@ mod integration_tests {
                 max_connections: 5,
                 connection_timeout_secs: 5,
                 default_ttl_secs: 3600,
+                vector_dimension: 384,
             };
             
             if let Ok(manager) = CacheManager::new(config).await {
@@ -667,6 +709,7 @@ mod integration_tests {
                 supabase_service_key: "test_key".to_string(),
                 max_connections: 5,
                 connection_timeout_secs: 30,
+                vector_dimension: 384,
             };
             
             if let Ok(manager) = DatabaseManager::new(config).await {
@@ -689,7 +732,7 @@ mod integration_tests {
             let query_vector = vec![0.1; 384]; // 384-dimensional vector
             let limit = 10;
 
-            let result = search_service.parallel_search(&query_vector, limit).await;
+            let result = search_service.parallel_search(&query_vector, limit, None).await;
             
             // Should succeed even if no results found
             assert!(result.is_ok(), "Parallel search failed: {:?}", result);
@@ -758,6 +801,7 @@ mod integration_tests {
                 post_id: "post1".to_string(),
                 title: "Machine Learning Basics".to_string(),
                 snippet: "Introduction to machine learning concepts".to_string(),
+                highlights: Vec::new(),
                 score: 0.7,
                 meta: PostMetadata {
                     author_name: "Author 1".to_string(),
@@ -765,12 +809,16 @@ mod integration_tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: false,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
             SearchResponse {
                 post_id: "post2".to_string(),
                 title: "Deep Learning Guide".to_string(),
                 snippet: "Comprehensive guide to deep learning".to_string(),
+                highlights: Vec::new(),
                 score: 0.6,
                 meta: PostMetadata {
                     author_name: "Author 2".to_string(),
@@ -778,7 +826,10 @@ mod integration_tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: false,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
         ];
 
@@ -788,7 +839,7 @@ mod integration_tests {
             .await;
 
         assert!(reranked_results.is_ok(), "Reranking should succeed");
-        let results = reranked_results.unwrap();
+        let (results, _) = reranked_results.unwrap();
         assert_eq!(results.len(), 2);
 
         // Test reranking disabled
@@ -797,7 +848,8 @@ mod integration_tests {
             .await;
 
         assert!(original_results.is_ok(), "Should return original results when disabled");
-        let results = original_results.unwrap();
+        let (results, scored) = original_results.unwrap();
+        assert!(!scored);
         assert_eq!(results.len(), 2);
         assert_eq!(results[0].score, 0.7); // Original scores preserved
     }
@@ -864,6 +916,7 @@ mod integration_tests {
                 post_id: "post1".to_string(),
                 title: "First Post".to_string(),
                 snippet: "First post content".to_string(),
+                highlights: Vec::new(),
                 score: 0.9,
                 meta: PostMetadata {
                     author_name: "Author 1".to_string(),
@@ -871,12 +924,16 @@ mod integration_tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: false,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
             SearchResponse {
                 post_id: "post2".to_string(),
                 title: "Second Post".to_string(),
                 snippet: "Second post content".to_string(),
+                highlights: Vec::new(),
                 score: 0.8,
                 meta: PostMetadata {
                     author_name: "Author 2".to_string(),
@@ -884,12 +941,16 @@ mod integration_tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: false,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
             SearchResponse {
                 post_id: "post3".to_string(),
                 title: "Third Post".to_string(),
                 snippet: "Third post content".to_string(),
+                highlights: Vec::new(),
                 score: 0.7,
                 meta: PostMetadata {
                     author_name: "Author 3".to_string(),
@@ -897,7 +958,10 @@ mod integration_tests {
                     date: Utc::now(),
                     language: "en".to_string(),
                     frozen: false,
+                    metadata: serde_json::Map::new(),
                 },
+                next_cursor: None,
+                explanation: None,
             },
         ];
 
@@ -906,7 +970,7 @@ mod integration_tests {
             .await;
 
         assert!(result.is_ok(), "Reranking with performance limit should succeed");
-        let results = result.unwrap();
+        let (results, _) = result.unwrap();
         assert_eq!(results.len(), 3); // Should return all results
         
         // Only first 2 should have been reranked, third should keep original score
@@ -1,11 +1,16 @@
 use axum::{
-    extract::{Request, State},
+    extract::{Path, Query, Request, State},
     http::{HeaderMap, HeaderValue, StatusCode, Method},
     middleware::{self, Next},
-    response::{Json, Response},
-    routing::{get, post},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::{delete, get, post},
     Router,
 };
+use futures::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -16,14 +21,20 @@ use tower_http::cors::{CorsLayer, Any};
 use tower_http::limit::RequestBodyLimitLayer;
 
 use crate::error::{SearchError, SearchResult};
-use crate::types::{SearchRequest, SearchResponse};
+use crate::types::{
+    AnswerRequest, BatchSearchRequest, BatchSearchResponse, BatchSearchResult, BatchUpsertPostsRequest,
+    BatchUpsertPostsResponse, CollectionParams, CollectionStats, ContextRequest, ContextResponse, DeletionReceipt,
+    DeletionStatus, SearchQueryParams, SearchRequest, SearchResponse, SimilarPostsParams, UpsertPostRequest,
+    UpsertPostResponse, MAX_BATCH_QUERIES,
+};
+use crate::collections::{Collection, CollectionRegistry};
 use crate::config::Config;
-use crate::cache::CacheManager;
-use crate::database::DatabaseManager;
+use crate::search::{SearchStage, MAX_CURSOR_LENGTH};
 
 /// Main search server structure
 pub struct SearchServer {
     app: Router,
+    grpc_service: crate::grpc::GrpcSearchService,
     config: Config,
 }
 
@@ -34,8 +45,10 @@ pub struct AppState {
     pub config: Config,
     /// Rate limiter for tracking requests per IP
     rate_limiter: Arc<RateLimiter>,
-    /// Complete search service with ML integration
-    search_service: Arc<crate::search::SearchService>,
+    /// Search, ingestion, erasure and answer services of every collection
+    collections: Arc<CollectionRegistry>,
+    /// Prometheus metrics exposed on /metrics
+    metrics: crate::observability::MetricsRegistry,
 }
 
 /// Advanced rate limiter with burst and sustained limits per IP
@@ -84,6 +97,12 @@ impl RateLimiter {
 
     /// Check if request should be rate limited for a specific IP
     pub fn check_rate_limit(&self, client_ip: &str) -> bool {
+        self.acquire_up_to(client_ip, 1) == 1
+    }
+
+    /// Count up to `wanted` more requests of a specific IP, as many as its
+    /// limits still allow, and return how many were counted
+    pub fn acquire_up_to(&self, client_ip: &str, wanted: u64) -> u64 {
         let mut states = self.ip_states.lock().unwrap();
         let now = Instant::now();
         
@@ -102,21 +121,21 @@ impl RateLimiter {
         }
         
         // Check both limits
-        if state.burst_count >= self.burst_limit {
+        let burst_left = self.burst_limit.saturating_sub(state.burst_count);
+        let sustained_left = self.sustained_limit.saturating_sub(state.sustained_count);
+        if wanted > burst_left {
             warn!("Burst rate limit exceeded for IP: {}", client_ip);
-            return false;
         }
-        
-        if state.sustained_count >= self.sustained_limit {
+        if wanted > sustained_left {
             warn!("Sustained rate limit exceeded for IP: {}", client_ip);
-            return false;
         }
+        let granted = wanted.min(burst_left).min(sustained_left);
         
         // Increment counters
-        state.burst_count += 1;
-        state.sustained_count += 1;
+        state.burst_count += granted;
+        state.sustained_count += granted;
         
-        true
+        granted
     }
     
     /// Clean up old IP states to prevent memory leaks
@@ -136,22 +155,14 @@ impl SearchServer {
     pub async fn new(config: Config) -> SearchResult<Self> {
         info!("Initializing search server components...");
 
-        // Initialize cache manager
-        let cache_manager = Arc::new(CacheManager::new(config.redis.clone()).await?);
-        
-        // Initialize database manager
-        let database_manager = Arc::new(DatabaseManager::new(config.database.clone()).await?);
-        
-        // Initialize ML service
-        let ml_service = Arc::new(crate::ml::MLService::new().await?);
-        
-        // Initialize complete search service
-        let search_service = Arc::new(
-            crate::search::SearchService::new(
-                cache_manager,
-                database_manager,
-                ml_service,
-            ).await?
+        let metrics = crate::observability::MetricsRegistry::new()?;
+
+        // Connect to Redis and Postgres and load the models of every collection
+        let collections = Arc::new(CollectionRegistry::new(&config, &metrics).await?);
+
+        let grpc_service = crate::grpc::GrpcSearchService::new(collections.clone()).with_batch_search(
+            config.server.batch_search_concurrency,
+            Duration::from_millis(config.server.request_timeout_ms),
         );
 
         let state = Arc::new(AppState {
@@ -159,26 +170,57 @@ impl SearchServer {
                 100, // burst limit: 100 RPS
                 config.server.rate_limit_per_minute, // sustained limit from config
             )),
-            search_service,
+            collections,
+            metrics,
             config: config.clone(),
         });
 
         // Configure CORS for production
         let cors = CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+            .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
             .allow_headers(Any)
             .allow_origin(Any) // In production, this should be more restrictive
             .max_age(Duration::from_secs(3600));
 
+        // Custom methods such as `/semantic-search:batch`; a batch holds up to
+        // `MAX_BATCH_QUERIES` searches, so it gets its own body limit
+        let batch_routes = Router::new()
+            .route("/semantic-search:action", post(semantic_search_action_handler))
+            .layer(RequestBodyLimitLayer::new(config.server.max_batch_request_size));
+
         let app = Router::new()
             .route("/semantic-search", post(semantic_search_handler))
+            .route(
+                "/semantic-search/stream",
+                get(semantic_search_stream_get_handler).post(semantic_search_stream_handler),
+            )
+            .route("/context", post(context_handler))
+            .route("/answer", post(answer_handler))
+            .route("/posts", post(upsert_post_handler))
+            // Custom methods such as `/posts:batch`; axum captures ":batch" as the parameter
+            .route("/posts:action", post(posts_action_handler))
+            .route("/posts/:post_id", delete(delete_post_handler))
+            .route("/posts/:post_id/similar", get(similar_posts_handler))
+            .route("/collections", get(collections_handler))
             .route("/health", get(health_handler))
+            .route("/metrics", get(metrics_handler))
             .layer(RequestBodyLimitLayer::new(config.server.max_request_size))
+            .merge(batch_routes)
             .layer(middleware::from_fn_with_state(state.clone(), security_middleware))
             .layer(middleware::from_fn_with_state(state.clone(), rate_limit_middleware))
             .layer(cors)
             .with_state(state.clone());
 
+        // Compute passages for posts ingested before passage indexing
+        for collection in state.collections.iter() {
+            let collection = collection.clone();
+            tokio::spawn(async move {
+                if let Err(e) = collection.ingestion_service().backfill_passages().await {
+                    warn!("Passage backfill of collection {} failed: {}", collection.name(), e);
+                }
+            });
+        }
+
         // Start periodic cleanup task for rate limiter
         let cleanup_state = state.clone();
         tokio::spawn(async move {
@@ -190,40 +232,38 @@ impl SearchServer {
         });
 
         info!("Search server initialized successfully");
-        Ok(SearchServer { app, config })
+        Ok(SearchServer { app, grpc_service, config })
     }
 
-    /// Run the HTTP server only
+    /// Run the HTTP and gRPC servers until either of them stops
     pub async fn run(self) -> SearchResult<()> {
         let bind_addr = format!("{}:{}", self.config.server.host, self.config.server.port);
         let listener = TcpListener::bind(&bind_addr)
             .await
             .map_err(|e| SearchError::ConfigError(format!("Failed to bind to {}: {}", bind_addr, e)))?;
 
+        let grpc_bind_addr = format!("{}:{}", self.config.server.host, self.config.server.grpc_port);
+        let grpc_addr = grpc_bind_addr
+            .parse()
+            .map_err(|e| SearchError::ConfigError(format!("Invalid gRPC address {}: {}", grpc_bind_addr, e)))?;
+
         info!("HTTP server listening on {}", bind_addr);
 
-        axum::serve(listener, self.app)
-            .await
-            .map_err(|e| SearchError::Internal(format!("Server error: {}", e)))?;
+        let http_server = async {
+            axum::serve(listener, self.app)
+                .await
+                .map_err(|e| SearchError::Internal(format!("Server error: {}", e)))
+        };
+        let grpc_server = crate::grpc::serve(self.grpc_service, grpc_addr);
+
+        tokio::try_join!(http_server, grpc_server)?;
 
         Ok(())
     }
 
     /// Get the gRPC service for external use
-    pub async fn create_grpc_service(&self) -> SearchResult<crate::grpc::GrpcSearchService> {
-        // Create the same services that the HTTP server uses
-        let cache_manager = Arc::new(crate::cache::CacheManager::new(self.config.redis.clone()).await?);
-        let database_manager = Arc::new(crate::database::DatabaseManager::new(self.config.database.clone()).await?);
-        let ml_service = Arc::new(crate::ml::MLService::new().await?);
-        let search_service = Arc::new(
-            crate::search::SearchService::new(
-                cache_manager,
-                database_manager,
-                ml_service,
-            ).await?
-        );
-
-        Ok(crate::grpc::GrpcSearchService::new(search_service))
+    pub fn grpc_service(&self) -> crate::grpc::GrpcSearchService {
+        self.grpc_service.clone()
     }
 }
 
@@ -248,8 +288,19 @@ async fn rate_limit_middleware(
         ));
     }
 
-    // Apply timeout to request processing
-    match timeout(Duration::from_millis(state.config.server.request_timeout_ms), next.run(request)).await {
+    // Apply timeout to request processing; answers also wait for the generator
+    let mut timeout_ms = state.config.server.request_timeout_ms;
+    if request.uri().path() == "/answer" {
+        timeout_ms += state.config.generator.timeout_ms;
+    }
+    // Batch searches embed their queries, then search them in rounds of
+    // `batch_search_concurrency`, each search with the usual deadline
+    if request.uri().path() == "/semantic-search:batch" {
+        let rounds = MAX_BATCH_QUERIES.div_ceil(state.config.server.batch_search_concurrency) as u64;
+        timeout_ms *= rounds + 1;
+    }
+
+    match timeout(Duration::from_millis(timeout_ms), next.run(request)).await {
         Ok(response) => Ok(response),
         Err(_) => {
             error!("Request timeout for IP: {}", client_ip);
@@ -321,8 +372,13 @@ async fn security_middleware(
 
 /// Extract client IP from request headers or connection info
 fn extract_client_ip(request: &Request) -> String {
+    client_ip_from_headers(request.headers())
+}
+
+/// Client IP from the proxy headers of a request
+fn client_ip_from_headers(headers: &HeaderMap) -> String {
     // Check for forwarded headers (common in production behind load balancers)
-    if let Some(forwarded_for) = request.headers().get("x-forwarded-for") {
+    if let Some(forwarded_for) = headers.get("x-forwarded-for") {
         if let Ok(forwarded_str) = forwarded_for.to_str() {
             // Take the first IP in the chain
             if let Some(first_ip) = forwarded_str.split(',').next() {
@@ -332,7 +388,7 @@ fn extract_client_ip(request: &Request) -> String {
     }
     
     // Check for real IP header
-    if let Some(real_ip) = request.headers().get("x-real-ip") {
+    if let Some(real_ip) = headers.get("x-real-ip") {
         if let Ok(ip_str) = real_ip.to_str() {
             return ip_str.to_string();
         }
@@ -378,8 +434,10 @@ async fn semantic_search_handler(
 
     info!("Processing search request for query: '{}' (rerank: {})", request.query, request.rerank);
 
+    let collection = resolve_collection(&state, request.collection.as_deref())?;
+
     // Perform semantic search with optional reranking
-    match state.search_service.semantic_search(request).await {
+    match collection.search_service().semantic_search(request).await {
         Ok(results) => {
             info!("Search completed successfully: {} results", results.len());
             Ok(Json(results))
@@ -389,6 +447,10 @@ async fn semantic_search_handler(
             
             // Map different error types to appropriate HTTP status codes
             let (status_code, error_message) = match &e {
+                SearchError::InvalidRequest(message) => (
+                    StatusCode::BAD_REQUEST,
+                    message.clone()
+                ),
                 SearchError::ModelError(_) => (
                     StatusCode::SERVICE_UNAVAILABLE,
                     "ML service temporarily unavailable".to_string()
@@ -422,10 +484,589 @@ async fn semantic_search_handler(
     }
 }
 
+/// Handler for `/semantic-search:<action>` custom methods
+///
+/// `:batch` runs up to `MAX_BATCH_QUERIES` searches with one embedding call.
+/// Each query gets its own entry in the response, holding its results or
+/// why it was rejected or failed, without failing the rest of the batch.
+/// Every searched query counts against the client's rate limit; queries past
+/// the client's remaining budget are answered with a rate limit error.
+async fn semantic_search_action_handler(
+    State(state): State<Arc<AppState>>,
+    Path(action): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<BatchSearchRequest>,
+) -> Result<Json<BatchSearchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if action != ":batch" {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Not found".to_string(),
+                message: format!("Unknown semantic-search method '{}'", action.trim_start_matches(':')),
+            }),
+        ));
+    }
+
+    if let Err(validation_error) = request.validate() {
+        error!("Invalid batch search request: {}", validation_error);
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid request".to_string(),
+                message: validation_error,
+            }),
+        ));
+    }
+
+    info!("Processing batch search request for {} queries", request.queries.len());
+
+    let collection = resolve_collection(&state, request.collection.as_deref())?;
+
+    // Invalid queries are answered without being searched
+    let mut queries: Vec<SearchResult<SearchRequest>> = request
+        .queries
+        .into_iter()
+        .map(|query| {
+            validate_search_request(&query)
+                .map(|()| query)
+                .map_err(SearchError::InvalidRequest)
+        })
+        .collect();
+
+    // The rate limit middleware already counted the request as one query
+    let client_ip = client_ip_from_headers(&headers);
+    let searches = queries.iter().filter(|query| query.is_ok()).count() as u64;
+    let mut budget = 1 + state.rate_limiter.acquire_up_to(&client_ip, searches.saturating_sub(1));
+    if budget < searches {
+        warn!("Rate limit allows {} of {} batch queries for IP: {}", budget, searches, client_ip);
+    }
+    for query in queries.iter_mut().filter(|query| query.is_ok()) {
+        if budget == 0 {
+            *query = Err(SearchError::InvalidRequest(
+                "Rate limit exceeded. Please try this query again later.".to_string(),
+            ));
+        } else {
+            budget -= 1;
+        }
+    }
+
+    let queries = collection
+        .search_service()
+        .semantic_search_batch(
+            queries,
+            state.config.server.batch_search_concurrency,
+            Duration::from_millis(state.config.server.request_timeout_ms),
+        )
+        .await
+        .into_iter()
+        .map(batch_search_result)
+        .collect();
+
+    Ok(Json(BatchSearchResponse { queries }))
+}
+
+/// Entry of a batch search response for one query's outcome
+fn batch_search_result(outcome: SearchResult<Vec<SearchResponse>>) -> BatchSearchResult {
+    match outcome {
+        Ok(results) => BatchSearchResult { results, error: None },
+        Err(e) => {
+            error!("Batch search query failed: {}", e);
+            // Only request problems are worth showing; backend details stay in the logs
+            let message = match e {
+                SearchError::InvalidRequest(message) => message,
+                SearchError::Timeout => "Request processing took too long".to_string(),
+                SearchError::ModelError(_) => "ML service temporarily unavailable".to_string(),
+                SearchError::RedisError(_) | SearchError::DatabaseError(_) => {
+                    "Search service temporarily unavailable".to_string()
+                }
+                _ => "Search failed".to_string(),
+            };
+            BatchSearchResult {
+                results: Vec::new(),
+                error: Some(message),
+            }
+        }
+    }
+}
+
+/// Handler for streamed semantic search with URL query parameters
+///
+/// Lets browser `EventSource` clients, which can only send `GET`, open the stream.
+async fn semantic_search_stream_get_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SearchQueryParams>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    match params.into_search_request() {
+        Ok(request) => semantic_search_stream_handler(State(state), Json(request)).await,
+        Err(validation_error) => {
+            error!("Invalid stream request: {}", validation_error);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Invalid request".to_string(),
+                    message: validation_error,
+                }),
+            ))
+        }
+    }
+}
+
+/// Handler for streamed semantic search
+///
+/// Sends server-sent events as the search progresses: a `results` event with
+/// the page in retrieval order, a `reranked` event with the page reordered by
+/// the cross-encoder when reranking runs, then `done` with the search mode and
+/// timings. Each page event carries the whole page and replaces the previous
+/// one; a failure sends `error` instead of `done`.
+async fn semantic_search_stream_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<SearchRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if let Err(validation_error) = validate_search_request(&request) {
+        error!("Invalid stream request: {}", validation_error);
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid request".to_string(),
+                message: validation_error,
+            }),
+        ));
+    }
+
+    info!("Processing streamed search request for query: '{}' (rerank: {})", request.query, request.rerank);
+
+    let collection = resolve_collection(&state, request.collection.as_deref())?;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let search_service = collection.search_service().clone();
+    // The response head goes out before the search runs, so the search
+    // gets its own deadline instead of the middleware's
+    let search_timeout = Duration::from_millis(state.config.server.request_timeout_ms);
+
+    tokio::spawn(async move {
+        let search = search_service.semantic_search_staged(request, tx.clone());
+        if timeout(search_timeout, search).await.is_err() {
+            error!("Streamed search timed out");
+            let _ = tx.send(Err(SearchError::Timeout)).await;
+        }
+    });
+
+    let events = ReceiverStream::new(rx).map(search_stage_event);
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()).into_response())
+}
+
+/// Server-sent event for a search stage
+fn search_stage_event(stage: SearchResult<SearchStage>) -> Result<Event, axum::Error> {
+    match stage {
+        Ok(SearchStage::Results(results)) => Event::default().event("results").json_data(results),
+        Ok(SearchStage::Reranked(results)) => Event::default().event("reranked").json_data(results),
+        Ok(SearchStage::Done(summary)) => Event::default().event("done").json_data(summary),
+        Err(e) => {
+            // Only request problems are worth showing; backend details stay in the logs
+            let message = match e {
+                SearchError::InvalidRequest(message) => message,
+                SearchError::Timeout => "Request processing took too long".to_string(),
+                _ => "Search failed".to_string(),
+            };
+            Event::default().event("error").json_data(serde_json::json!({ "message": message }))
+        }
+    }
+}
+
+/// Handler for context blocks
+///
+/// Returns the top passages for the query packed into `max_tokens` tokens,
+/// with each passage's post and character range in the block, for callers
+/// prompting their own model.
+async fn context_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ContextRequest>,
+) -> Result<Json<ContextResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if let Err(validation_error) = request.validate().and_then(|_| validate_search_request(&request.to_search_request())) {
+        error!("Invalid context request: {}", validation_error);
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid request".to_string(),
+                message: validation_error,
+            }),
+        ));
+    }
+
+    info!("Processing context request for query: '{}' (max_tokens: {})", request.query, request.max_tokens);
+
+    let collection = resolve_collection(&state, request.collection.as_deref())?;
+
+    collection
+        .context_service()
+        .build_context(request)
+        .await
+        .map(Json)
+        .map_err(|e| context_error_response(&e))
+}
+
+/// Map context assembly errors to HTTP responses
+fn context_error_response(e: &SearchError) -> (StatusCode, Json<ErrorResponse>) {
+    retrieval_error_response(e, "Context assembly failed", "ML service temporarily unavailable")
+}
+
+/// Map errors of endpoints built on search results to HTTP responses
+///
+/// `failure` titles the error; `model_message` explains a model failure,
+/// which differs between the embedding and the generator model.
+fn retrieval_error_response(e: &SearchError, failure: &str, model_message: &str) -> (StatusCode, Json<ErrorResponse>) {
+    error!("{}: {}", failure, e);
+
+    let (status_code, message) = match e {
+        SearchError::InvalidRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+        SearchError::ModelError(_) => (StatusCode::SERVICE_UNAVAILABLE, model_message.to_string()),
+        SearchError::RedisError(_) | SearchError::DatabaseError(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Search service temporarily unavailable".to_string(),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal server error".to_string(),
+        ),
+    };
+
+    (
+        status_code,
+        Json(ErrorResponse {
+            error: failure.to_string(),
+            message,
+        }),
+    )
+}
+
+/// Handler for retrieval-augmented answers
+///
+/// Responds with the complete answer as JSON, or with server-sent events when
+/// `stream` is set: one `citations` event, `token` events as the answer is
+/// generated, then `done` (or `error` if generation fails midway).
+async fn answer_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<AnswerRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if let Err(validation_error) = request.validate().and_then(|_| validate_search_request(&request.to_search_request())) {
+        error!("Invalid answer request: {}", validation_error);
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid request".to_string(),
+                message: validation_error,
+            }),
+        ));
+    }
+
+    let collection = resolve_collection(&state, request.collection.as_deref())?;
+    let answer_service = collection.answer_service().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: "Answer generation disabled".to_string(),
+                message: "No answer generator is configured".to_string(),
+            }),
+        )
+    })?;
+
+    info!("Processing answer request for query: '{}' (stream: {})", request.query, request.stream);
+
+    if !request.stream {
+        return answer_service
+            .answer(request)
+            .await
+            .map(|answer| Json(answer).into_response())
+            .map_err(|e| answer_error_response(&e));
+    }
+
+    let answer = answer_service.answer_stream(request).await.map_err(|e| answer_error_response(&e))?;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(32);
+    tokio::spawn(async move {
+        let citations = Event::default().event("citations").json_data(&answer.citations);
+        if tx.send(citations).await.is_err() {
+            return;
+        }
+
+        let mut tokens = answer.tokens;
+        while let Some(token) = tokens.next().await {
+            let event = match token {
+                Ok(text) => Event::default().event("token").json_data(serde_json::json!({ "text": text })),
+                Err(e) => {
+                    error!("Answer generation failed: {}", e);
+                    let failed = Event::default()
+                        .event("error")
+                        .json_data(serde_json::json!({ "message": "Answer generation failed" }));
+                    let _ = tx.send(failed).await;
+                    return;
+                }
+            };
+
+            if tx.send(event).await.is_err() {
+                // Client disconnected, stop generating
+                warn!("Answer stream client disconnected");
+                return;
+            }
+        }
+
+        let done = Event::default().event("done").json_data(serde_json::json!({ "model": answer.model }));
+        let _ = tx.send(done).await;
+    });
+
+    Ok(Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default()).into_response())
+}
+
+/// Map answer errors to HTTP responses
+fn answer_error_response(e: &SearchError) -> (StatusCode, Json<ErrorResponse>) {
+    retrieval_error_response(e, "Answer failed", "Answer generation temporarily unavailable")
+}
+
+/// Handler for single post ingestion
+async fn upsert_post_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<UpsertPostRequest>,
+) -> Result<Json<UpsertPostResponse>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Processing ingestion request for post: {}", request.post_id);
+
+    let collection = resolve_collection(&state, request.collection.as_deref())?;
+
+    collection
+        .ingestion_service()
+        .upsert_post(request)
+        .await
+        .map(Json)
+        .map_err(|e| ingestion_error_response(&e))
+}
+
+/// Handler for `/posts:<action>` custom methods
+async fn posts_action_handler(
+    State(state): State<Arc<AppState>>,
+    Path(action): Path<String>,
+    Json(request): Json<BatchUpsertPostsRequest>,
+) -> Result<Json<BatchUpsertPostsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if action != ":batch" {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Not found".to_string(),
+                message: format!("Unknown posts method '{}'", action.trim_start_matches(':')),
+            }),
+        ));
+    }
+
+    if let Err(validation_error) = request.validate_collection() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid request".to_string(),
+                message: validation_error,
+            }),
+        ));
+    }
+
+    info!("Processing batch ingestion request for {} posts", request.posts.len());
+
+    let collection = resolve_collection(&state, request.collection.as_deref())?;
+
+    collection
+        .ingestion_service()
+        .upsert_posts(request.posts)
+        .await
+        .map(|posts| Json(BatchUpsertPostsResponse { posts }))
+        .map_err(|e| ingestion_error_response(&e))
+}
+
+/// Handler for GDPR erasure of a post
+///
+/// The collection is taken from the `collection` query parameter. Responds 200
+/// with the receipt when every step succeeded, and 503 with the receipt when
+/// some step still failed after retries so the caller can repeat it.
+async fn delete_post_handler(
+    State(state): State<Arc<AppState>>,
+    Path(post_id): Path<String>,
+    Query(params): Query<CollectionParams>,
+) -> Result<(StatusCode, Json<DeletionReceipt>), (StatusCode, Json<ErrorResponse>)> {
+    info!("Processing GDPR erasure request for post: {}", post_id);
+
+    let collection = resolve_collection(&state, params.collection.as_deref())?;
+
+    match collection.gdpr_service().delete_post(&post_id).await {
+        Ok(receipt) => {
+            let status_code = match receipt.status {
+                DeletionStatus::Completed => StatusCode::OK,
+                DeletionStatus::Partial => StatusCode::SERVICE_UNAVAILABLE,
+            };
+            Ok((status_code, Json(receipt)))
+        }
+        Err(SearchError::InvalidRequest(msg)) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid request".to_string(),
+                message: msg,
+            }),
+        )),
+        Err(e) => {
+            error!("GDPR erasure failed: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Erasure failed".to_string(),
+                    message: "Internal server error".to_string(),
+                }),
+            ))
+        }
+    }
+}
+
+/// Handler for "more like this" searches seeded from a stored post
+///
+/// Takes the search parameters as query parameters, with `filters`
+/// JSON-encoded. Responds 404 when the post does not exist in the collection
+/// or has no embedding.
+async fn similar_posts_handler(
+    State(state): State<Arc<AppState>>,
+    Path(post_id): Path<String>,
+    Query(params): Query<SimilarPostsParams>,
+) -> Result<Json<Vec<SearchResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let request = params
+        .into_similar_posts_request(post_id)
+        .and_then(|request| {
+            request.validate()?;
+            validate_search_request(&request.to_search_request())?;
+            Ok(request)
+        })
+        .map_err(|validation_error| {
+            error!("Invalid similar posts request: {}", validation_error);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Invalid request".to_string(),
+                    message: validation_error,
+                }),
+            )
+        })?;
+
+    info!("Processing similar posts request for post: {}", request.post_id);
+
+    let collection = resolve_collection(&state, request.collection.as_deref())?;
+
+    match collection.search_service().similar_posts(&request).await {
+        Ok(Some(results)) => Ok(Json(results)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Not found".to_string(),
+                message: format!("Post '{}' not found", request.post_id),
+            }),
+        )),
+        Err(e) => Err(similar_posts_error_response(&e)),
+    }
+}
+
+/// Map similar posts search errors to HTTP responses
+fn similar_posts_error_response(e: &SearchError) -> (StatusCode, Json<ErrorResponse>) {
+    error!("Similar posts search failed: {}", e);
+
+    let (status_code, message) = match e {
+        SearchError::InvalidRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+        SearchError::RedisError(_) | SearchError::DatabaseError(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Search service temporarily unavailable".to_string(),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal server error".to_string(),
+        ),
+    };
+
+    (
+        status_code,
+        Json(ErrorResponse {
+            error: "Search failed".to_string(),
+            message,
+        }),
+    )
+}
+
+/// Map ingestion errors to HTTP responses
+fn ingestion_error_response(e: &SearchError) -> (StatusCode, Json<ErrorResponse>) {
+    error!("Ingestion failed: {}", e);
+
+    let (status_code, error, message) = match e {
+        SearchError::InvalidRequest(msg) => (
+            StatusCode::BAD_REQUEST,
+            "Invalid request",
+            msg.clone(),
+        ),
+        SearchError::ModelError(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Ingestion failed",
+            "ML service temporarily unavailable".to_string(),
+        ),
+        SearchError::DatabaseError(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Ingestion failed",
+            "Database temporarily unavailable".to_string(),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Ingestion failed",
+            "Internal server error".to_string(),
+        ),
+    };
+
+    (
+        status_code,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message,
+        }),
+    )
+}
+
+/// Resolve the collection named by a request
+///
+/// Unknown collections are reported as 404, like unknown resources.
+fn resolve_collection(
+    state: &AppState,
+    name: Option<&str>,
+) -> Result<Arc<Collection>, (StatusCode, Json<ErrorResponse>)> {
+    state.collections.get(name).map_err(|e| {
+        let message = match e {
+            SearchError::InvalidRequest(message) => message,
+            other => other.to_string(),
+        };
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Not found".to_string(),
+                message,
+            }),
+        )
+    })
+}
+
+/// Handler listing every collection with its model and sizes
+async fn collections_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<CollectionStats>>, (StatusCode, Json<ErrorResponse>)> {
+    state.collections.stats().await.map(Json).map_err(|e| {
+        error!("Failed to collect collection stats: {}", e);
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: "Stats unavailable".to_string(),
+                message: "Database temporarily unavailable".to_string(),
+            }),
+        )
+    })
+}
+
 /// Handler for health check endpoint
 async fn health_handler(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
-    // Perform comprehensive health check
-    let search_health = state.search_service.health_check().await;
+    // Both stores are shared by every collection, so the default one speaks for all
+    let search_health = state.collections.default_collection().search_service().health_check().await;
     
     let status = match search_health {
         Ok(_) => "healthy".to_string(),
@@ -438,6 +1079,14 @@ async fn health_handler(State(state): State<Arc<AppState>>) -> Json<HealthRespon
     })
 }
 
+/// Handler for Prometheus metrics scraping
+async fn metrics_handler(State(state): State<Arc<AppState>>) -> Result<String, StatusCode> {
+    state.metrics.gather().map_err(|e| {
+        error!("Failed to gather metrics: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
 /// Comprehensive request validation with enhanced security
 fn validate_search_request(request: &SearchRequest) -> Result<(), String> {
     // Validate query
@@ -474,6 +1123,32 @@ fn validate_search_request(request: &SearchRequest) -> Result<(), String> {
         }
     }
     
+    // Validate fusion weight (only used by hybrid retrieval)
+    if let Some(weight) = request.fusion_weight {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err("Parameter 'fusion_weight' must be between 0.0 and 1.0".to_string());
+        }
+    }
+    
+    // Validate MMR diversification weight
+    if let Some(lambda) = request.mmr_lambda {
+        if !(0.0..=1.0).contains(&lambda) {
+            return Err("Parameter 'mmr_lambda' must be between 0.0 and 1.0".to_string());
+        }
+    }
+    
+    // Validate passage aggregation
+    if let Some(aggregation) = &request.passage_aggregation {
+        aggregation.validate()?;
+    }
+    
+    // Cursors are verified by the search service; reject obvious garbage early
+    if let Some(cursor) = &request.cursor {
+        if cursor.len() > MAX_CURSOR_LENGTH || !cursor.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("Parameter 'cursor' is not a valid cursor".to_string());
+        }
+    }
+    
     // Validate filters
     if let Some(filters) = &request.filters {
         if let Some(language) = &filters.language {
@@ -486,6 +1161,9 @@ fn validate_search_request(request: &SearchRequest) -> Result<(), String> {
                 return Err("Language filter contains invalid characters or format".to_string());
             }
         }
+
+        // Dates, authors, URLs and nested combinators
+        filters.validate()?;
     }
     
     Ok(())
@@ -587,7 +1265,7 @@ struct HealthResponse {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{SearchRequest, SearchFilters};
+    use crate::types::{SearchRequest, SearchFilters, RetrievalMode, PassageAggregation};
     use axum::{
         http::StatusCode,
     };
@@ -638,6 +1316,13 @@ mod tests {
             min_score: Some(0.5),
             rerank: false,
             filters: None,
+            mode: RetrievalMode::Vector,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         }
     }
 
@@ -653,6 +1338,56 @@ mod tests {
         assert_eq!(body.status, "healthy");
     }
 
+    #[tokio::test]
+    async fn test_custom_method_routing() {
+        // Mirrors the `/posts` + `/posts:action` routes registered by the server
+        let app = Router::new()
+            .route("/posts", post(|| async { "single".to_string() }))
+            .route("/posts:action", post(|Path(action): Path<String>| async move { action }));
+        let server = TestServer::new(app).unwrap();
+
+        let response = server.post("/posts").await;
+        assert_eq!(response.text(), "single");
+
+        let response = server.post("/posts:batch").await;
+        assert_eq!(response.text(), ":batch");
+
+        // `/semantic-search:action` sits next to the nested stream route
+        let app = Router::new()
+            .route("/semantic-search", post(|| async { "single".to_string() }))
+            .route("/semantic-search:action", post(|Path(action): Path<String>| async move { action }))
+            .route("/semantic-search/stream", post(|| async { "stream".to_string() }));
+        let server = TestServer::new(app).unwrap();
+
+        assert_eq!(server.post("/semantic-search").await.text(), "single");
+        assert_eq!(server.post("/semantic-search:batch").await.text(), ":batch");
+        assert_eq!(server.post("/semantic-search/stream").await.text(), "stream");
+    }
+
+    #[test]
+    fn test_similar_posts_error_response() {
+        let (status, body) = similar_posts_error_response(&SearchError::DatabaseError("timeout".to_string()));
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.message, "Search service temporarily unavailable");
+
+        let (status, _) = similar_posts_error_response(&SearchError::Internal("bug".to_string()));
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_batch_search_result() {
+        let result = batch_search_result(Ok(Vec::new()));
+        assert!(result.error.is_none());
+
+        let result = batch_search_result(Err(SearchError::InvalidRequest("Bad cursor".to_string())));
+        assert_eq!(result.error.as_deref(), Some("Bad cursor"));
+
+        // Backend details are not exposed to clients
+        let result = batch_search_result(Err(SearchError::DatabaseError("connection refused".to_string())));
+        assert_eq!(result.error.as_deref(), Some("Search service temporarily unavailable"));
+        assert!(result.results.is_empty());
+    }
+
     #[tokio::test]
     async fn test_valid_search_request() {
         let server = create_test_server().await;
@@ -755,6 +1490,58 @@ mod tests {
         assert!(error.message.contains("must be between 0.0 and 1.0"));
     }
 
+    #[tokio::test]
+    async fn test_invalid_fusion_weight() {
+        let server = create_test_server().await;
+        let mut request = create_valid_request();
+        request.mode = RetrievalMode::Hybrid;
+        request.fusion_weight = Some(1.5);
+        
+        let response = server
+            .post("/test-validation")
+            .json(&request)
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        
+        let error: ErrorResponse = response.json();
+        assert!(error.message.contains("fusion_weight"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_mmr_lambda() {
+        let server = create_test_server().await;
+        let mut request = create_valid_request();
+        request.mmr_lambda = Some(-0.1);
+        
+        let response = server
+            .post("/test-validation")
+            .json(&request)
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        
+        let error: ErrorResponse = response.json();
+        assert!(error.message.contains("mmr_lambda"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_passage_aggregation() {
+        let server = create_test_server().await;
+        let mut request = create_valid_request();
+        request.passage_aggregation = Some(PassageAggregation::MeanTopN(0));
+        
+        let response = server
+            .post("/test-validation")
+            .json(&request)
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        
+        let error: ErrorResponse = response.json();
+        assert!(error.message.contains("passage_aggregation"));
+    }
+
     #[tokio::test]
     async fn test_invalid_min_score_above_one() {
         let server = create_test_server().await;
@@ -779,6 +1566,7 @@ mod tests {
         request.filters = Some(SearchFilters {
             language: Some("INVALID123".to_string()), // Invalid language code
             frozen: None,
+            ..Default::default()
         });
         
         let response = server
@@ -799,6 +1587,7 @@ mod tests {
         request.filters = Some(SearchFilters {
             language: Some("en".to_string()),
             frozen: Some(false),
+            ..Default::default()
         });
         
         let response = server
@@ -858,6 +1647,13 @@ mod tests {
             min_score: None,
             rerank: false,
             filters: None,
+            mode: RetrievalMode::Vector,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
         
         let json_body = serde_json::to_string(&request).unwrap();
@@ -896,6 +1692,19 @@ mod tests {
         assert!(!rate_limiter.check_rate_limit(test_ip));
     }
 
+    #[tokio::test]
+    async fn test_rate_limiter_acquire_up_to() {
+        let rate_limiter = RateLimiter::new(100, 10);
+        let test_ip = "192.168.1.1";
+
+        // Requests are granted up to the remaining budget
+        assert!(rate_limiter.check_rate_limit(test_ip));
+        assert_eq!(rate_limiter.acquire_up_to(test_ip, 5), 5);
+        assert_eq!(rate_limiter.acquire_up_to(test_ip, 99), 4);
+        assert_eq!(rate_limiter.acquire_up_to(test_ip, 1), 0);
+        assert!(!rate_limiter.check_rate_limit(test_ip));
+    }
+
     #[tokio::test]
     async fn test_rate_limiter_per_ip_isolation() {
         let rate_limiter = RateLimiter::new(2, 10);
@@ -1117,6 +1926,7 @@ mod tests {
         request.filters = Some(SearchFilters {
             language: Some("en-us".to_string()),
             frozen: None,
+            ..Default::default()
         });
         
         let response = server
@@ -1131,6 +1941,7 @@ mod tests {
         request.filters = Some(SearchFilters {
             language: Some("en123".to_string()),
             frozen: None,
+            ..Default::default()
         });
         
         let response = server
@@ -1142,4 +1953,91 @@ mod tests {
         let error: ErrorResponse = response.json();
         assert!(error.message.contains("invalid characters"));
     }
+
+    #[tokio::test]
+    async fn test_rich_filter_validation() {
+        let server = create_test_server().await;
+
+        let mut request = create_valid_request();
+        request.filters = Some(SearchFilters {
+            authors: Some(vec!["Ada".to_string()]),
+            or: Some(vec![SearchFilters {
+                url_domains: Some(vec!["example.com".to_string()]),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+
+        let response = server
+            .post("/test-validation")
+            .json(&request)
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        // Empty nested filters are rejected
+        let mut request = create_valid_request();
+        request.filters = Some(SearchFilters {
+            not: Some(Box::new(SearchFilters::default())),
+            ..Default::default()
+        });
+
+        let response = server
+            .post("/test-validation")
+            .json(&request)
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let error: ErrorResponse = response.json();
+        assert!(error.message.contains("Nested filters"));
+    }
+
+    #[tokio::test]
+    async fn test_search_stage_events() {
+        async fn staged() -> Sse<impl futures::Stream<Item = Result<Event, axum::Error>>> {
+            let summary = crate::types::SearchSummary {
+                search_mode: crate::types::SearchMode::Full,
+                reranked: true,
+                cached: false,
+                result_count: 0,
+                retrieval_ms: 3,
+                rerank_ms: Some(40),
+                total_ms: 43,
+            };
+            let stages = vec![
+                Ok(SearchStage::Results(Vec::new())),
+                Ok(SearchStage::Reranked(Vec::new())),
+                Ok(SearchStage::Done(summary)),
+                Err(SearchError::DatabaseError("connection refused".to_string())),
+            ];
+            Sse::new(futures::stream::iter(stages).map(search_stage_event))
+        }
+
+        let server = TestServer::new(Router::new().route("/stream", get(staged))).unwrap();
+        let body = server.get("/stream").await.text();
+
+        let events: Vec<&str> = body.lines().filter_map(|line| line.strip_prefix("event: ")).collect();
+        assert_eq!(events, vec!["results", "reranked", "done", "error"]);
+        assert!(body.contains(r#""search_mode":"full""#));
+        assert!(body.contains(r#""rerank_ms":40"#));
+        // Backend errors are not exposed to clients
+        assert!(!body.contains("connection refused"));
+    }
+
+    #[test]
+    fn test_answer_error_response() {
+        let (status, Json(body)) = answer_error_response(&SearchError::InvalidRequest("bad k".to_string()));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.message, "bad k");
+
+        // Generator failures do not leak backend details
+        let (status, Json(body)) = answer_error_response(&SearchError::ModelError("401 from api".to_string()));
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!body.message.contains("401"));
+
+        // Context assembly maps errors the same way under its own title
+        let (status, Json(body)) = context_error_response(&SearchError::DatabaseError("down".to_string()));
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.error, "Context assembly failed");
+    }
 }
\ No newline at end of file
@@ -5,25 +5,851 @@ use uuid::Uuid;
 /// Core search request structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchRequest {
+    /// Natural language query
+    pub query: String,
+    /// Maximum number of results to return (max 50)
+    pub k: u32,
+    /// Minimum similarity score threshold (optional); in hybrid mode it applies
+    /// to the vector and full-text scores before fusion
+    pub min_score: Option<f32>,
+    /// Enable cross-encoder reranking
+    pub rerank: bool,
+    /// Optional filters for search results
+    pub filters: Option<SearchFilters>,
+    /// Retrieval mode (vector, lexical or hybrid)
+    #[serde(default)]
+    pub mode: RetrievalMode,
+    /// Weight of the vector ranking in hybrid fusion, 0.0-1.0 (default 0.5)
+    #[serde(default)]
+    pub fusion_weight: Option<f32>,
+    /// Enable MMR diversification with this relevance weight, 0.0-1.0
+    /// (1.0 ranks by relevance only, lower values favour diverse results)
+    #[serde(default)]
+    pub mmr_lambda: Option<f32>,
+    /// Search passages of long posts, combining passage hits per post this way
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passage_aggregation: Option<PassageAggregation>,
+    /// Cursor from the previous page's `next_cursor`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Attach a score breakdown to every result
+    #[serde(default)]
+    pub explain: bool,
+    /// Collection to search (defaults to the default collection)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+}
+
+/// Search request passed as URL query parameters
+///
+/// Used by `GET` endpoints that browser `EventSource` clients can open; the
+/// filter expression is passed JSON-encoded in `filters`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchQueryParams {
     /// Natural language query
     pub query: String,
     /// Maximum number of results to return (max 50)
     pub k: u32,
     /// Minimum similarity score threshold (optional)
+    #[serde(default)]
     pub min_score: Option<f32>,
     /// Enable cross-encoder reranking
+    #[serde(default)]
     pub rerank: bool,
+    /// JSON-encoded filter expression
+    #[serde(default)]
+    pub filters: Option<String>,
+    /// Retrieval mode (vector, lexical or hybrid)
+    #[serde(default)]
+    pub mode: RetrievalMode,
+    /// Weight of the vector ranking in hybrid fusion, 0.0-1.0
+    #[serde(default)]
+    pub fusion_weight: Option<f32>,
+    /// Enable MMR diversification with this relevance weight, 0.0-1.0
+    #[serde(default)]
+    pub mmr_lambda: Option<f32>,
+    /// Search passages of long posts, combining passage hits per post by
+    /// `max` or `mean_top_n`
+    #[serde(default)]
+    pub passage_aggregation: Option<String>,
+    /// Passages averaged per post by `mean_top_n` (defaults to 3)
+    #[serde(default)]
+    pub passage_top_n: Option<u32>,
+    /// Cursor from the previous page's `next_cursor`
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Attach a score breakdown to every result
+    #[serde(default)]
+    pub explain: bool,
+    /// Collection to search (defaults to the default collection)
+    #[serde(default)]
+    pub collection: Option<String>,
+}
+
+impl SearchQueryParams {
+    /// Convert to a search request, decoding the filter expression
+    pub fn into_search_request(self) -> Result<SearchRequest, String> {
+        let filters = match self.filters.as_deref() {
+            Some(encoded) => Some(serde_json::from_str(encoded).map_err(|e| {
+                format!("Parameter 'filters' is not a valid filter expression: {}", e)
+            })?),
+            None => None,
+        };
+
+        // Query strings cannot nest `{"mean_top_n": 3}`, so n is its own parameter
+        let passage_aggregation = match self.passage_aggregation.as_deref() {
+            None => None,
+            Some("max") => Some(PassageAggregation::Max),
+            Some("mean_top_n") => Some(PassageAggregation::MeanTopN(
+                self.passage_top_n.unwrap_or(DEFAULT_PASSAGE_TOP_N),
+            )),
+            Some(other) => {
+                return Err(format!(
+                    "Parameter 'passage_aggregation' must be 'max' or 'mean_top_n', got '{}'",
+                    other
+                ))
+            }
+        };
+
+        Ok(SearchRequest {
+            query: self.query,
+            k: self.k,
+            min_score: self.min_score,
+            rerank: self.rerank,
+            filters,
+            mode: self.mode,
+            fusion_weight: self.fusion_weight,
+            mmr_lambda: self.mmr_lambda,
+            passage_aggregation,
+            cursor: self.cursor,
+            explain: self.explain,
+            collection: self.collection,
+        })
+    }
+}
+
+/// Largest number of queries accepted in one batch search
+pub const MAX_BATCH_QUERIES: usize = 100;
+
+/// Batch search request; every query runs in the batch's collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchSearchRequest {
+    /// Queries to run (1-100)
+    pub queries: Vec<SearchRequest>,
+    /// Collection to search (defaults to the default collection)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+}
+
+impl BatchSearchRequest {
+    /// Check the batch size and that no query names a collection other than the batch's
+    pub fn validate(&self) -> Result<(), String> {
+        validate_batch_queries(
+            self.collection.as_deref(),
+            self.queries.iter().map(|query| query.collection.as_deref()),
+        )
+    }
+}
+
+/// Check a batch search's size and the collections named by its queries
+///
+/// Takes only the collection of each query so gRPC batches can be checked
+/// before their queries are converted.
+pub fn validate_batch_queries<'a>(
+    collection: Option<&str>,
+    query_collections: impl ExactSizeIterator<Item = Option<&'a str>>,
+) -> Result<(), String> {
+    if query_collections.len() == 0 || query_collections.len() > MAX_BATCH_QUERIES {
+        return Err(format!("Batch must contain 1-{} queries", MAX_BATCH_QUERIES));
+    }
+
+    let batch_collection = collection.unwrap_or(DEFAULT_COLLECTION);
+    for (index, query_collection) in query_collections.enumerate() {
+        if let Some(query_collection) = query_collection.filter(|name| *name != batch_collection) {
+            return Err(format!(
+                "Query {} names collection '{}' but the batch targets '{}'",
+                index, query_collection, batch_collection
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Outcome of one query of a batch search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchSearchResult {
+    /// Results of the query; empty when it failed
+    pub results: Vec<SearchResponse>,
+    /// Why the query failed; a failed query does not affect the rest of the batch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of a batch search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchSearchResponse {
+    /// Per-query outcomes in request order
+    pub queries: Vec<BatchSearchResult>,
+}
+
+/// Default number of posts returned by a similar posts search
+pub const DEFAULT_SIMILAR_POSTS: u32 = 5;
+
+fn default_similar_posts() -> u32 {
+    DEFAULT_SIMILAR_POSTS
+}
+
+/// "More like this" request seeded from a stored post's embedding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarPostsRequest {
+    /// Post whose embedding seeds the search; it is never returned
+    pub post_id: String,
+    /// Maximum number of results to return (default 5, max 50)
+    #[serde(default = "default_similar_posts")]
+    pub k: u32,
+    /// Minimum similarity score threshold (optional)
+    #[serde(default)]
+    pub min_score: Option<f32>,
     /// Optional filters for search results
+    #[serde(default)]
     pub filters: Option<SearchFilters>,
+    /// Also leave out posts that embed almost identically to the seed post,
+    /// such as syndicated copies of it
+    #[serde(default)]
+    pub exclude_near_duplicates: bool,
+    /// Collection holding the post (defaults to the default collection)
+    #[serde(default)]
+    pub collection: Option<String>,
 }
 
-/// Search filters for metadata-based filtering
+/// Query standing in for the seed post when a similar posts request is
+/// checked with the search rules
+pub const SIMILAR_POSTS_QUERY: &str = "more like this";
+
+impl SimilarPostsRequest {
+    /// Check the seed post's identifier; the search parameters are checked
+    /// on `to_search_request`
+    pub fn validate(&self) -> Result<(), String> {
+        validate_post_id(&self.post_id)
+    }
+
+    /// Search request with the same limits and filters, so they can be
+    /// checked like any other search
+    ///
+    /// The post id is an identifier rather than query text, so a fixed
+    /// query stands in for it.
+    pub fn to_search_request(&self) -> SearchRequest {
+        SearchRequest {
+            query: SIMILAR_POSTS_QUERY.to_string(),
+            k: self.k,
+            min_score: self.min_score,
+            rerank: false,
+            filters: self.filters.clone(),
+            mode: RetrievalMode::Vector,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: None,
+            cursor: None,
+            explain: false,
+            collection: self.collection.clone(),
+        }
+    }
+}
+
+/// Similar posts request passed as URL query parameters
+///
+/// The post comes from the path; the filter expression is passed JSON-encoded
+/// in `filters`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarPostsParams {
+    /// Maximum number of results to return (default 5, max 50)
+    #[serde(default = "default_similar_posts")]
+    pub k: u32,
+    /// Minimum similarity score threshold (optional)
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    /// JSON-encoded filter expression
+    #[serde(default)]
+    pub filters: Option<String>,
+    /// Also leave out near-duplicates of the seed post
+    #[serde(default)]
+    pub exclude_near_duplicates: bool,
+    /// Collection holding the post (defaults to the default collection)
+    #[serde(default)]
+    pub collection: Option<String>,
+}
+
+impl SimilarPostsParams {
+    /// Convert to a similar posts request for the given post, decoding the filter expression
+    pub fn into_similar_posts_request(self, post_id: String) -> Result<SimilarPostsRequest, String> {
+        let filters = match self.filters.as_deref() {
+            Some(encoded) => Some(serde_json::from_str(encoded).map_err(|e| {
+                format!("Parameter 'filters' is not a valid filter expression: {}", e)
+            })?),
+            None => None,
+        };
+
+        Ok(SimilarPostsRequest {
+            post_id,
+            k: self.k,
+            min_score: self.min_score,
+            filters,
+            exclude_near_duplicates: self.exclude_near_duplicates,
+            collection: self.collection,
+        })
+    }
+}
+
+/// How candidates are retrieved for a search request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetrievalMode {
+    /// Embedding similarity only
+    #[default]
+    Vector,
+    /// Postgres full-text search only
+    Lexical,
+    /// Vector and full-text candidates merged with reciprocal rank fusion
+    Hybrid,
+}
+
+impl RetrievalMode {
+    /// Stable string form used in cache keys and logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetrievalMode::Vector => "vector",
+            RetrievalMode::Lexical => "lexical",
+            RetrievalMode::Hybrid => "hybrid",
+        }
+    }
+}
+
+/// How passage-level hits are combined into one score per post
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PassageAggregation {
+    /// Score of the best matching passage
+    Max,
+    /// Mean of the top n passage scores, missing passages counting as 0, so
+    /// posts matching in several places rank higher while scores stay within
+    /// 0.0-1.0 like any similarity
+    MeanTopN(u32),
+}
+
+/// Default n of `PassageAggregation::MeanTopN` for clients that only pick the method
+pub const DEFAULT_PASSAGE_TOP_N: u32 = 3;
+
+/// Largest n accepted for `PassageAggregation::MeanTopN`
+pub const MAX_PASSAGE_TOP_N: u32 = 10;
+
+impl PassageAggregation {
+    /// Stable string form used in cache keys and logs
+    pub fn as_str(&self) -> String {
+        match self {
+            PassageAggregation::Max => "max".to_string(),
+            PassageAggregation::MeanTopN(n) => format!("mean_top_{}", n),
+        }
+    }
+
+    /// Validate the aggregation parameters
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            PassageAggregation::MeanTopN(n) if *n == 0 || *n > MAX_PASSAGE_TOP_N => Err(format!(
+                "Parameter 'passage_aggregation' mean_top_n must be between 1 and {}",
+                MAX_PASSAGE_TOP_N
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Search filters for metadata-based filtering
+///
+/// All conditions set on one filter must hold. `and`, `or` and `not` nest
+/// further filters for boolean composition. Frozen posts are excluded unless a
+/// `frozen` condition appears somewhere in the expression.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SearchFilters {
     /// Filter by language (e.g., "en", "es")
     pub language: Option<String>,
     /// Filter by frozen status (false excludes frozen posts)
     pub frozen: Option<bool>,
+    /// Only posts published at or after this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_after: Option<DateTime<Utc>>,
+    /// Only posts published before this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_before: Option<DateTime<Utc>>,
+    /// Only posts by one of these authors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<String>>,
+    /// Exclude posts by any of these authors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_authors: Option<Vec<String>>,
+    /// Only posts hosted on one of these domains or their subdomains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_domains: Option<Vec<String>>,
+    /// Only posts whose URL starts with this prefix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_prefix: Option<String>,
+    /// Every nested filter must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub and: Option<Vec<SearchFilters>>,
+    /// At least one nested filter must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub or: Option<Vec<SearchFilters>>,
+    /// The nested filter must not match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not: Option<Box<SearchFilters>>,
+    /// Conditions on custom post metadata, all of which must hold
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Vec<MetadataFilter>>,
+}
+
+/// Condition on one key of the custom post metadata
+///
+/// A dotted key such as `product.category` is a path into nested objects.
+/// `eq`, `in` and `contains` take strings, numbers or booleans; the range
+/// operators only match numeric values.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataFilter {
+    /// Metadata key or dotted path (e.g. "category", "tags" or "product.category")
+    pub key: String,
+    /// Value equals this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eq: Option<serde_json::Value>,
+    /// Value equals one of these
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#in: Option<Vec<serde_json::Value>>,
+    /// Value is an array containing this element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contains: Option<serde_json::Value>,
+    /// Value is a number greater than this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gt: Option<f64>,
+    /// Value is a number greater than or equal to this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gte: Option<f64>,
+    /// Value is a number less than this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lt: Option<f64>,
+    /// Value is a number less than or equal to this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lte: Option<f64>,
+}
+
+impl MetadataFilter {
+    /// Whether any numeric range operator is set
+    pub fn has_range(&self) -> bool {
+        self.gt.is_some() || self.gte.is_some() || self.lt.is_some() || self.lte.is_some()
+    }
+
+    /// Keys of the path from the metadata root to the compared value
+    pub fn path(&self) -> impl Iterator<Item = &str> {
+        self.key.split('.')
+    }
+
+    /// Evaluate the condition against custom post metadata
+    pub fn matches(&self, metadata: &serde_json::Map<String, serde_json::Value>) -> bool {
+        let mut path = self.path();
+        let root = path.next().and_then(|key| metadata.get(key));
+        let value = match path.try_fold(root, |value, key| Some(value?.as_object()?.get(key))) {
+            Some(Some(value)) => value,
+            _ => return false,
+        };
+
+        if let Some(expected) = &self.eq {
+            if !json_scalar_eq(value, expected) {
+                return false;
+            }
+        }
+
+        if let Some(expected) = &self.r#in {
+            if !expected.iter().any(|expected| json_scalar_eq(value, expected)) {
+                return false;
+            }
+        }
+
+        if let Some(expected) = &self.contains {
+            let contained = value
+                .as_array()
+                .is_some_and(|items| items.iter().any(|item| json_scalar_eq(item, expected)));
+            if !contained {
+                return false;
+            }
+        }
+
+        if self.has_range() {
+            let number = match value.as_f64() {
+                Some(number) => number,
+                None => return false,
+            };
+            if self.gt.is_some_and(|bound| number <= bound)
+                || self.gte.is_some_and(|bound| number < bound)
+                || self.lt.is_some_and(|bound| number >= bound)
+                || self.lte.is_some_and(|bound| number > bound)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Validate the key, operands and that at least one operator is set
+    pub fn validate(&self) -> Result<(), String> {
+        if self.key.len() > MAX_METADATA_KEY_LENGTH || !self.path().all(is_valid_metadata_key) {
+            return Err(format!(
+                "Metadata filter key '{}' must be up to {} characters of '.'-separated keys \
+                 made of letters, digits, '_' or '-'",
+                self.key, MAX_METADATA_KEY_LENGTH
+            ));
+        }
+
+        if self.eq.is_none() && self.r#in.is_none() && self.contains.is_none() && !self.has_range() {
+            return Err(format!("Metadata filter on '{}' needs an operator", self.key));
+        }
+
+        let mut operands = self.eq.iter().chain(self.contains.iter()).chain(self.r#in.iter().flatten());
+        if !operands.all(|value| value.is_string() || value.is_number() || value.is_boolean()) {
+            return Err(format!(
+                "Metadata filter on '{}' only compares strings, numbers and booleans",
+                self.key
+            ));
+        }
+
+        if let Some(values) = &self.r#in {
+            if values.is_empty() || values.len() > MAX_FILTER_VALUES {
+                return Err(format!("Metadata filter 'in' must list 1-{} values", MAX_FILTER_VALUES));
+            }
+        }
+
+        let bounds = [self.gt, self.gte, self.lt, self.lte];
+        if bounds.iter().flatten().any(|bound| !bound.is_finite()) {
+            return Err(format!("Metadata range on '{}' must use finite numbers", self.key));
+        }
+
+        Ok(())
+    }
+}
+
+/// Maximum length of a custom metadata key
+pub const MAX_METADATA_KEY_LENGTH: usize = 64;
+
+/// Check a custom metadata key such as `category` or `product_id`
+///
+/// Dots are not allowed, since filters use them to separate the keys of a
+/// nested path.
+pub fn is_valid_metadata_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= MAX_METADATA_KEY_LENGTH
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Maximum length of a post identifier
+pub const MAX_POST_ID_LENGTH: usize = 255;
+
+/// Check a post identifier such as `post_123` or a URL slug
+///
+/// Identifiers are opaque keys, never parsed as queries, so any characters
+/// but whitespace and control characters are allowed.
+pub fn validate_post_id(post_id: &str) -> Result<(), String> {
+    if post_id.is_empty() || post_id.len() > MAX_POST_ID_LENGTH {
+        return Err(format!("post_id must be 1-{} characters", MAX_POST_ID_LENGTH));
+    }
+
+    if post_id.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err("post_id must not contain whitespace or control characters".to_string());
+    }
+
+    Ok(())
+}
+
+/// Collection used when a request does not name one
+pub const DEFAULT_COLLECTION: &str = "default";
+
+/// Maximum length of a collection name
+pub const MAX_COLLECTION_NAME_LENGTH: usize = 32;
+
+/// Check a collection name such as `docs` or `support_kb`
+///
+/// Names become part of Postgres table names and Redis keys, so only
+/// lowercase letters, digits and underscores are allowed, starting with a letter.
+pub fn is_valid_collection_name(name: &str) -> bool {
+    name.len() <= MAX_COLLECTION_NAME_LENGTH
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Compare JSON scalars, treating numbers by value as JSONB does (1 == 1.0)
+fn json_scalar_eq(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Maximum nesting depth of `and`/`or`/`not` filters
+pub const MAX_FILTER_DEPTH: usize = 4;
+
+/// Maximum number of filters in one expression, nested filters included
+pub const MAX_FILTER_NODES: usize = 32;
+
+/// Maximum number of values in an author or domain list
+pub const MAX_FILTER_VALUES: usize = 50;
+
+impl SearchFilters {
+    /// Whether the filter sets no condition at all
+    pub fn is_empty(&self) -> bool {
+        self.language.is_none()
+            && self.frozen.is_none()
+            && self.date_after.is_none()
+            && self.date_before.is_none()
+            && self.authors.is_none()
+            && self.exclude_authors.is_none()
+            && self.url_domains.is_none()
+            && self.url_prefix.is_none()
+            && self.and.is_none()
+            && self.or.is_none()
+            && self.not.is_none()
+            && self.metadata.is_none()
+    }
+
+    /// Names of the conditions set at the top level of the expression
+    pub fn condition_names(&self) -> Vec<&'static str> {
+        [
+            ("language", self.language.is_some()),
+            ("frozen", self.frozen.is_some()),
+            ("date_after", self.date_after.is_some()),
+            ("date_before", self.date_before.is_some()),
+            ("authors", self.authors.is_some()),
+            ("exclude_authors", self.exclude_authors.is_some()),
+            ("url_domains", self.url_domains.is_some()),
+            ("url_prefix", self.url_prefix.is_some()),
+            ("metadata", self.metadata.is_some()),
+            ("and", self.and.is_some()),
+            ("or", self.or.is_some()),
+            ("not", self.not.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, set)| *set)
+        .map(|(name, _)| name)
+        .collect()
+    }
+
+    /// Nested filters of the `and`, `or` and `not` combinators
+    pub fn children(&self) -> impl Iterator<Item = &SearchFilters> {
+        self.and
+            .iter()
+            .flatten()
+            .chain(self.or.iter().flatten())
+            .chain(self.not.as_deref())
+    }
+
+    /// Whether a frozen condition appears anywhere in the expression
+    ///
+    /// When none does, frozen posts are excluded by default.
+    pub fn mentions_frozen(&self) -> bool {
+        self.frozen.is_some() || self.children().any(SearchFilters::mentions_frozen)
+    }
+
+    /// Whether a URL prefix condition appears anywhere in the expression
+    pub fn mentions_url_prefix(&self) -> bool {
+        self.url_prefix.is_some() || self.children().any(SearchFilters::mentions_url_prefix)
+    }
+
+    /// Whether a custom metadata condition appears anywhere in the expression
+    pub fn mentions_metadata(&self) -> bool {
+        self.metadata.is_some() || self.children().any(SearchFilters::mentions_metadata)
+    }
+
+    /// Evaluate the filter against post metadata
+    pub fn matches(&self, meta: &PostMetadata) -> bool {
+        if let Some(language) = &self.language {
+            if !meta.language.eq_ignore_ascii_case(language) {
+                return false;
+            }
+        }
+
+        if let Some(frozen) = self.frozen {
+            if meta.frozen != frozen {
+                return false;
+            }
+        }
+
+        if self.date_after.is_some_and(|after| meta.date < after) {
+            return false;
+        }
+
+        if self.date_before.is_some_and(|before| meta.date >= before) {
+            return false;
+        }
+
+        if let Some(authors) = &self.authors {
+            if !authors.iter().any(|author| author == &meta.author_name) {
+                return false;
+            }
+        }
+
+        if let Some(authors) = &self.exclude_authors {
+            if authors.iter().any(|author| author == &meta.author_name) {
+                return false;
+            }
+        }
+
+        if let Some(domains) = &self.url_domains {
+            let host = url_host(&meta.url);
+            let on_domain = |domain: &String| {
+                host.as_deref().is_some_and(|host| {
+                    host == domain || host.ends_with(&format!(".{}", domain))
+                })
+            };
+            if !domains.iter().any(on_domain) {
+                return false;
+            }
+        }
+
+        if let Some(prefix) = &self.url_prefix {
+            if !meta.url.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(conditions) = &self.metadata {
+            if !conditions.iter().all(|condition| condition.matches(&meta.metadata)) {
+                return false;
+            }
+        }
+
+        if let Some(filters) = &self.and {
+            if !filters.iter().all(|filter| filter.matches(meta)) {
+                return false;
+            }
+        }
+
+        if let Some(filters) = &self.or {
+            if !filters.iter().any(|filter| filter.matches(meta)) {
+                return false;
+            }
+        }
+
+        if let Some(filter) = &self.not {
+            if filter.matches(meta) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Validate the filter expression
+    pub fn validate(&self) -> Result<(), String> {
+        let mut nodes = 0;
+        self.validate_node(0, &mut nodes)
+    }
+
+    fn validate_node(&self, depth: usize, nodes: &mut usize) -> Result<(), String> {
+        *nodes += 1;
+        if *nodes > MAX_FILTER_NODES {
+            return Err(format!("Filter expression too large (maximum {} filters)", MAX_FILTER_NODES));
+        }
+
+        if depth > MAX_FILTER_DEPTH {
+            return Err(format!("Filters nested too deeply (maximum depth {})", MAX_FILTER_DEPTH));
+        }
+
+        if depth > 0 && self.is_empty() {
+            return Err("Nested filters cannot be empty".to_string());
+        }
+
+        if let Some(language) = &self.language {
+            if language.is_empty() || language.len() > 10 {
+                return Err("Language filter must be 1-10 characters".to_string());
+            }
+
+            if !language.chars().all(|c| c.is_ascii_lowercase() || c == '-') || language.len() < 2 {
+                return Err("Language filter contains invalid characters or format".to_string());
+            }
+        }
+
+        if let (Some(after), Some(before)) = (self.date_after, self.date_before) {
+            if after >= before {
+                return Err("Filter 'date_after' must be earlier than 'date_before'".to_string());
+            }
+        }
+
+        for (name, authors) in [("authors", &self.authors), ("exclude_authors", &self.exclude_authors)] {
+            if let Some(authors) = authors {
+                validate_filter_values(name, authors)?;
+                if authors.iter().any(|author| author.trim().is_empty() || author.len() > 255) {
+                    return Err(format!("Filter '{}' entries must be 1-255 characters", name));
+                }
+            }
+        }
+
+        if let Some(domains) = &self.url_domains {
+            validate_filter_values("url_domains", domains)?;
+            if !domains.iter().all(|domain| is_valid_domain(domain)) {
+                return Err("Filter 'url_domains' entries must be lowercase domain names".to_string());
+            }
+        }
+
+        if let Some(prefix) = &self.url_prefix {
+            if prefix.len() > 2048 || !(prefix.starts_with("https://") || prefix.starts_with("http://")) {
+                return Err("Filter 'url_prefix' must be an http(s) URL prefix of at most 2048 characters".to_string());
+            }
+        }
+
+        if let Some(conditions) = &self.metadata {
+            if conditions.is_empty() || conditions.len() > MAX_FILTER_VALUES {
+                return Err(format!("Filter 'metadata' must list 1-{} conditions", MAX_FILTER_VALUES));
+            }
+            for condition in conditions {
+                condition.validate()?;
+            }
+        }
+
+        for (name, filters) in [("and", &self.and), ("or", &self.or)] {
+            if filters.as_ref().is_some_and(|filters| filters.is_empty()) {
+                return Err(format!("Filter '{}' needs at least one nested filter", name));
+            }
+        }
+
+        for child in self.children() {
+            child.validate_node(depth + 1, nodes)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Validate the length of an author or domain list
+fn validate_filter_values(name: &str, values: &[String]) -> Result<(), String> {
+    if values.is_empty() || values.len() > MAX_FILTER_VALUES {
+        return Err(format!("Filter '{}' must list 1-{} values", name, MAX_FILTER_VALUES));
+    }
+    Ok(())
+}
+
+/// Check a domain filter value such as `example.com`
+fn is_valid_domain(domain: &str) -> bool {
+    !domain.is_empty()
+        && domain.len() <= 253
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        })
+}
+
+/// Lowercase host of a post URL
+pub fn url_host(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_ascii_lowercase()))
 }
 
 /// Search response structure
@@ -35,10 +861,79 @@ pub struct SearchResponse {
     pub title: String,
     /// Post snippet (truncated to 300 chars for GDPR)
     pub snippet: String,
+    /// Query terms matched in the snippet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<HighlightSpan>,
     /// Similarity score (0.0 to 1.0)
     pub score: f32,
     /// Additional post metadata
     pub meta: PostMetadata,
+    /// Cursor for the next page, set on the last result of a full page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Score breakdown, set when the request asked to explain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ScoreExplanation>,
+}
+
+/// How a result got its score and rank
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreExplanation {
+    /// Degradation mode the search ran in
+    pub search_mode: SearchMode,
+    /// Retrieval backends that returned the post
+    pub sources: Vec<SearchSource>,
+    /// Vector similarity, aggregated over passages in passage search
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector_score: Option<f32>,
+    /// Full-text rank score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lexical_score: Option<f32>,
+    /// Contributions of each ranking to the hybrid score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fusion: Option<FusionExplanation>,
+    /// Score after retrieval and fusion, before reranking
+    pub retrieval_score: f32,
+    /// Cross-encoder score, when the result was reranked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
+    /// Top-level filter conditions the post satisfied
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters_passed: Vec<String>,
+}
+
+/// Reciprocal rank fusion terms of a hybrid result
+///
+/// The contributions add up to the fused score, capped at 1.0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FusionExplanation {
+    /// Weight of the vector ranking
+    pub vector_weight: f32,
+    /// 1-based rank in the vector ranking
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector_rank: Option<u32>,
+    /// 1-based rank in the full-text ranking
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lexical_rank: Option<u32>,
+    /// Score contributed by the vector rank
+    pub vector_contribution: f32,
+    /// Score contributed by the full-text rank
+    pub lexical_contribution: f32,
+}
+
+/// Maximum snippet length in characters (GDPR)
+pub const MAX_SNIPPET_CHARS: usize = 300;
+
+/// Range of a matched query term within a snippet (end exclusive)
+///
+/// Offsets count UTF-16 code units, the unit JavaScript strings are indexed
+/// by, so a UI can pass them to `String.prototype.slice` unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightSpan {
+    /// UTF-16 offset of the first code unit of the term
+    pub start: u32,
+    /// UTF-16 offset just past the last code unit of the term
+    pub end: u32,
 }
 
 /// Post metadata structure
@@ -54,6 +949,361 @@ pub struct PostMetadata {
     pub language: String,
     /// Whether the post is frozen
     pub frozen: bool,
+    /// Custom metadata such as tags, categories or product IDs
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Post ingestion request; the embedding is computed by the service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertPostRequest {
+    /// External post identifier (existing posts are updated)
+    pub post_id: String,
+    /// Post title
+    pub title: String,
+    /// Full post content
+    pub content: String,
+    /// Post metadata
+    pub meta: PostMetadata,
+    /// Collection to store the post in (defaults to the default collection)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+}
+
+/// Batch post ingestion request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchUpsertPostsRequest {
+    /// Posts to insert or update
+    pub posts: Vec<UpsertPostRequest>,
+    /// Collection to store the posts in (defaults to the default collection)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+}
+
+impl BatchUpsertPostsRequest {
+    /// Check that no post names a collection other than the batch's
+    pub fn validate_collection(&self) -> Result<(), String> {
+        let batch_collection = self.collection.as_deref().unwrap_or(DEFAULT_COLLECTION);
+        match self
+            .posts
+            .iter()
+            .find(|post| post.collection.as_deref().is_some_and(|collection| collection != batch_collection))
+        {
+            Some(post) => Err(format!(
+                "Post {} names collection '{}' but the batch targets '{}'",
+                post.post_id,
+                post.collection.as_deref().unwrap_or_default(),
+                batch_collection
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Result of ingesting a single post
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertPostResponse {
+    /// External post identifier
+    pub post_id: String,
+    /// Embedding model used for the stored vector
+    pub embedding_model: String,
+    /// Whether the Redis vector and metadata entries were written
+    pub cached: bool,
+}
+
+/// Result of a batch ingestion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchUpsertPostsResponse {
+    /// Per-post results in request order
+    pub posts: Vec<UpsertPostResponse>,
+}
+
+/// Outcome of a GDPR erasure request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionStatus {
+    /// The post was erased from every store and the audit record was written
+    Completed,
+    /// At least one step still failed after retries; the request can be repeated
+    Partial,
+}
+
+impl DeletionStatus {
+    /// Stable string form used in the audit log
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeletionStatus::Completed => "completed",
+            DeletionStatus::Partial => "partial",
+        }
+    }
+}
+
+/// Receipt returned for a GDPR erasure request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletionReceipt {
+    /// Receipt identifier, also the audit record key
+    pub receipt_id: Uuid,
+    /// External post identifier
+    pub post_id: String,
+    /// Overall outcome
+    pub status: DeletionStatus,
+    /// Whether a Postgres row existed and was deleted
+    pub postgres_deleted: bool,
+    /// Number of Redis vector/metadata keys removed
+    pub cache_keys_deleted: u64,
+    /// Number of top-k cache entries purged because they contained the post
+    pub topk_entries_purged: u64,
+    /// Total attempts across all steps, including retries
+    pub attempts: u32,
+    /// Errors from steps that still failed after retries
+    pub errors: Vec<String>,
+    /// When the erasure was requested
+    pub requested_at: DateTime<Utc>,
+    /// When the erasure finished
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Default number of search results used as answer context
+pub const DEFAULT_ANSWER_SOURCES: u32 = 5;
+
+/// Maximum number of search results used as answer context
+pub const MAX_ANSWER_SOURCES: u32 = 20;
+
+/// Maximum context budget a request may ask for, in tokens
+pub const MAX_CONTEXT_TOKENS: u32 = 8192;
+
+/// Maximum answer length a request may ask for, in tokens
+pub const MAX_ANSWER_TOKENS: u32 = 4096;
+
+fn default_answer_sources() -> u32 {
+    DEFAULT_ANSWER_SOURCES
+}
+
+/// Question answering request; the answer is generated from search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerRequest {
+    /// Natural language question, also used as the search query
+    pub query: String,
+    /// Number of search results to consider as context (default 5, max 20)
+    #[serde(default = "default_answer_sources")]
+    pub k: u32,
+    /// Minimum similarity score of context results
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    /// Enable cross-encoder reranking of context results
+    #[serde(default)]
+    pub rerank: bool,
+    /// Optional filters for context results
+    #[serde(default)]
+    pub filters: Option<SearchFilters>,
+    /// Retrieval mode (vector, lexical or hybrid)
+    #[serde(default)]
+    pub mode: RetrievalMode,
+    /// Context budget in tokens (defaults to the configured budget), of which
+    /// passages fill 80% to allow for the generator's own tokenizer
+    #[serde(default)]
+    pub max_context_tokens: Option<u32>,
+    /// Maximum answer length in tokens (defaults to the configured limit)
+    #[serde(default)]
+    pub max_answer_tokens: Option<u32>,
+    /// Stream the answer as server-sent events
+    #[serde(default)]
+    pub stream: bool,
+    /// Collection to search for context (defaults to the default collection)
+    #[serde(default)]
+    pub collection: Option<String>,
+}
+
+impl AnswerRequest {
+    /// Search request retrieving the context results
+    pub fn to_search_request(&self) -> SearchRequest {
+        SearchRequest {
+            query: self.query.clone(),
+            k: self.k,
+            min_score: self.min_score,
+            rerank: self.rerank,
+            filters: self.filters.clone(),
+            mode: self.mode,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: None,
+            cursor: None,
+            explain: false,
+            collection: self.collection.clone(),
+        }
+    }
+
+    /// Check the answer-specific limits; search parameters are validated on
+    /// the search request
+    pub fn validate(&self) -> Result<(), String> {
+        if self.k > MAX_ANSWER_SOURCES {
+            return Err(format!("Parameter 'k' must not exceed {} for answers", MAX_ANSWER_SOURCES));
+        }
+
+        if self.max_context_tokens.is_some_and(|tokens| tokens == 0 || tokens > MAX_CONTEXT_TOKENS) {
+            return Err(format!("Parameter 'max_context_tokens' must be between 1 and {}", MAX_CONTEXT_TOKENS));
+        }
+
+        if self.max_answer_tokens.is_some_and(|tokens| tokens == 0 || tokens > MAX_ANSWER_TOKENS) {
+            return Err(format!("Parameter 'max_answer_tokens' must be between 1 and {}", MAX_ANSWER_TOKENS));
+        }
+
+        Ok(())
+    }
+}
+
+/// Search result an answer was generated from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// Number the answer cites the result by, as in `[1]`
+    pub index: u32,
+    /// External post identifier
+    pub post_id: String,
+    /// Post title
+    pub title: String,
+    /// Post URL
+    pub url: String,
+    /// Search score of the result
+    pub score: f32,
+}
+
+/// Generated answer with the search results it was based on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerResponse {
+    /// Answer text citing results as `[n]`
+    pub answer: String,
+    /// Results given to the generator, numbered as cited in the answer
+    pub citations: Vec<Citation>,
+    /// Generator model that wrote the answer
+    pub model: String,
+}
+
+/// Default number of search results considered for a context block
+pub const DEFAULT_CONTEXT_SOURCES: u32 = 10;
+
+fn default_context_sources() -> u32 {
+    DEFAULT_CONTEXT_SOURCES
+}
+
+/// Request for a token-budgeted context block built from search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextRequest {
+    /// Natural language query
+    pub query: String,
+    /// Token budget of the context block
+    pub max_tokens: u32,
+    /// Number of search results to consider (default 10, max 50)
+    #[serde(default = "default_context_sources")]
+    pub k: u32,
+    /// Minimum similarity score of included results
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    /// Enable cross-encoder reranking before packing
+    #[serde(default)]
+    pub rerank: bool,
+    /// Optional filters for search results
+    #[serde(default)]
+    pub filters: Option<SearchFilters>,
+    /// Retrieval mode (vector, lexical or hybrid)
+    #[serde(default)]
+    pub mode: RetrievalMode,
+    /// Collection to search (defaults to the default collection)
+    #[serde(default)]
+    pub collection: Option<String>,
+}
+
+impl ContextRequest {
+    /// Search request retrieving the results to pack
+    pub fn to_search_request(&self) -> SearchRequest {
+        SearchRequest {
+            query: self.query.clone(),
+            k: self.k,
+            min_score: self.min_score,
+            rerank: self.rerank,
+            filters: self.filters.clone(),
+            mode: self.mode,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: None,
+            cursor: None,
+            explain: false,
+            collection: self.collection.clone(),
+        }
+    }
+
+    /// Check the token budget; search parameters are validated on the search request
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_tokens == 0 || self.max_tokens > MAX_CONTEXT_TOKENS {
+            return Err(format!("Parameter 'max_tokens' must be between 1 and {}", MAX_CONTEXT_TOKENS));
+        }
+        Ok(())
+    }
+}
+
+/// Passage of a context block and where it sits in the block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextSource {
+    /// 1-based passage number, as rendered in `[n]`
+    pub index: u32,
+    /// External post identifier
+    pub post_id: String,
+    /// Post title
+    pub title: String,
+    /// Post URL
+    pub url: String,
+    /// Search score of the result
+    pub score: f32,
+    /// Character offset of the rendered passage, `[n]` header included, in
+    /// the context block (not in the source post)
+    pub start: u32,
+    /// Character offset just past the rendered passage in the context block
+    pub end: u32,
+    /// Tokens of the passage
+    pub tokens: u32,
+    /// Whether the passage was cut short to fit the budget
+    pub truncated: bool,
+}
+
+/// Context block ready to place in a prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextResponse {
+    /// Numbered passages separated by blank lines
+    pub context: String,
+    /// Total tokens of the passages
+    pub tokens: u32,
+    /// Passages in block order
+    pub passages: Vec<ContextSource>,
+}
+
+/// Query parameters naming a collection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CollectionParams {
+    /// Collection name (defaults to the default collection)
+    #[serde(default)]
+    pub collection: Option<String>,
+}
+
+/// Size and configuration of one collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionStats {
+    /// Collection name
+    pub name: String,
+    /// Embedding model of the collection
+    pub embedding_model: String,
+    /// Dimension of the collection's vectors
+    pub embedding_dimension: usize,
+    /// Posts stored in Postgres
+    pub total_posts: u64,
+    /// Stored posts that have an embedding
+    pub posts_with_embeddings: u64,
+    /// Stored posts that are frozen
+    pub frozen_posts: u64,
+    /// Passages stored in Postgres
+    pub total_passages: u64,
+    /// Posts in the Redis vector index
+    pub indexed_posts: u64,
+    /// Passages in the Redis passage index
+    pub indexed_passages: u64,
 }
 
 /// Internal post representation
@@ -77,6 +1327,8 @@ pub struct Post {
     pub date_gmt: DateTime<Utc>,
     /// Post URL
     pub url: String,
+    /// Custom JSON metadata
+    pub metadata: serde_json::Map<String, serde_json::Value>,
     /// Vector embedding (384 dimensions)
     pub embedding: Vec<f32>,
 }
@@ -90,13 +1342,64 @@ pub struct SearchCandidate {
     pub score: f32,
     /// Source of the candidate (Redis or Postgres)
     pub source: SearchSource,
+    /// Best matching passage when the candidate came from passage search
+    pub passage: Option<PassageSpan>,
+    /// Other sources that returned the same post, recorded when duplicates are merged
+    pub merged_from: Vec<SearchSource>,
+}
+
+/// Maximum number of passages indexed per post; content past the last one is not searchable by passage
+pub const MAX_CHUNKS_PER_POST: usize = 128;
+
+/// Byte range of a passage within a post's content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassageSpan {
+    /// Start offset (inclusive)
+    pub start: u32,
+    /// End offset (exclusive)
+    pub end: u32,
+}
+
+/// Passage of a post with its own embedding, stored in `post_chunks`
+#[derive(Debug, Clone)]
+pub struct PostChunk {
+    /// Post the passage belongs to
+    pub post_id: String,
+    /// Position of the passage within the post
+    pub chunk_index: u32,
+    /// Location of the passage in the post content
+    pub span: PassageSpan,
+    /// Passage text
+    pub content: String,
+    /// Vector embedding of the passage text
+    pub embedding: Vec<f32>,
+}
+
+/// Passage-level vector search hit
+#[derive(Debug, Clone)]
+pub struct ChunkCandidate {
+    /// Post the passage belongs to
+    pub post_id: String,
+    /// Position of the passage within the post
+    pub chunk_index: u32,
+    /// Location of the passage in the post content
+    pub span: PassageSpan,
+    /// Similarity score
+    pub score: f32,
+    /// Source of the hit (Redis or Postgres)
+    pub source: SearchSource,
 }
 
 /// Source of search results
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SearchSource {
     Redis,
     Postgres,
+    /// Postgres full-text search
+    Lexical,
+    /// Fused vector and full-text rankings
+    Hybrid,
 }
 
 /// Cached search result
@@ -108,6 +1411,9 @@ pub struct CachedResult {
     pub title: String,
     /// Post snippet
     pub snippet: String,
+    /// Highlighted query terms in the snippet
+    #[serde(default)]
+    pub highlights: Vec<HighlightSpan>,
     /// Similarity score
     pub score: f32,
     /// Metadata
@@ -117,7 +1423,8 @@ pub struct CachedResult {
 }
 
 /// Search operation mode for graceful degradation
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SearchMode {
     /// Full functionality: Redis + Postgres + Rerank
     Full,
@@ -129,6 +1436,26 @@ pub enum SearchMode {
     Degraded,
 }
 
+/// Outcome of a search, sent as the final event of a streamed search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchSummary {
+    /// Degradation mode the search ran in
+    pub search_mode: SearchMode,
+    /// Whether the final results were reordered by the cross-encoder
+    pub reranked: bool,
+    /// Whether the results came from the top-k cache
+    pub cached: bool,
+    /// Number of final results
+    pub result_count: usize,
+    /// Milliseconds until the results before reranking were ready
+    pub retrieval_ms: u64,
+    /// Milliseconds spent reranking, when reranking ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_ms: Option<u64>,
+    /// Milliseconds for the whole search
+    pub total_ms: u64,
+}
+
 
 
 impl Post {
@@ -140,6 +1467,7 @@ impl Post {
             post_id: self.post_id.clone(),
             title: self.title.clone(),
             snippet,
+            highlights: Vec::new(),
             score,
             meta: PostMetadata {
                 author_name: self.author_name.clone(),
@@ -147,21 +1475,28 @@ impl Post {
                 date: self.date_gmt,
                 language: self.language.clone(),
                 frozen: self.frozen,
+                metadata: self.metadata.clone(),
             },
+            next_cursor: None,
+            explanation: None,
         }
     }
 
+    /// Text of the passage at `span`, if it lies within the content
+    pub fn passage_text(&self, span: &PassageSpan) -> Option<&str> {
+        self.content
+            .get(span.start as usize..span.end as usize)
+            .map(str::trim)
+    }
+
     /// Truncate content to 300 characters for GDPR compliance
     /// Ensures we don't break in the middle of a word and adds ellipsis if truncated
     pub fn truncate_snippet_for_gdpr(content: &str) -> String {
-        const MAX_SNIPPET_LENGTH: usize = 300;
-        
-        if content.len() <= MAX_SNIPPET_LENGTH {
-            return content.to_string();
-        }
-        
-        // Reserve 3 characters for "..."
-        let max_content_length = MAX_SNIPPET_LENGTH - 3;
+        // Byte offset of the character after the first 297, keeping 3 for "..."
+        let max_content_length = match content.char_indices().nth(MAX_SNIPPET_CHARS - 3) {
+            Some((offset, _)) if content[offset..].chars().count() > 3 => offset,
+            _ => return content.to_string(),
+        };
         
         // Find the last word boundary before the limit
         let truncate_at = if let Some(last_space_pos) = content[..max_content_length].rfind(char::is_whitespace) {
@@ -190,18 +1525,22 @@ impl SearchResponse {
             post_id,
             title,
             snippet,
+            highlights: Vec::new(),
             score,
             meta,
+            next_cursor: None,
+            explanation: None,
         }
     }
 
     /// Validate that the response complies with GDPR requirements
     pub fn validate_gdpr_compliance(&self) -> Result<(), String> {
         // Check snippet length
-        if self.snippet.len() > 300 {
+        let snippet_chars = self.snippet.chars().count();
+        if snippet_chars > MAX_SNIPPET_CHARS {
             return Err(format!(
-                "Snippet exceeds GDPR limit: {} characters (max 300)",
-                self.snippet.len()
+                "Snippet exceeds GDPR limit: {} characters (max {})",
+                snippet_chars, MAX_SNIPPET_CHARS
             ));
         }
         
@@ -214,6 +1553,74 @@ impl SearchResponse {
     }
 }
 
+impl CachedResult {
+    /// Snapshot a search response for the top-k cache
+    pub fn from_response(response: &SearchResponse) -> Self {
+        Self {
+            post_id: response.post_id.clone(),
+            title: response.title.clone(),
+            snippet: response.snippet.clone(),
+            highlights: response.highlights.clone(),
+            score: response.score,
+            meta: response.meta.clone(),
+            cached_at: Utc::now(),
+        }
+    }
+}
+
+impl From<CachedResult> for SearchResponse {
+    fn from(cached: CachedResult) -> Self {
+        Self {
+            post_id: cached.post_id,
+            title: cached.title,
+            snippet: cached.snippet,
+            highlights: cached.highlights,
+            score: cached.score,
+            meta: cached.meta,
+            next_cursor: None,
+            explanation: None,
+        }
+    }
+}
+
+/// Builders for the search types shared by unit tests across the crate
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+    use super::*;
+
+    /// Result for `post_id` with the given score and an empty snippet
+    pub fn search_response(post_id: &str, score: f32) -> SearchResponse {
+        SearchResponse {
+            post_id: post_id.to_string(),
+            title: format!("Title {}", post_id),
+            snippet: String::new(),
+            highlights: Vec::new(),
+            score,
+            meta: PostMetadata {
+                author_name: "Author".to_string(),
+                url: format!("https://example.com/{}", post_id),
+                date: chrono::Utc::now(),
+                language: "en".to_string(),
+                frozen: false,
+                metadata: serde_json::Map::new(),
+            },
+            next_cursor: None,
+            explanation: None,
+        }
+    }
+
+    /// Candidate for `post_id` from a single source, without a passage
+    pub fn search_candidate(post_id: &str, score: f32, source: SearchSource) -> SearchCandidate {
+        SearchCandidate {
+            post_id: post_id.to_string(),
+            score,
+            source,
+            passage: None,
+            merged_from: Vec::new(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,6 +1633,7 @@ mod tests {
             date: Utc::now(),
             language: "en".to_string(),
             frozen: false,
+            metadata: serde_json::Map::new(),
         }
     }
 
@@ -286,6 +1694,19 @@ mod tests {
         assert!(snippet.ends_with("..."));
     }
 
+    #[test]
+    fn test_snippet_truncation_multibyte() {
+        // 300 characters but 600 bytes: fits as is
+        let content = "é".repeat(300);
+        assert_eq!(Post::truncate_snippet_for_gdpr(&content), content);
+
+        // Cutting must land on a character boundary
+        let content = "日本語のテキスト ".repeat(60);
+        let snippet = Post::truncate_snippet_for_gdpr(&content);
+        assert!(snippet.chars().count() <= MAX_SNIPPET_CHARS);
+        assert!(snippet.ends_with("..."));
+    }
+
     #[test]
     fn test_search_response_new_with_truncation() {
         let long_content = "This is a very long post content. ".repeat(20);
@@ -309,8 +1730,11 @@ mod tests {
             post_id: "test_post".to_string(),
             title: "Test Title".to_string(),
             snippet: "This is a valid snippet under 300 characters.".to_string(),
+            highlights: Vec::new(),
             score: 0.85,
             meta: create_test_metadata(),
+            next_cursor: None,
+            explanation: None,
         };
         
         assert!(response.validate_gdpr_compliance().is_ok());
@@ -322,8 +1746,11 @@ mod tests {
             post_id: "test_post".to_string(),
             title: "Test Title".to_string(),
             snippet: "a".repeat(301), // Exceeds 300 character limit
+            highlights: Vec::new(),
             score: 0.85,
             meta: create_test_metadata(),
+            next_cursor: None,
+            explanation: None,
         };
         
         let result = response.validate_gdpr_compliance();
@@ -337,8 +1764,11 @@ mod tests {
             post_id: "test_post".to_string(),
             title: "Test Title".to_string(),
             snippet: "This snippet contains a null byte\0".to_string(),
+            highlights: Vec::new(),
             score: 0.85,
             meta: create_test_metadata(),
+            next_cursor: None,
+            explanation: None,
         };
         
         let result = response.validate_gdpr_compliance();
@@ -359,6 +1789,7 @@ mod tests {
             date_gmt: Utc::now(),
             url: "https://example.com/test".to_string(),
             embedding: vec![0.1; 384],
+            metadata: serde_json::Map::new(),
         };
         
         let response = post.to_search_response(0.92);
@@ -386,6 +1817,7 @@ mod tests {
             date_gmt: Utc::now(),
             url: "https://example.com/test".to_string(),
             embedding: vec![0.1; 384],
+            metadata: serde_json::Map::new(),
         };
         
         let response = post.to_search_response(0.92);
@@ -400,6 +1832,7 @@ mod tests {
         let filters = SearchFilters {
             language: Some("en".to_string()),
             frozen: Some(false),
+            ..Default::default()
         };
         
         // Test that filters can be serialized/deserialized
@@ -410,6 +1843,138 @@ mod tests {
         assert_eq!(deserialized.frozen, Some(false));
     }
 
+    #[test]
+    fn test_search_filters_matches() {
+        let meta = PostMetadata {
+            author_name: "Ada".to_string(),
+            url: "https://blog.example.com/posts/1".to_string(),
+            date: "2024-06-01T12:00:00Z".parse().unwrap(),
+            language: "en".to_string(),
+            frozen: false,
+            metadata: serde_json::Map::new(),
+        };
+
+        let filters: SearchFilters = serde_json::from_str(
+            r#"{
+                "date_after": "2024-01-01T00:00:00Z",
+                "url_domains": ["example.com"],
+                "or": [{"authors": ["Ada"]}, {"language": "es"}],
+                "not": {"url_prefix": "https://blog.example.com/drafts/"}
+            }"#,
+        )
+        .unwrap();
+        assert!(filters.matches(&meta));
+
+        let filters = SearchFilters {
+            date_before: Some(meta.date),
+            ..Default::default()
+        };
+        assert!(!filters.matches(&meta));
+
+        let filters = SearchFilters {
+            url_domains: Some(vec!["ample.com".to_string()]),
+            ..Default::default()
+        };
+        assert!(!filters.matches(&meta));
+
+        let filters = SearchFilters {
+            exclude_authors: Some(vec!["Ada".to_string()]),
+            ..Default::default()
+        };
+        assert!(!filters.matches(&meta));
+    }
+
+    #[test]
+    fn test_metadata_filter_matches() {
+        let metadata: serde_json::Map<String, serde_json::Value> = serde_json::from_str(
+            r#"{"tags": ["rust", "async"], "category": "guides", "price": 25, "sku": "25"}"#,
+        )
+        .unwrap();
+
+        let filter: MetadataFilter = serde_json::from_str(r#"{"key": "tags", "contains": "rust"}"#).unwrap();
+        assert!(filter.matches(&metadata));
+
+        let filter: MetadataFilter = serde_json::from_str(r#"{"key": "category", "in": ["news", "guides"]}"#).unwrap();
+        assert!(filter.matches(&metadata));
+
+        let filter: MetadataFilter = serde_json::from_str(r#"{"key": "price", "eq": 25.0, "gte": 10, "lt": 100}"#).unwrap();
+        assert!(filter.matches(&metadata));
+
+        // Range operators never match non-numeric values
+        let filter: MetadataFilter = serde_json::from_str(r#"{"key": "sku", "gte": 10}"#).unwrap();
+        assert!(!filter.matches(&metadata));
+
+        let filter: MetadataFilter = serde_json::from_str(r#"{"key": "missing", "eq": "x"}"#).unwrap();
+        assert!(!filter.matches(&metadata));
+
+        // Dotted keys walk nested objects
+        let nested: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(r#"{"product": {"category": "books", "price": 12}, "tags": ["rust"]}"#).unwrap();
+        let filter: MetadataFilter = serde_json::from_str(r#"{"key": "product.category", "eq": "books"}"#).unwrap();
+        assert!(filter.validate().is_ok());
+        assert!(filter.matches(&nested));
+        let filter: MetadataFilter = serde_json::from_str(r#"{"key": "product.price", "lt": 20}"#).unwrap();
+        assert!(filter.matches(&nested));
+        let filter: MetadataFilter = serde_json::from_str(r#"{"key": "tags.0", "eq": "rust"}"#).unwrap();
+        assert!(!filter.matches(&nested));
+
+        let invalid = [
+            r#"{"key": "tags"}"#,
+            r#"{"key": "bad key", "eq": 1}"#,
+            r#"{"key": "product..category", "eq": 1}"#,
+            r#"{"key": ".category", "eq": 1}"#,
+            r#"{"key": "tags", "contains": ["rust"]}"#,
+            r#"{"key": "category", "in": []}"#,
+        ];
+        for json in invalid {
+            let filter: MetadataFilter = serde_json::from_str(json).unwrap();
+            assert!(filter.validate().is_err(), "expected {} to be rejected", json);
+        }
+    }
+
+    #[test]
+    fn test_search_filters_validation() {
+        let nested = |depth: usize| {
+            let mut filters = SearchFilters {
+                language: Some("en".to_string()),
+                ..Default::default()
+            };
+            for _ in 0..depth {
+                filters = SearchFilters {
+                    not: Some(Box::new(filters)),
+                    ..Default::default()
+                };
+            }
+            filters
+        };
+        assert!(nested(MAX_FILTER_DEPTH).validate().is_ok());
+        assert!(nested(MAX_FILTER_DEPTH + 1).validate().is_err());
+
+        let invalid = [
+            r#"{"authors": []}"#,
+            r#"{"url_domains": ["Example.com"]}"#,
+            r#"{"url_prefix": "ftp://example.com/"}"#,
+            r#"{"date_after": "2025-01-01T00:00:00Z", "date_before": "2024-01-01T00:00:00Z"}"#,
+            r#"{"or": []}"#,
+            r#"{"and": [{}]}"#,
+        ];
+        for json in invalid {
+            let filters: SearchFilters = serde_json::from_str(json).unwrap();
+            assert!(filters.validate().is_err(), "expected {} to be rejected", json);
+        }
+
+        assert!(SearchFilters::default().validate().is_ok());
+        assert!(!SearchFilters::default().mentions_frozen());
+        assert!(SearchFilters {
+            or: Some(vec![SearchFilters {
+                frozen: Some(true),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }
+        .mentions_frozen());
+    }
+
     #[test]
     fn test_search_request_with_filters() {
         let request = SearchRequest {
@@ -420,7 +1985,15 @@ mod tests {
             filters: Some(SearchFilters {
                 language: Some("en".to_string()),
                 frozen: Some(false),
+                ..Default::default()
             }),
+            mode: RetrievalMode::Vector,
+            fusion_weight: None,
+            mmr_lambda: None,
+            passage_aggregation: None,
+            cursor: None,
+            explain: false,
+            collection: None,
         };
         
         // Test serialization
@@ -438,14 +2011,255 @@ mod tests {
         assert_eq!(filters.frozen, Some(false));
     }
 
+    #[test]
+    fn test_search_request_mode_defaults_to_vector() {
+        let request: SearchRequest = serde_json::from_str(r#"{"query":"rust","k":5,"rerank":false}"#).unwrap();
+        assert_eq!(request.mode, RetrievalMode::Vector);
+        assert_eq!(request.fusion_weight, None);
+        assert_eq!(request.mmr_lambda, None);
+
+        let request: SearchRequest =
+            serde_json::from_str(r#"{"query":"rust","k":5,"rerank":false,"mode":"hybrid","fusion_weight":0.7}"#).unwrap();
+        assert_eq!(request.mode, RetrievalMode::Hybrid);
+        assert_eq!(request.fusion_weight, Some(0.7));
+        assert_eq!(request.passage_aggregation, None);
+    }
+
+    #[test]
+    fn test_passage_aggregation_serialization() {
+        let request: SearchRequest =
+            serde_json::from_str(r#"{"query":"rust","k":5,"rerank":false,"passage_aggregation":"max"}"#).unwrap();
+        assert_eq!(request.passage_aggregation, Some(PassageAggregation::Max));
+
+        let request: SearchRequest = serde_json::from_str(
+            r#"{"query":"rust","k":5,"rerank":false,"passage_aggregation":{"mean_top_n":3}}"#,
+        )
+        .unwrap();
+        assert_eq!(request.passage_aggregation, Some(PassageAggregation::MeanTopN(3)));
+
+        assert!(PassageAggregation::Max.validate().is_ok());
+        assert!(PassageAggregation::MeanTopN(MAX_PASSAGE_TOP_N).validate().is_ok());
+        assert!(PassageAggregation::MeanTopN(0).validate().is_err());
+        assert!(PassageAggregation::MeanTopN(MAX_PASSAGE_TOP_N + 1).validate().is_err());
+    }
+
+    #[test]
+    fn test_passage_text() {
+        let post = Post {
+            id: Uuid::new_v4(),
+            post_id: "post_1".to_string(),
+            title: "Title".to_string(),
+            content: "Intro paragraph. The part about borrowing.".to_string(),
+            author_name: "Author".to_string(),
+            language: "en".to_string(),
+            frozen: false,
+            date_gmt: Utc::now(),
+            url: "https://example.com/post_1".to_string(),
+            metadata: serde_json::Map::new(),
+            embedding: Vec::new(),
+        };
+
+        let passage = post.passage_text(&PassageSpan { start: 16, end: 42 });
+        assert_eq!(passage, Some("The part about borrowing."));
+        assert_eq!(post.passage_text(&PassageSpan { start: 16, end: 400 }), None);
+    }
+
+    #[test]
+    fn test_answer_request_defaults_and_limits() {
+        let request: AnswerRequest = serde_json::from_str(r#"{"query": "what is ownership?"}"#).unwrap();
+        assert_eq!(request.k, DEFAULT_ANSWER_SOURCES);
+        assert!(!request.stream);
+        assert!(request.validate().is_ok());
+
+        let search_request = request.to_search_request();
+        assert_eq!(search_request.query, "what is ownership?");
+        assert_eq!(search_request.k, DEFAULT_ANSWER_SOURCES);
+        assert!(search_request.cursor.is_none());
+
+        let mut request = request;
+        request.k = MAX_ANSWER_SOURCES + 1;
+        assert!(request.validate().is_err());
+
+        request.k = 3;
+        request.max_context_tokens = Some(0);
+        assert!(request.validate().is_err());
+
+        request.max_context_tokens = Some(MAX_CONTEXT_TOKENS);
+        request.max_answer_tokens = Some(MAX_ANSWER_TOKENS + 1);
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_search_query_params_decode_filters() {
+        let params: SearchQueryParams = serde_json::from_str(
+            r#"{"query": "rust", "k": 5, "filters": "{\"language\": \"en\"}"}"#,
+        )
+        .unwrap();
+        let request = params.into_search_request().unwrap();
+        assert_eq!(request.k, 5);
+        assert!(!request.rerank);
+        assert_eq!(request.filters.unwrap().language.as_deref(), Some("en"));
+
+        let params: SearchQueryParams =
+            serde_json::from_str(r#"{"query": "rust", "k": 5, "filters": "not json"}"#).unwrap();
+        assert!(params.into_search_request().is_err());
+    }
+
+    #[test]
+    fn test_search_query_params_passage_aggregation() {
+        let decode = |json: &str| serde_json::from_str::<SearchQueryParams>(json).unwrap().into_search_request();
+
+        let request = decode(r#"{"query": "rust", "k": 5, "passage_aggregation": "mean_top_n", "passage_top_n": 5}"#).unwrap();
+        assert_eq!(request.passage_aggregation, Some(PassageAggregation::MeanTopN(5)));
+
+        let request = decode(r#"{"query": "rust", "k": 5, "passage_aggregation": "mean_top_n"}"#).unwrap();
+        assert_eq!(request.passage_aggregation, Some(PassageAggregation::MeanTopN(DEFAULT_PASSAGE_TOP_N)));
+
+        let request = decode(r#"{"query": "rust", "k": 5, "passage_aggregation": "max"}"#).unwrap();
+        assert_eq!(request.passage_aggregation, Some(PassageAggregation::Max));
+
+        assert!(decode(r#"{"query": "rust", "k": 5, "passage_aggregation": "mean"}"#).is_err());
+    }
+
+    #[test]
+    fn test_search_summary_serialization() {
+        let summary = SearchSummary {
+            search_mode: SearchMode::PostgresOnly,
+            reranked: false,
+            cached: false,
+            result_count: 3,
+            retrieval_ms: 12,
+            rerank_ms: None,
+            total_ms: 12,
+        };
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["search_mode"], "postgres_only");
+        assert!(json.get("rerank_ms").is_none());
+    }
+
+    #[test]
+    fn test_context_request_requires_budget() {
+        assert!(serde_json::from_str::<ContextRequest>(r#"{"query": "ownership"}"#).is_err());
+
+        let mut request: ContextRequest =
+            serde_json::from_str(r#"{"query": "ownership", "max_tokens": 500}"#).unwrap();
+        assert_eq!(request.k, DEFAULT_CONTEXT_SOURCES);
+        assert!(request.validate().is_ok());
+        assert_eq!(request.to_search_request().k, DEFAULT_CONTEXT_SOURCES);
+
+        request.max_tokens = 0;
+        assert!(request.validate().is_err());
+        request.max_tokens = MAX_CONTEXT_TOKENS + 1;
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_collection_names() {
+        assert!(is_valid_collection_name("docs"));
+        assert!(is_valid_collection_name("support_v2"));
+        assert!(!is_valid_collection_name(""));
+        assert!(!is_valid_collection_name("2docs"));
+        assert!(!is_valid_collection_name("Docs"));
+        assert!(!is_valid_collection_name("docs-v2"));
+        assert!(!is_valid_collection_name(&"a".repeat(MAX_COLLECTION_NAME_LENGTH + 1)));
+
+        let post = |collection: Option<&str>| {
+            serde_json::json!({
+                "post_id": "post_1",
+                "title": "Title",
+                "content": "Content",
+                "meta": {
+                    "author_name": "Author",
+                    "url": "https://example.com/post_1",
+                    "date": "2024-01-15T10:30:00Z",
+                    "language": "en",
+                    "frozen": false
+                },
+                "collection": collection
+            })
+        };
+
+        // Posts may repeat the batch's collection but not name another one
+        let batch: BatchUpsertPostsRequest = serde_json::from_value(serde_json::json!({
+            "collection": "docs",
+            "posts": [post(None), post(Some("docs"))]
+        }))
+        .unwrap();
+        assert!(batch.validate_collection().is_ok());
+
+        let batch: BatchUpsertPostsRequest =
+            serde_json::from_value(serde_json::json!({ "posts": [post(Some("docs"))] })).unwrap();
+        assert!(batch.validate_collection().unwrap_err().contains("'default'"));
+    }
+
+    #[test]
+    fn test_similar_posts_params() {
+        let params: SimilarPostsParams = serde_json::from_value(serde_json::json!({
+            "filters": r#"{"language": "en"}"#,
+            "exclude_near_duplicates": true
+        }))
+        .unwrap();
+        let request = params.into_similar_posts_request("post_1".to_string()).unwrap();
+        assert_eq!(request.k, DEFAULT_SIMILAR_POSTS);
+        assert!(request.exclude_near_duplicates);
+        assert_eq!(request.filters.as_ref().unwrap().language.as_deref(), Some("en"));
+
+        // Checked as a rerank-free vector search over the same filters
+        let search_request = request.to_search_request();
+        assert_eq!(search_request.query, SIMILAR_POSTS_QUERY);
+        assert!(!search_request.rerank);
+        assert_eq!(search_request.mode, RetrievalMode::Vector);
+
+        let params: SimilarPostsParams =
+            serde_json::from_value(serde_json::json!({ "filters": "not json" })).unwrap();
+        assert!(params.into_similar_posts_request("post_1".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_validate_post_id() {
+        // Slugs and paths are identifiers, not malicious queries
+        assert!(validate_post_id("2024/01/../$gt-proc").is_ok());
+        assert!(validate_post_id("").is_err());
+        assert!(validate_post_id("post 1").is_err());
+        assert!(validate_post_id(&"a".repeat(MAX_POST_ID_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_batch_search_validation() {
+        let query = |collection: Option<&str>| {
+            serde_json::json!({ "query": "rust", "k": 5, "rerank": false, "filters": null, "collection": collection })
+        };
+
+        let batch: BatchSearchRequest = serde_json::from_value(serde_json::json!({
+            "collection": "docs",
+            "queries": [query(None), query(Some("docs"))]
+        }))
+        .unwrap();
+        assert!(batch.validate().is_ok());
+
+        let batch: BatchSearchRequest =
+            serde_json::from_value(serde_json::json!({ "queries": [query(None), query(Some("docs"))] })).unwrap();
+        assert!(batch.validate().unwrap_err().starts_with("Query 1 "));
+
+        let batch: BatchSearchRequest = serde_json::from_value(serde_json::json!({ "queries": [] })).unwrap();
+        assert!(batch.validate().is_err());
+
+        let queries: Vec<_> = (0..=MAX_BATCH_QUERIES).map(|_| query(None)).collect();
+        let batch: BatchSearchRequest = serde_json::from_value(serde_json::json!({ "queries": queries })).unwrap();
+        assert!(batch.validate().is_err());
+    }
+
     #[test]
     fn test_search_response_serialization() {
         let response = SearchResponse {
             post_id: "test_post".to_string(),
             title: "Test Title".to_string(),
             snippet: "Test snippet content.".to_string(),
+            highlights: Vec::new(),
             score: 0.85,
             meta: create_test_metadata(),
+            next_cursor: None,
+            explanation: None,
         };
         
         // Test JSON serialization
@@ -458,4 +2272,24 @@ mod tests {
         assert_eq!(deserialized.score, 0.85);
         assert_eq!(deserialized.meta.author_name, "Test Author");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cached_result_roundtrip() {
+        let response = SearchResponse::new(
+            "post_1".to_string(),
+            "Title".to_string(),
+            "Cached content".to_string(),
+            0.42,
+            create_test_metadata(),
+        );
+
+        let cached = CachedResult::from_response(&response);
+        assert_eq!(cached.post_id, "post_1");
+        assert_eq!(cached.score, 0.42);
+
+        let restored = SearchResponse::from(cached);
+        assert_eq!(restored.post_id, response.post_id);
+        assert_eq!(restored.snippet, response.snippet);
+        assert_eq!(restored.meta.url, response.meta.url);
+    }
+}